@@ -1,4 +1,4 @@
 """配置模块"""
-from .settings import Config, default_config
+from .settings import Config, default_config, load_config
 
-__all__ = ["Config", "default_config"]
+__all__ = ["Config", "default_config", "load_config"]
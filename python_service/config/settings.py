@@ -3,8 +3,9 @@ PhantomHand 配置文件
 包含手势识别阈值、状态机参数、服务器配置等
 """
 
-from dataclasses import dataclass, field
-from typing import Dict
+import json
+from dataclasses import dataclass, field, fields, is_dataclass
+from typing import Any, Dict
 
 
 @dataclass
@@ -49,12 +50,14 @@ class StateMachineConfig:
     median_window: int = 5   # 中值滤波窗口大小
 
     # 手势优先级（数字越大优先级越高）
+    # open 和 fist 用于激活/停用控制，需要最高优先级
     gesture_priority: Dict[str, int] = field(default_factory=lambda: {
-        "pinch": 5,
-        "fist": 4,
+        "open": 6,
+        "fist": 5,
+        "thumbs_up": 4,
         "point": 3,
-        "open": 2,
-        "slide": 1,
+        "victory": 3,
+        "ok": 3,
         "idle": 0
     })
 
@@ -67,12 +70,13 @@ class ActionMapping:
     mouse_sensitivity: float = 1.5      # 鼠标灵敏度
     mouse_smoothing: float = 0.7        # 鼠标平滑系数
 
-    # 默认手势映射
+    # 默认手势映射（宿主连接前使用，之后以宿主推送的绑定为准）
+    # open 固定用于激活控制、fist 固定用于停用控制，不在此映射中
     mappings: Dict[str, str] = field(default_factory=lambda: {
-        "open": "activate",              # 张开手掌：激活控制
-        "fist": "media_pause",           # 握拳：暂停/静音
-        "pinch": "mouse_click",          # 捏合：鼠标点击
+        "thumbs_up": "mouse_click",      # 竖大拇指：鼠标点击
         "point": "mouse_move",           # 指向：鼠标移动
+        "victory": "screenshot",         # 剪刀手：截屏
+        "ok": "volume_mute",             # OK：静音切换
         "slide_left": "switch_window",   # 左滑：切换窗口
         "slide_right": "switch_window",  # 右滑：切换窗口
         "slide_up": "volume_up",         # 上滑：音量增加
@@ -86,6 +90,7 @@ class ServerConfig:
 
     host: str = "127.0.0.1"
     port: int = 8765
+    mjpeg_port: int = 8766           # MJPEG 视频流端口
 
     # 心跳配置
     heartbeat_interval: int = 5000   # 心跳间隔（毫秒）
@@ -121,3 +126,32 @@ class Config:
 
 # 创建默认配置实例
 default_config = Config()
+
+
+def load_config(path: str) -> Config:
+    """
+    读取宿主写入的配置文件（JSON，结构与 Config 一致，可只包含部分字段）
+
+    未知字段会被忽略并打印警告
+    """
+    with open(path, "r", encoding="utf-8") as f:
+        data = json.load(f)
+
+    config = Config()
+    _apply(config, data, "")
+    return config
+
+
+def _apply(target: Any, data: Dict[str, Any], prefix: str):
+    """把字典中的值写入 dataclass（递归处理嵌套配置）"""
+    known = {f.name for f in fields(target)}
+    for key, value in data.items():
+        path = f"{prefix}{key}"
+        if key not in known:
+            print(f"[WARN] 忽略未知配置项: {path}")
+            continue
+        current = getattr(target, key)
+        if is_dataclass(current) and isinstance(value, dict):
+            _apply(current, value, f"{path}.")
+        else:
+            setattr(target, key, value)
@@ -4,7 +4,7 @@
 """
 
 import time
-from typing import Callable, Dict, Optional, Tuple
+from typing import Callable, Dict, List, Optional, Tuple
 from dataclasses import dataclass
 from enum import Enum
 import threading
@@ -26,17 +26,9 @@ if platform.system() == "Windows":
 
     KEYEVENTF_KEYUP = 0x0002
     KEYEVENTF_EXTENDEDKEY = 0x0001
+    KEYEVENTF_UNICODE = 0x0004
 
-    # 虚拟键码
-    VK_VOLUME_UP = 0xAF
-    VK_VOLUME_DOWN = 0xAE
-    VK_VOLUME_MUTE = 0xAD
-    VK_MEDIA_PLAY_PAUSE = 0xB3
-    VK_MEDIA_NEXT_TRACK = 0xB0
-    VK_MEDIA_PREV_TRACK = 0xB1
-    VK_LWIN = 0x5B
-    VK_TAB = 0x09
-    VK_MENU = 0x12  # Alt key
+    WHEEL_DELTA = 120
 
     # INPUT 结构体定义
     class MOUSEINPUT(ctypes.Structure):
@@ -87,10 +79,109 @@ class ActionType(Enum):
     MEDIA_NEXT = "media_next"
     MEDIA_PREV = "media_prev"
     SWITCH_WINDOW = "switch_window"
+    SLIDE_NEXT = "slide_next"
+    SLIDE_PREV = "slide_prev"
     SCREENSHOT = "screenshot"
+    KEY_CHORD = "key_chord"
+    TEXT = "text"
     CUSTOM = "custom"
 
 
+# 持续动作：占用整个手势周期（进入、保持、退出），绑定时写在 hold 上
+POINTER_ACTIONS = (ActionType.MOUSE_MOVE, ActionType.MOUSE_CLICK, ActionType.MOUSE_DRAG)
+
+# 组合键的规范按键名 -> 虚拟键码
+# 须与 tauri_app/src-tauri/src/mapping/keys.rs 中的按键名保持一致
+KEY_CODES: Dict[str, int] = {
+    "Ctrl": 0x11, "Alt": 0x12, "Shift": 0x10, "Super": 0x5B,
+    **{chr(c): c for c in range(ord("A"), ord("Z") + 1)},
+    **{chr(c): c for c in range(ord("0"), ord("9") + 1)},
+    **{f"F{n}": 0x6F + n for n in range(1, 25)},
+    "Enter": 0x0D, "Tab": 0x09, "Space": 0x20, "Escape": 0x1B,
+    "Backspace": 0x08, "Delete": 0x2E, "Insert": 0x2D,
+    "Home": 0x24, "End": 0x23, "PageUp": 0x21, "PageDown": 0x22,
+    "Left": 0x25, "Up": 0x26, "Right": 0x27, "Down": 0x28,
+    "PrintScreen": 0x2C,
+    "Minus": 0xBD, "Equal": 0xBB, "Comma": 0xBC, "Period": 0xBE,
+    "Slash": 0xBF, "Semicolon": 0xBA, "Quote": 0xDE,
+    "BracketLeft": 0xDB, "BracketRight": 0xDD, "Backslash": 0xDC, "Backquote": 0xC0,
+}
+
+# 媒体键（只在执行器内部使用，不能写在组合键中）
+MEDIA_KEY_CODES: Dict[str, int] = {
+    "VolumeUp": 0xAF, "VolumeDown": 0xAE, "VolumeMute": 0xAD,
+    "MediaPlayPause": 0xB3, "MediaNext": 0xB0, "MediaPrev": 0xB1,
+}
+
+EVENT_TYPES = ("enter", "hold", "exit", "slide")
+
+
+@dataclass
+class ActionBinding:
+    """一条动作绑定（对应协议中的 ActionBinding）"""
+    gesture: str                 # 手势名；滑动时为方向
+    on: str                      # enter / hold / exit / slide
+    action: ActionType
+    interval_ms: Optional[int] = None  # hold 上一次性动作的重复间隔
+    amount: int = 0              # mouse_scroll 格数，正数向上
+    keys: str = ""               # key_chord 的规范形式（修饰键在前）
+    text: str = ""               # text 动作的内容
+
+    @classmethod
+    def from_dict(cls, data: Dict) -> "ActionBinding":
+        """解析协议中的绑定，无效时抛出 ValueError"""
+        gesture = str(data["gesture"])
+        on = data["on"]
+        if on not in EVENT_TYPES:
+            raise ValueError(f"未知的触发时机 {on}")
+        # fist 可以由手势配置绑定（此时不再停用控制），open 始终用于激活
+        if on != "slide" and gesture == "open":
+            raise ValueError("open 固定用于激活控制")
+
+        params = dict(data["action"])
+        action = ActionType(params.pop("type"))
+        binding = cls(gesture=gesture, on=on, action=action)
+
+        if action in POINTER_ACTIONS:
+            if on != "hold":
+                raise ValueError(f"{action.value} 只能绑定在 hold 上")
+        elif on == "hold":
+            interval = int(data.get("interval_ms") or 0)
+            if interval <= 0:
+                raise ValueError("hold 上的一次性动作需要重复间隔")
+            binding.interval_ms = interval
+
+        if action == ActionType.MOUSE_SCROLL:
+            binding.amount = int(params["amount"])
+        elif action == ActionType.KEY_CHORD:
+            names = str(params["keys"]).split("+")
+            unknown = [name for name in names if name not in KEY_CODES]
+            if unknown:
+                raise ValueError(f"未知的按键 {'+'.join(unknown)}")
+            binding.keys = "+".join(names)
+        elif action == ActionType.TEXT:
+            binding.text = str(params["text"])
+        return binding
+
+
+def legacy_bindings(mappings: Dict[str, str]) -> List[Dict]:
+    """
+    把旧版配置中的 手势/slide_方向 -> 动作类型 映射转换为协议中的绑定列表
+    （鼠标动作绑定在 hold 上，其余在 enter 上；open 与 fist 固定，忽略）
+    """
+    pointer = {action.value for action in POINTER_ACTIONS}
+    bindings = []
+    for key, value in mappings.items():
+        if key.startswith("slide_"):
+            gesture, on = key[len("slide_"):], "slide"
+        elif key in ("open", "fist"):
+            continue
+        else:
+            gesture, on = key, "hold" if value in pointer else "enter"
+        bindings.append({"gesture": gesture, "on": on, "action": {"type": value}})
+    return bindings
+
+
 @dataclass
 class ActionConfig:
     """动作配置"""
@@ -105,6 +196,10 @@ class ActionConfig:
     scroll_speed: int = 3              # 滚动速度
     screen_width: int = 1920           # 屏幕宽度
     screen_height: int = 1080          # 屏幕高度
+    # 绝对定位映射的显示器区域 (x, y, 宽, 高)，由宿主设置；为空时映射到主屏
+    display_area: Optional[Tuple[int, int, int, int]] = None
+    # 指尖位置的校准单应矩阵（按行排列的 3x3），由宿主设置；为空时整个画面映射到区域
+    calibration: Optional[List[float]] = None
 
 
 class ActionExecutor:
@@ -118,6 +213,8 @@ class ActionExecutor:
 
         # 获取屏幕尺寸
         if platform.system() == "Windows":
+            # 宿主报告的显示器区域为物理像素，需要关闭 DPI 虚拟化后坐标才一致
+            user32.SetProcessDPIAware()
             self.config.screen_width = user32.GetSystemMetrics(0)
             self.config.screen_height = user32.GetSystemMetrics(1)
 
@@ -131,21 +228,20 @@ class ActionExecutor:
         self._rel_smoothed_delta: Tuple[float, float] = (0.0, 0.0)
         self._rel_is_lifted = True  # 初始为抬起状态
 
-        # 动作映射（open 和 fist 单独处理，不在此映射中）
-        self._gesture_action_map: Dict[str, ActionType] = {
-            "thumbs_up": ActionType.MOUSE_CLICK,  # 竖大拇指用于点击
-            "point": ActionType.MOUSE_MOVE,
-            "victory": ActionType.SCREENSHOT,
-            "ok": ActionType.VOLUME_MUTE,
-        }
-
-        # 滑动方向映射
-        self._slide_action_map: Dict[str, ActionType] = {
-            "left": ActionType.SWITCH_WINDOW,
-            "right": ActionType.SWITCH_WINDOW,
-            "up": ActionType.VOLUME_UP,
-            "down": ActionType.VOLUME_DOWN,
-        }
+        # 动作绑定（open 单独处理；默认不绑定 fist，由 fist 停用控制），默认与宿主的默认配置一致
+        self._bindings: List[ActionBinding] = [
+            ActionBinding("thumbs_up", "hold", ActionType.MOUSE_CLICK),  # 竖大拇指用于点击
+            ActionBinding("point", "hold", ActionType.MOUSE_MOVE),
+            ActionBinding("victory", "enter", ActionType.SCREENSHOT),
+            ActionBinding("ok", "enter", ActionType.VOLUME_MUTE),
+            ActionBinding("left", "slide", ActionType.SWITCH_WINDOW),
+            ActionBinding("right", "slide", ActionType.SWITCH_WINDOW),
+            ActionBinding("up", "slide", ActionType.VOLUME_UP),
+            ActionBinding("down", "slide", ActionType.VOLUME_DOWN),
+        ]
+
+        # hold 重复动作已触发的次数：(手势, 绑定序号) -> 次数，进入与退出时清零
+        self._hold_fired: Dict[Tuple[str, int], int] = {}
 
         # 控制是否激活
         self._active = False
@@ -154,10 +250,76 @@ class ActionExecutor:
         # 激活状态变更回调（用于通知 server 广播状态）
         self._on_active_changed: Optional[Callable[[bool], None]] = None
 
+        # 委托给宿主注入时的发送回调（参数为协议中的 InputCommand）
+        self._delegate: Optional[Callable[[Dict], None]] = None
+
+        # 是否由宿主执行动作（此时本执行器不处理手势，包括 open / fist 激活）
+        self._host_execution = False
+
     def set_on_active_changed(self, callback: Callable[[bool], None]):
         """设置激活状态变更回调"""
         self._on_active_changed = callback
 
+    def set_delegate(
+        self,
+        callback: Optional[Callable[[Dict], None]],
+        screen_size: Optional[Tuple[int, int]] = None
+    ):
+        """
+        设置输入注入的委托（为 None 时由本进程直接注入，目前只支持 Windows）
+
+        Args:
+            callback: 发送 InputCommand 的回调
+            screen_size: 宿主报告的桌面尺寸，用于换算鼠标位移
+        """
+        with self._action_lock:
+            # 切换前释放按住的鼠标，避免在另一端无法松开
+            self._release_all()
+            self._delegate = callback
+            if screen_size:
+                self.config.screen_width, self.config.screen_height = screen_size
+        print(f"[ACTION] 输入注入: {'由宿主执行' if callback else '本地执行'}")
+
+    def set_display_area(self, area: Optional[Tuple[int, int, int, int]]):
+        """
+        设置绝对定位映射的显示器区域
+
+        Args:
+            area: 桌面上的 (x, y, 宽, 高)，为 None 时映射到主屏
+        """
+        with self._action_lock:
+            self.config.display_area = area
+            self._smoothed_pos = None
+        if area:
+            print(f"[ACTION] 鼠标映射区域: {area[2]}x{area[3]} @ ({area[0]}, {area[1]})")
+
+    def set_calibration(self, matrix: Optional[List[float]]):
+        """
+        设置鼠标映射的校准变换
+
+        Args:
+            matrix: 按行排列的 3x3 单应矩阵，为 None 时不校准
+        """
+        with self._action_lock:
+            self.config.calibration = list(matrix) if matrix else None
+            self.reset_mouse_tracking()
+        print(f"[ACTION] 鼠标映射校准: {'已启用' if matrix else '未校准'}")
+
+    def set_host_execution(self, enabled: bool):
+        """
+        设置是否由宿主执行动作
+
+        Args:
+            enabled: 为 True 时忽略手势与滑动，只保留由宿主设置的激活状态
+        """
+        with self._action_lock:
+            # 切换前释放按住的鼠标，避免交给宿主后无法松开
+            self._release_all()
+            self.reset_mouse_tracking()
+            self._hold_fired.clear()
+            self._host_execution = enabled
+        print(f"[ACTION] 动作执行: {'由宿主执行' if enabled else '本地执行'}")
+
     def set_active(self, active: bool, notify: bool = True):
         """
         设置是否激活控制
@@ -186,11 +348,40 @@ class ActionExecutor:
         """是否激活"""
         return self._active
 
+    def set_bindings(self, bindings: List[Dict]):
+        """
+        替换动作绑定（open 固定用于激活，不可绑定；绑定了 fist 时 fist 不再停用控制）
+
+        Args:
+            bindings: 协议中的绑定列表，无效的条目会被跳过
+        """
+        parsed = []
+        for index, item in enumerate(bindings):
+            try:
+                parsed.append(ActionBinding.from_dict(item))
+            except (KeyError, TypeError, ValueError) as e:
+                print(f"[WARN] 忽略无效的动作绑定 #{index}: {e}")
+
+        with self._action_lock:
+            # 切换前释放按住的鼠标，避免新绑定下无法松开
+            self._release_all()
+            self.reset_mouse_tracking()
+            self._bindings = parsed
+            self._hold_fired.clear()
+        print(f"[ACTION] 动作绑定已更新: {len(parsed)} 条")
+
+    def release_all(self):
+        """释放所有按住的按键并重置鼠标追踪"""
+        with self._action_lock:
+            self._release_all()
+            self.reset_mouse_tracking()
+
     def execute_gesture(
         self,
         gesture: str,
         event_type: str,
         hand_pos: Optional[Tuple[float, float]] = None,
+        hold_duration: float = 0,
         meta: Optional[Dict] = None
     ):
         """
@@ -200,8 +391,13 @@ class ActionExecutor:
             gesture: 手势类型
             event_type: 事件类型 (enter/hold/exit)
             hand_pos: 手部位置 (归一化 0-1)
+            hold_duration: 保持时长（毫秒）
             meta: 附加信息
         """
+        # 由宿主执行时只负责识别
+        if self._host_execution:
+            return
+
         # open 手势用于激活控制（无论当前是否激活都可以触发）
         if gesture == "open":
             print(f"[ACTION] 收到 open 手势, event_type={event_type}, active={self._active}", flush=True)
@@ -210,8 +406,9 @@ class ActionExecutor:
                 print("[ACTION] 控制已激活 (open 手势)")
             return
 
-        # fist 手势用于停用控制（无论当前是否激活都可以触发）
-        if gesture == "fist":
+        # fist 手势用于停用控制（无论当前是否激活都可以触发），
+        # 当前绑定占用了 fist 时（如视频播放器中播放/暂停）按普通手势执行
+        if gesture == "fist" and not self._binds_fist():
             if event_type == "enter" and self._active:
                 self.set_active(False)
                 print("[ACTION] 控制已停用 (fist 手势)")
@@ -222,31 +419,25 @@ class ActionExecutor:
             return
 
         with self._action_lock:
-            # 根据手势执行动作
-            action = self._gesture_action_map.get(gesture)
-            if not action:
-                return
-
-            if action == ActionType.MOUSE_MOVE:
-                if hand_pos and event_type in ("enter", "hold"):
-                    self._move_mouse(hand_pos)
-                elif event_type == "exit":
-                    # 手势退出时重置追踪（相当于"抬手"）
-                    self.reset_mouse_tracking()
-
-            elif action == ActionType.MOUSE_CLICK:
-                if event_type == "enter":
-                    self._mouse_down()
-                elif event_type == "exit":
-                    self._mouse_up()
-
-            elif action == ActionType.VOLUME_MUTE:
-                if event_type == "enter":
-                    self._volume_mute()
-
-            elif action == ActionType.SCREENSHOT:
-                if event_type == "enter":
-                    self._screenshot()
+            if event_type in ("enter", "exit"):
+                self._hold_fired = {
+                    key: count for key, count in self._hold_fired.items() if key[0] != gesture
+                }
+
+            for index, binding in enumerate(self._bindings):
+                if binding.gesture != gesture or binding.on == "slide":
+                    continue
+                if binding.action in POINTER_ACTIONS:
+                    # 持续动作响应整个手势周期
+                    self._execute_pointer(binding.action, event_type, hand_pos)
+                elif binding.on != event_type:
+                    continue
+                elif event_type != "hold" or self._hold_due((gesture, index), binding, hold_duration):
+                    self._execute_instant(binding)
+
+    def _binds_fist(self) -> bool:
+        """当前绑定是否占用了 fist"""
+        return any(b.gesture == "fist" and b.on != "slide" for b in self._bindings)
 
     def execute_slide(self, direction: str, distance: float):
         """
@@ -256,35 +447,105 @@ class ActionExecutor:
             direction: 滑动方向 (left/right/up/down)
             distance: 滑动距离
         """
-        if not self._active:
+        if not self._active or self._host_execution:
             return
 
         with self._action_lock:
-            action = self._slide_action_map.get(direction)
-            if not action:
-                return
-
-            if action == ActionType.SWITCH_WINDOW:
-                self._switch_window(direction == "right")
-
-            elif action == ActionType.VOLUME_UP:
-                self._volume_change(up=True)
-
-            elif action == ActionType.VOLUME_DOWN:
-                self._volume_change(up=False)
-
-    # ========== Windows 平台实现 ==========
+            for binding in self._bindings:
+                if binding.on == "slide" and binding.gesture == direction:
+                    self._execute_instant(binding, forward=direction != "left")
+
+    def _hold_due(self, key: Tuple[str, int], binding: ActionBinding, hold_duration: float) -> bool:
+        """hold 上的一次性动作是否到了下一次重复（每个间隔触发一次，跳帧时不补发）"""
+        count = int(hold_duration // binding.interval_ms)
+        if count <= self._hold_fired.get(key, 0):
+            return False
+        self._hold_fired[key] = count
+        return True
+
+    def _execute_pointer(
+        self,
+        action: ActionType,
+        event_type: str,
+        hand_pos: Optional[Tuple[float, float]]
+    ):
+        """执行鼠标移动、点击与拖动"""
+        if action in (ActionType.MOUSE_MOVE, ActionType.MOUSE_DRAG):
+            if hand_pos and event_type in ("enter", "hold"):
+                self._move_mouse(hand_pos)
+            elif event_type == "exit":
+                # 手势退出时重置追踪（相当于"抬手"）
+                self.reset_mouse_tracking()
+
+        if action in (ActionType.MOUSE_CLICK, ActionType.MOUSE_DRAG):
+            if event_type == "enter":
+                self._mouse_down()
+            elif event_type == "exit":
+                self._mouse_up()
+
+    def _execute_instant(self, binding: ActionBinding, forward: bool = True):
+        """执行一次性动作（鼠标移动、点击与拖动除外）"""
+        action = binding.action
+        if action == ActionType.SWITCH_WINDOW:
+            self._switch_window(forward)
+        elif action == ActionType.VOLUME_UP:
+            self._volume_change(up=True)
+        elif action == ActionType.VOLUME_DOWN:
+            self._volume_change(up=False)
+        elif action == ActionType.VOLUME_MUTE:
+            self._volume_mute()
+        elif action == ActionType.MEDIA_PLAY_PAUSE:
+            self._media_play_pause()
+        elif action == ActionType.MEDIA_NEXT:
+            self._media_track(next_track=True)
+        elif action == ActionType.MEDIA_PREV:
+            self._media_track(next_track=False)
+        elif action == ActionType.SLIDE_NEXT:
+            self._slide_page(next_slide=True)
+        elif action == ActionType.SLIDE_PREV:
+            self._slide_page(next_slide=False)
+        elif action == ActionType.SCREENSHOT:
+            self._screenshot()
+        elif action == ActionType.MOUSE_SCROLL:
+            self._scroll(binding.amount)
+        elif action == ActionType.KEY_CHORD:
+            self._key_chord(binding.keys)
+        elif action == ActionType.TEXT:
+            self._type_text(binding.text)
+
+    # ========== 输入注入（Windows 或委托给宿主） ==========
+
+    def _can_inject(self) -> bool:
+        """当前是否可以注入输入"""
+        return self._delegate is not None or platform.system() == "Windows"
 
     def _move_mouse(self, pos: Tuple[float, float]):
         """移动鼠标"""
-        if platform.system() != "Windows":
+        if not self._can_inject():
             return
 
+        # 校准后的位置为映射区域内的归一化坐标
+        if self.config.calibration:
+            pos = self._calibrate(pos)
+            if pos is None:
+                return
+
         if self.config.mouse_mode == "relative":
             self._move_mouse_relative(pos)
         else:
             self._move_mouse_absolute(pos)
 
+    def _calibrate(self, pos: Tuple[float, float]) -> Optional[Tuple[float, float]]:
+        """按校准的单应矩阵变换指尖位置，点在无穷远处时返回 None"""
+        m = self.config.calibration
+        w = m[6] * pos[0] + m[7] * pos[1] + m[8]
+        if abs(w) < 1e-12:
+            return None
+        return (
+            (m[0] * pos[0] + m[1] * pos[1] + m[2]) / w,
+            (m[3] * pos[0] + m[4] * pos[1] + m[5]) / w,
+        )
+
     def _move_mouse_relative(self, pos: Tuple[float, float]):
         """相对定位模式（类似触摸板）"""
         # 如果是抬起状态或没有上一个位置，初始化参考点
@@ -323,6 +584,10 @@ class ActionExecutor:
 
     def _send_mouse_move_relative(self, dx: int, dy: int):
         """发送相对鼠标移动"""
+        if self._delegate:
+            self._delegate({"op": "move_relative", "dx": dx, "dy": dy})
+            return
+
         inp = INPUT()
         inp.type = 0  # INPUT_MOUSE
         inp.union.mi.dx = dx
@@ -342,20 +607,34 @@ class ActionExecutor:
                 alpha * self._smoothed_pos[1] + (1 - alpha) * pos[1]
             )
 
+        # 映射区域：委托时为区域内的归一化位置，由宿主换算到所选显示器
+        if self.config.display_area and not self._delegate:
+            left, top, width, height = self.config.display_area
+        else:
+            left, top, width, height = 0, 0, self.config.screen_width, self.config.screen_height
+
         # 转换为屏幕坐标
-        x = int(self._smoothed_pos[0] * self.config.screen_width)
-        y = int(self._smoothed_pos[1] * self.config.screen_height)
+        x = int(self._smoothed_pos[0] * width)
+        y = int(self._smoothed_pos[1] * height)
 
-        # 应用灵敏度
-        x = int(x * self.config.mouse_sensitivity)
-        y = int(y * self.config.mouse_sensitivity)
+        # 应用灵敏度（校准后已按校准映射，不再应用）
+        sensitivity = 1.0 if self.config.calibration else self.config.mouse_sensitivity
+        x = int(x * sensitivity)
+        y = int(y * sensitivity)
 
-        # 限制在屏幕范围内
-        x = max(0, min(x, self.config.screen_width - 1))
-        y = max(0, min(y, self.config.screen_height - 1))
+        # 限制在映射区域内
+        x = max(0, min(x, width - 1))
+        y = max(0, min(y, height - 1))
 
         # 移动鼠标
-        user32.SetCursorPos(x, y)
+        if self._delegate:
+            self._delegate({
+                "op": "move_absolute",
+                "x": x / max(1, width - 1),
+                "y": y / max(1, height - 1),
+            })
+        else:
+            user32.SetCursorPos(left + x, top + y)
 
     def reset_mouse_tracking(self):
         """重置鼠标追踪状态（用于抬手重新定位）"""
@@ -366,20 +645,26 @@ class ActionExecutor:
 
     def _mouse_down(self):
         """鼠标按下"""
-        if platform.system() != "Windows" or self._mouse_pressed:
+        if not self._can_inject() or self._mouse_pressed:
             return
 
         self._mouse_pressed = True
-        self._send_mouse_event(MOUSEEVENTF_LEFTDOWN)
+        if self._delegate:
+            self._delegate({"op": "button", "button": "left", "pressed": True})
+        else:
+            self._send_mouse_event(MOUSEEVENTF_LEFTDOWN)
         print("[ACTION] 鼠标按下")
 
     def _mouse_up(self):
         """鼠标释放"""
-        if platform.system() != "Windows" or not self._mouse_pressed:
+        if not self._can_inject() or not self._mouse_pressed:
             return
 
         self._mouse_pressed = False
-        self._send_mouse_event(MOUSEEVENTF_LEFTUP)
+        if self._delegate:
+            self._delegate({"op": "button", "button": "left", "pressed": False})
+        else:
+            self._send_mouse_event(MOUSEEVENTF_LEFTUP)
         print("[ACTION] 鼠标释放")
 
     def _send_mouse_event(self, flags: int, data: int = 0):
@@ -404,66 +689,117 @@ class ActionExecutor:
         inp.union.ki.dwFlags = KEYEVENTF_KEYUP if up else 0
         user32.SendInput(1, ctypes.byref(inp), ctypes.sizeof(INPUT))
 
-    def _press_key(self, vk: int):
-        """按下并释放按键"""
-        self._send_key(vk, up=False)
-        time.sleep(0.05)
-        self._send_key(vk, up=True)
+    def _send_keys(self, keys: str):
+        """按下并松开组合键（规范形式，如 Ctrl+Shift+T；媒体键如 VolumeUp），依次按下，逆序松开"""
+        if self._delegate:
+            self._delegate({"op": "keys", "keys": keys})
+            return
+        if platform.system() != "Windows":
+            return
+
+        codes = [KEY_CODES.get(name) or MEDIA_KEY_CODES[name] for name in keys.split("+")]
+        for vk in codes:
+            self._send_key(vk, up=False)
+            time.sleep(0.02)
+        for vk in reversed(codes):
+            self._send_key(vk, up=True)
 
     def _volume_change(self, up: bool):
         """调节音量"""
-        if platform.system() != "Windows":
+        if not self._can_inject():
             return
 
-        vk = VK_VOLUME_UP if up else VK_VOLUME_DOWN
-        self._press_key(vk)
+        self._send_keys("VolumeUp" if up else "VolumeDown")
         print(f"[ACTION] 音量{'增加' if up else '减少'}")
 
     def _volume_mute(self):
         """静音切换"""
-        if platform.system() != "Windows":
+        if not self._can_inject():
             return
 
-        self._press_key(VK_VOLUME_MUTE)
+        self._send_keys("VolumeMute")
         print("[ACTION] 静音切换")
 
     def _media_play_pause(self):
         """播放/暂停"""
-        if platform.system() != "Windows":
+        if not self._can_inject():
             return
 
-        self._press_key(VK_MEDIA_PLAY_PAUSE)
+        self._send_keys("MediaPlayPause")
         print("[ACTION] 播放/暂停")
 
+    def _media_track(self, next_track: bool):
+        """上一曲/下一曲"""
+        if not self._can_inject():
+            return
+
+        self._send_keys("MediaNext" if next_track else "MediaPrev")
+        print(f"[ACTION] {'下一曲' if next_track else '上一曲'}")
+
+    def _slide_page(self, next_slide: bool):
+        """幻灯片翻页 (Page Down / Page Up，演示软件与 PDF 阅读器通用)"""
+        if not self._can_inject():
+            return
+
+        self._send_keys("PageDown" if next_slide else "PageUp")
+        print(f"[ACTION] {'下一页' if next_slide else '上一页'}")
+
     def _switch_window(self, forward: bool = True):
-        """切换窗口 (Alt+Tab)"""
-        if platform.system() != "Windows":
+        """切换窗口 (Alt+Tab，后退时为 Alt+Shift+Tab)"""
+        if not self._can_inject():
             return
 
-        # Alt + Tab
-        self._send_key(VK_MENU, up=False)
-        time.sleep(0.05)
-        self._press_key(VK_TAB)
-        time.sleep(0.05)
-        self._send_key(VK_MENU, up=True)
+        self._send_keys("Alt+Tab" if forward else "Alt+Shift+Tab")
         print(f"[ACTION] 切换窗口 ({'前进' if forward else '后退'})")
 
     def _screenshot(self):
-        """截屏 (Win + Shift + S)"""
-        if platform.system() != "Windows":
+        """截屏（Windows 为 Win + Shift + S，委托给宿主时为 PrintScreen）"""
+        if not self._can_inject():
             return
 
-        # Win + Shift + S
-        self._send_key(VK_LWIN, up=False)
-        time.sleep(0.02)
-        self._send_key(0x10, up=False)  # Shift
-        time.sleep(0.02)
-        self._press_key(0x53)  # S
-        time.sleep(0.02)
-        self._send_key(0x10, up=True)
-        self._send_key(VK_LWIN, up=True)
+        self._send_keys("PrintScreen" if self._delegate else "Shift+Super+S")
         print("[ACTION] 截屏")
 
+    def _scroll(self, amount: int):
+        """滚动，正数向上"""
+        if self._delegate:
+            self._delegate({"op": "scroll", "amount": amount})
+        elif platform.system() == "Windows":
+            # mouseData 为 DWORD，负数按补码传递
+            self._send_mouse_event(MOUSEEVENTF_WHEEL, (amount * WHEEL_DELTA) & 0xFFFFFFFF)
+        else:
+            return
+        print(f"[ACTION] 滚动 {amount}")
+
+    def _key_chord(self, keys: str):
+        """按下组合键"""
+        if not self._can_inject():
+            return
+
+        self._send_keys(keys)
+        print(f"[ACTION] 组合键 {keys}")
+
+    def _type_text(self, text: str):
+        """输入文本（Unicode 键盘事件，不受输入法与键盘布局影响）"""
+        if self._delegate:
+            self._delegate({"op": "text", "text": text})
+            print(f"[ACTION] 输入文本 ({len(text)} 个字符)")
+            return
+        if platform.system() != "Windows":
+            return
+
+        # 超出 BMP 的字符需要拆成 UTF-16 代理对
+        encoded = text.encode("utf-16-le")
+        for i in range(0, len(encoded), 2):
+            unit = int.from_bytes(encoded[i:i + 2], "little")
+            for up in (False, True):
+                inp = INPUT()
+                inp.type = 1  # INPUT_KEYBOARD
+                inp.union.ki.wScan = unit
+                inp.union.ki.dwFlags = KEYEVENTF_UNICODE | (KEYEVENTF_KEYUP if up else 0)
+                user32.SendInput(1, ctypes.byref(inp), ctypes.sizeof(INPUT))
+        print(f"[ACTION] 输入文本 ({len(text)} 个字符)")
+
     def _release_all(self):
         """释放所有按键"""
         if self._mouse_pressed:
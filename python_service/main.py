@@ -14,7 +14,7 @@ import asyncio
 import sys
 import cv2
 
-from config.settings import Config, default_config
+from config.settings import Config, default_config, load_config
 
 
 def run_debug_mode(config: Config):
@@ -190,7 +190,8 @@ def run_server_mode(config: Config):
     try:
         asyncio.run(server.run(
             host=config.server.host,
-            port=config.server.port
+            port=config.server.port,
+            mjpeg_port=config.server.mjpeg_port
         ))
     except KeyboardInterrupt:
         print("\n[SERVER] 收到中断信号")
@@ -272,6 +273,7 @@ def main():
     python main.py --debug          启动调试预览窗口
     python main.py --test           运行测试
     python main.py --port 9000      指定端口号
+    python main.py --config cfg.json  使用配置文件
         """
     )
 
@@ -287,34 +289,59 @@ def main():
         help="运行测试模式"
     )
 
+    parser.add_argument(
+        "--config",
+        type=str,
+        default=None,
+        help="配置文件路径（JSON，由桌面端写入）"
+    )
+
     parser.add_argument(
         "--host",
         type=str,
-        default="127.0.0.1",
+        default=None,
         help="服务器主机地址 (默认: 127.0.0.1)"
     )
 
     parser.add_argument(
         "--port", "-p",
         type=int,
-        default=8765,
+        default=None,
         help="服务器端口 (默认: 8765)"
     )
 
+    parser.add_argument(
+        "--mjpeg-port",
+        type=int,
+        default=None,
+        help="MJPEG 视频流端口 (默认: 8766)"
+    )
+
     parser.add_argument(
         "--camera", "-c",
         type=int,
-        default=0,
+        default=None,
         help="摄像头设备 ID (默认: 0)"
     )
 
     args = parser.parse_args()
 
-    # 创建配置
+    # 创建配置（命令行参数优先于配置文件）
     config = Config()
-    config.server.host = args.host
-    config.server.port = args.port
-    config.camera.device_id = args.camera
+    if args.config:
+        try:
+            config = load_config(args.config)
+            print(f"[SERVER] 已读取配置文件: {args.config}")
+        except (OSError, ValueError) as e:
+            print(f"[WARN] 无法读取配置文件 {args.config}，使用默认配置: {e}")
+    if args.host is not None:
+        config.server.host = args.host
+    if args.port is not None:
+        config.server.port = args.port
+    if args.mjpeg_port is not None:
+        config.server.mjpeg_port = args.mjpeg_port
+    if args.camera is not None:
+        config.camera.device_id = args.camera
 
     # 根据参数选择模式
     if args.test:
@@ -9,7 +9,7 @@ import time
 import threading
 import socketserver
 from typing import Set, Optional, Dict, Any
-from dataclasses import dataclass, asdict
+from dataclasses import dataclass, asdict, fields, replace
 from http.server import HTTPServer, BaseHTTPRequestHandler
 import websockets
 from websockets.server import WebSocketServerProtocol
@@ -20,9 +20,12 @@ from core.capture import CameraCapture, Frame
 from core.detector import HandDetector, DetectionResult
 from core.gesture import GestureClassifier, GestureProba
 from core.state_machine import GestureStateMachine, GestureEvent
-from core.action import ActionExecutor
+from core.action import ActionConfig, ActionExecutor, legacy_bindings
 from config.settings import Config, default_config
 
+# WebSocket 协议版本（须与 tauri_app/src-tauri/protocol 中的 PROTOCOL_VERSION 兼容）
+PROTOCOL_VERSION = "0.9.0"
+
 
 # Global reference for MJPEG stream
 _current_frame: Optional[np.ndarray] = None
@@ -107,6 +110,30 @@ class WebSocketMessage:
         return cls(**data)
 
 
+# 可通过 config_update 在运行时修改的配置段（摄像头与服务参数需要重启）
+LIVE_CONFIG_SECTIONS = ("gesture", "state_machine", "action")
+
+
+def _coerce_config_value(path: str, current: Any, value: Any) -> Any:
+    """按当前值的类型检查新值（JSON 中的整数可用于浮点字段）"""
+    if isinstance(current, bool):
+        ok = isinstance(value, bool)
+    elif isinstance(current, int):
+        ok = isinstance(value, int) and not isinstance(value, bool) and value >= 0
+    elif isinstance(current, float):
+        ok = isinstance(value, (int, float)) and not isinstance(value, bool)
+        value = float(value) if ok else value
+    elif isinstance(current, dict):
+        ok = isinstance(value, dict) and all(
+            isinstance(v, int) and not isinstance(v, bool) for v in value.values()
+        )
+    else:
+        ok = isinstance(value, type(current))
+    if not ok:
+        raise ValueError(f"{path} 的类型无效: {value!r}")
+    return value
+
+
 class PhantomHandServer:
     """
     PhantomHand WebSocket 服务器
@@ -125,14 +152,24 @@ class PhantomHandServer:
 
         # WebSocket 连接
         self._clients: Set[WebSocketServerProtocol] = set()
+        # 接管输入注入的宿主连接
+        self._input_client: Optional[WebSocketServerProtocol] = None
+        # 接管动作执行的宿主连接
+        self._execution_client: Optional[WebSocketServerProtocol] = None
 
         # 运行状态
         self._running = False
+        self._stopped = False
         self._processing_task: Optional[asyncio.Task] = None
+        self._camera_paused = False
 
         # 统计信息
         self._frame_count = 0
         self._start_time = 0.0
+        self._last_frame_id = 0
+        self._dropped_frames = 0
+        self._inference_total_ms = 0.0
+        self._inference_samples = 0
 
     async def start(self):
         """启动服务"""
@@ -173,14 +210,23 @@ class PhantomHandServer:
             p_low=self.config.state_machine.p_low,
             t_enter=self.config.state_machine.t_enter,
             t_exit=self.config.state_machine.t_exit,
-            t_cooldown=self.config.state_machine.t_cooldown
+            t_cooldown=self.config.state_machine.t_cooldown,
+            ema_alpha=self.config.state_machine.ema_alpha,
+            median_window=self.config.state_machine.median_window,
+            gesture_priority=dict(self.config.state_machine.gesture_priority)
         )
 
         # 注册手势事件回调
         self.state_machine.register_callback(self._on_gesture_event)
 
         # 初始化动作执行器
-        self.action_executor = ActionExecutor()
+        self.action_executor = ActionExecutor(ActionConfig(
+            mouse_sensitivity=self.config.action.mouse_sensitivity,
+            mouse_smoothing=self.config.action.mouse_smoothing
+        ))
+
+        # 宿主连接后会推送当前手势配置的绑定，此前使用配置文件中的映射
+        self.action_executor.set_bindings(legacy_bindings(self.config.action.mappings))
 
         # 注册激活状态变更回调（用于广播到前端）
         self.action_executor.set_on_active_changed(self._on_active_changed)
@@ -191,11 +237,19 @@ class PhantomHandServer:
         print("[SERVER] 组件初始化完成")
 
     async def stop(self):
-        """停止服务"""
+        """停止服务（可重复调用）"""
+        if self._stopped:
+            return
+        self._stopped = True
+
         print("[SERVER] 正在停止服务...")
 
         self._running = False
 
+        # 释放可能按住的鼠标按键
+        if self.action_executor:
+            self.action_executor.set_active(False, notify=False)
+
         # 停止处理任务
         if self._processing_task:
             self._processing_task.cancel()
@@ -237,6 +291,7 @@ class PhantomHandServer:
                 gesture=event.gesture,
                 event_type=event.event_type,
                 hand_pos=hand_pos,
+                hold_duration=event.hold_duration,
                 meta=event.meta
             )
 
@@ -268,6 +323,17 @@ class PhantomHandServer:
 
         await self._broadcast(message.to_json())
 
+    def _send_input(self, command: Dict[str, Any]):
+        """把输入注入发给接管注入的宿主（在事件循环线程中调用）"""
+        if not self._input_client:
+            return
+        message = WebSocketMessage(
+            type="input",
+            timestamp=time.time() * 1000,
+            data=command
+        )
+        asyncio.create_task(self._input_client.send(message.to_json()))
+
     async def _broadcast(self, message: str):
         """广播消息到所有客户端"""
         if not self._clients:
@@ -291,6 +357,10 @@ class PhantomHandServer:
                 continue
 
             self._frame_count += 1
+            # 帧序号不连续说明采集队列满时丢弃了旧帧（摄像头重启后序号从头开始）
+            if frame.frame_id > self._last_frame_id:
+                self._dropped_frames += frame.frame_id - self._last_frame_id - 1
+            self._last_frame_id = frame.frame_id
 
             # 检测手部
             detection = self.detector.detect(
@@ -299,6 +369,8 @@ class PhantomHandServer:
                 timestamp=frame.timestamp
             )
             self._last_detection = detection
+            self._inference_total_ms += detection.inference_time_ms
+            self._inference_samples += 1
 
             # 绘制骨骼并更新 MJPEG 流
             output_frame = self.detector.draw_landmarks(frame.image, detection)
@@ -323,7 +395,11 @@ class PhantomHandServer:
                 # 检测滑动（当 point 手势控制鼠标时禁用，避免冲突）
                 is_pointing = state and state.gesture == "point"
                 if not is_pointing:
-                    slide = self.classifier.detect_slide(hand)
+                    slide = self.classifier.detect_slide(
+                        hand,
+                        min_distance=self.config.gesture.slide_min_distance,
+                        max_z_change=self.config.gesture.slide_max_z_change
+                    )
                     if slide:
                         direction, distance = slide
                         if self.action_executor:
@@ -382,8 +458,9 @@ class PhantomHandServer:
             timestamp=time.time() * 1000,
             data={
                 "message": "Welcome to PhantomHand",
-                "version": "0.1.0",
+                "version": PROTOCOL_VERSION,
                 "active": self.action_executor.is_active() if self.action_executor else False,
+                "camera_paused": self._camera_paused,
                 "config": {
                     "camera": {
                         "width": self.config.camera.width,
@@ -401,6 +478,14 @@ class PhantomHandServer:
             pass
         finally:
             self._clients.discard(websocket)
+            if websocket is self._input_client and self.action_executor:
+                # 宿主断开后恢复本地注入
+                self._input_client = None
+                self.action_executor.set_delegate(None)
+            if websocket is self._execution_client and self.action_executor:
+                # 宿主断开后恢复本地执行
+                self._execution_client = None
+                self.action_executor.set_host_execution(False)
             print(f"[SERVER] 客户端已断开: {client_id}")
 
     async def _handle_message(self, websocket: WebSocketServerProtocol, message: str):
@@ -423,6 +508,9 @@ class PhantomHandServer:
                 active = data.get("data", {}).get("active", False)
                 if self.action_executor:
                     self.action_executor.set_active(active, notify=False)
+                    if not active:
+                        # 已停用时 set_active 不会再释放，紧急停止仍须松开按住的按键
+                        self.action_executor.release_all()
                     print(f"[SERVER] 前端触发控制状态: {'激活' if active else '停用'}")
                     # 广播状态变更给所有客户端
                     state_msg = WebSocketMessage(
@@ -432,16 +520,189 @@ class PhantomHandServer:
                     )
                     asyncio.create_task(self._broadcast(state_msg.to_json()))
 
+            elif msg_type == "set_camera_paused":
+                # 暂停时释放摄像头（例如让给视频会议）
+                paused = data.get("data", {}).get("paused", False)
+                await self._set_camera_paused(paused)
+
+            elif msg_type == "set_action_map":
+                # 切换手势配置或修改绑定
+                mapping = data.get("data", {})
+                if self.action_executor:
+                    self.action_executor.set_bindings(mapping.get("bindings", []))
+
+            elif msg_type == "set_input_delegate":
+                # 宿主接管输入注入：之后的注入以 input 消息发给该客户端
+                payload = data.get("data", {})
+                if self.action_executor:
+                    enabled = payload.get("enabled", False)
+                    width = payload.get("screen_width")
+                    height = payload.get("screen_height")
+                    self._input_client = websocket if enabled else None
+                    self.action_executor.set_delegate(
+                        self._send_input if enabled else None,
+                        (width, height) if width and height else None
+                    )
+
+            elif msg_type == "set_display_area":
+                # 宿主选择的鼠标映射区域（桌面上的物理像素）
+                area = data.get("data", {})
+                if self.action_executor:
+                    self.action_executor.set_display_area((
+                        int(area.get("x", 0)),
+                        int(area.get("y", 0)),
+                        int(area.get("width", 0)),
+                        int(area.get("height", 0)),
+                    ) if area.get("width") and area.get("height") else None)
+
+            elif msg_type == "set_pointer_calibration":
+                # 宿主按摄像头与显示器选择的校准变换
+                matrix = data.get("data", {}).get("matrix")
+                if self.action_executor:
+                    self.action_executor.set_calibration(
+                        matrix if matrix and len(matrix) == 9 else None
+                    )
+
+            elif msg_type == "set_host_execution":
+                # 宿主接管动作执行：本进程只识别并广播事件，激活状态由宿主通过 set_active 设置
+                enabled = data.get("data", {}).get("enabled", False)
+                if self.action_executor:
+                    self._execution_client = websocket if enabled else None
+                    self.action_executor.set_host_execution(enabled)
+
+            elif msg_type == "shutdown":
+                # 宿主请求关闭：退出主循环，由 run() 释放资源
+                print("[SERVER] 收到关闭请求")
+                self._running = False
+
             elif msg_type == "config_update":
-                # 更新配置
-                # TODO: 实现配置更新逻辑
-                pass
+                # 运行时修改识别参数，结果只回复给请求方
+                payload = data.get("data", {})
+                ack = self._apply_config_update(payload.get("changes", {}))
+                ack["request_id"] = payload.get("request_id", 0)
+                reply = WebSocketMessage(
+                    type="config_ack",
+                    timestamp=time.time() * 1000,
+                    data=ack
+                )
+                await websocket.send(reply.to_json())
 
         except json.JSONDecodeError:
             print(f"[WARN] 无效的 JSON 消息: {message}")
         except Exception as e:
             print(f"[ERROR] 处理消息异常: {e}")
 
+    def _apply_config_update(self, changes: Dict[str, Any]) -> Dict[str, Any]:
+        """
+        应用部分配置（gesture / state_machine / action）
+
+        先整体校验，任一字段无效时不应用任何修改
+
+        Returns:
+            {"applied": [字段路径], "error": 错误信息或 None}
+        """
+        try:
+            updated = self._validate_config_update(changes)
+        except ValueError as e:
+            print(f"[WARN] 拒绝配置更新: {e}")
+            return {"applied": [], "error": str(e)}
+
+        applied = []
+        for section, values in updated.items():
+            target = getattr(self.config, section)
+            for key, value in values.items():
+                setattr(target, key, value)
+                applied.append(f"{section}.{key}")
+        self._sync_components_config()
+
+        if applied:
+            print(f"[SERVER] 已应用配置更新: {', '.join(applied)}")
+        return {"applied": applied, "error": None}
+
+    def _validate_config_update(self, changes: Dict[str, Any]) -> Dict[str, Dict[str, Any]]:
+        """校验部分配置，返回按配置段整理后的新值（只包含有变化的字段）"""
+        if not isinstance(changes, dict):
+            raise ValueError("changes 应为对象")
+
+        updated: Dict[str, Dict[str, Any]] = {}
+        for section, values in changes.items():
+            if section not in LIVE_CONFIG_SECTIONS:
+                raise ValueError(f"{section} 不能在运行时修改")
+            if not isinstance(values, dict):
+                raise ValueError(f"{section} 应为对象")
+
+            target = getattr(self.config, section)
+            known = {f.name for f in fields(target)}
+            for key, value in values.items():
+                path = f"{section}.{key}"
+                if key not in known:
+                    raise ValueError(f"未知的配置项: {path}")
+                value = _coerce_config_value(path, getattr(target, key), value)
+                if value != getattr(target, key):
+                    updated.setdefault(section, {})[key] = value
+
+        # 组合约束按修改后的值检查
+        sm = replace(self.config.state_machine, **updated.get("state_machine", {}))
+        if not (sm.p_low <= sm.p_hold <= sm.p_high):
+            raise ValueError("state_machine 须满足 p_low <= p_hold <= p_high")
+        if not (0 < sm.ema_alpha <= 1):
+            raise ValueError("state_machine.ema_alpha 应在 (0, 1] 之间")
+        if sm.median_window < 1:
+            raise ValueError("state_machine.median_window 不能小于 1")
+        return updated
+
+    def _sync_components_config(self):
+        """把 self.config 中的识别参数同步到各组件"""
+        gesture = self.config.gesture
+        if self.classifier:
+            self.classifier.finger_extended_angle = gesture.finger_extended_angle
+            self.classifier.finger_bent_angle = gesture.finger_bent_angle
+            self.classifier.pinch_distance_ratio = gesture.pinch_distance_ratio
+            self.classifier.fist_tip_wrist_ratio = gesture.fist_tip_wrist_ratio
+            self.classifier.open_spread_ratio = gesture.open_spread_ratio
+
+        sm = self.config.state_machine
+        if self.state_machine:
+            self.state_machine.p_high = sm.p_high
+            self.state_machine.p_hold = sm.p_hold
+            self.state_machine.p_low = sm.p_low
+            self.state_machine.t_enter = sm.t_enter
+            self.state_machine.t_exit = sm.t_exit
+            self.state_machine.t_cooldown = sm.t_cooldown
+            self.state_machine.ema_alpha = sm.ema_alpha
+            self.state_machine.median_window = sm.median_window
+            self.state_machine.gesture_priority = dict(sm.gesture_priority)
+
+        if self.action_executor:
+            self.action_executor.config.mouse_sensitivity = self.config.action.mouse_sensitivity
+            self.action_executor.config.mouse_smoothing = self.config.action.mouse_smoothing
+
+    async def _set_camera_paused(self, paused: bool):
+        """暂停或恢复摄像头采集"""
+        if paused == self._camera_paused or not self.camera:
+            return
+
+        loop = asyncio.get_running_loop()
+        if paused:
+            # 停止前释放按住的鼠标
+            if self.action_executor:
+                self.action_executor.release_all()
+            await loop.run_in_executor(None, self.camera.stop)
+            print("[SERVER] 摄像头已暂停")
+        else:
+            if not await loop.run_in_executor(None, self.camera.start):
+                print("[ERROR] 恢复摄像头失败")
+                return
+            print("[SERVER] 摄像头已恢复")
+
+        self._camera_paused = paused
+        state_msg = WebSocketMessage(
+            type="camera_paused",
+            timestamp=time.time() * 1000,
+            data={"paused": paused}
+        )
+        await self._broadcast(state_msg.to_json())
+
     async def run(self, host: str = "127.0.0.1", port: int = 8765, mjpeg_port: int = 8766):
         """运行服务器"""
         await self.start()
@@ -468,8 +729,17 @@ class PhantomHandServer:
                 if self._frame_count > 0:
                     elapsed = time.time() - self._start_time
                     fps = self._frame_count / elapsed if elapsed > 0 else 0
+                    # 本周期的平均推理耗时
+                    inference = (self._inference_total_ms / self._inference_samples
+                                 if self._inference_samples > 0 else 0.0)
+                    self._inference_total_ms = 0.0
+                    self._inference_samples = 0
                     print(f"[STATS] 帧数: {self._frame_count}, FPS: {fps:.1f}, "
-                          f"客户端: {len(self._clients)}")
+                          f"客户端: {len(self._clients)}, 丢帧: {self._dropped_frames}, "
+                          f"推理: {inference:.1f}ms")
+
+            # 在关闭 WebSocket 服务前释放资源
+            await self.stop()
 
 
 async def main():
@@ -1,3 +1,28 @@
+use std::path::PathBuf;
+
+use phantom_protocol::codegen;
+
 fn main() {
+    generate_protocol_bindings();
     tauri_build::build()
 }
+
+/// 由协议类型生成前端 TypeScript 定义与 JSON Schema
+///
+/// 协议 crate 是构建依赖，其变更会使本脚本重新执行
+fn generate_protocol_bindings() {
+    let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let outputs = [
+        (root.join("../src/types/protocol.ts"), codegen::typescript()),
+        (
+            root.join("protocol/protocol.schema.json"),
+            codegen::json_schema(),
+        ),
+    ];
+
+    for (path, content) in outputs {
+        if let Err(e) = codegen::write_if_changed(&path, &content) {
+            panic!("无法写入 {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! 生成 TypeScript 定义与 JSON Schema
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use schemars::schema_for;
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::{
+    Action, ActionBinding, ActionMap, ActiveState, CameraInfo, CameraPaused, ClientMessage,
+    ConfigAck, ConfigUpdate, Connected, ConnectedConfig, DisplayArea, Empty, Envelope, FrameData,
+    GestureEvent, GestureEventType, HandData, HostExecution, InputCommand, InputDelegate,
+    MouseButton, PointerCalibration, ServerMessage, PROTOCOL_VERSION,
+};
+
+/// 生成的 TypeScript 定义
+pub fn typescript() -> String {
+    let declarations = [
+        Value::decl(),
+        Empty::decl(),
+        CameraInfo::decl(),
+        ConnectedConfig::decl(),
+        Connected::decl(),
+        HandData::decl(),
+        FrameData::decl(),
+        GestureEventType::decl(),
+        GestureEvent::decl(),
+        ActiveState::decl(),
+        CameraPaused::decl(),
+        Action::decl(),
+        ActionBinding::decl(),
+        ActionMap::decl(),
+        MouseButton::decl(),
+        InputCommand::decl(),
+        InputDelegate::decl(),
+        HostExecution::decl(),
+        DisplayArea::decl(),
+        PointerCalibration::decl(),
+        ConfigUpdate::decl(),
+        ConfigAck::decl(),
+        ServerMessage::decl(),
+        ClientMessage::decl(),
+        Envelope::<ServerMessage>::decl(),
+    ];
+
+    let mut out = String::from(HEADER);
+    out.push_str(&format!(
+        "export const PROTOCOL_VERSION = \"{}\"\n",
+        PROTOCOL_VERSION
+    ));
+    for declaration in declarations {
+        out.push('\n');
+        out.push_str("export ");
+        out.push_str(&declaration);
+        out.push('\n');
+    }
+    out
+}
+
+/// 生成的 JSON Schema（后端消息与客户端消息各一份）
+pub fn json_schema() -> String {
+    let schema = serde_json::json!({
+        "version": PROTOCOL_VERSION,
+        "server": schema_for!(Envelope<ServerMessage>),
+        "client": schema_for!(Envelope<ClientMessage>),
+    });
+    let mut out = serde_json::to_string_pretty(&schema).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// 内容变化时才写入，避免无谓地触发前端重新构建
+pub fn write_if_changed(path: &Path, content: &str) -> io::Result<bool> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+const HEADER: &str = "// 此文件由 src-tauri/protocol 生成，请勿手动修改\n\n";
@@ -0,0 +1,581 @@
+//! PhantomHand WebSocket 协议
+//!
+//! 宿主与 Python 后端之间所有消息的唯一定义。消息格式与 `server.py` 中的
+//! `WebSocketMessage` 一致：`{ type, timestamp, data }`，字段保持后端的 snake_case 命名。
+//!
+//! 启用 `codegen` 特性时可由这些类型生成 TypeScript 定义与 JSON Schema，
+//! 宿主的 build.rs 每次构建都会重新生成，协议变更会直接体现为前端的类型错误
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
+
+/// 协议版本，须与后端欢迎消息中的 `version` 兼容
+pub const PROTOCOL_VERSION: &str = "0.9.0";
+
+/// 检查后端协议版本是否兼容
+///
+/// 主版本号须一致；主版本号为 0 时次版本号也须一致
+pub fn check_version(server: &str) -> Result<(), String> {
+    compatible(PROTOCOL_VERSION, server)
+}
+
+fn compatible(host: &str, server: &str) -> Result<(), String> {
+    let parse = |version: &str| -> Option<(u64, u64)> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some((major, minor))
+    };
+
+    let (Some(ours), Some(theirs)) = (parse(host), parse(server)) else {
+        return Err(format!("无法识别后端协议版本: {}", server));
+    };
+    let compatible = if ours.0 == 0 {
+        ours == theirs
+    } else {
+        ours.0 == theirs.0
+    };
+    if compatible {
+        Ok(())
+    } else {
+        Err(format!("后端协议版本 {} 与宿主 {} 不兼容", server, host))
+    }
+}
+
+/// 空的消息内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct Empty {}
+
+/// 欢迎消息中的摄像头配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct CameraInfo {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 欢迎消息中的配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct ConnectedConfig {
+    pub camera: CameraInfo,
+}
+
+/// `connected`：连接建立后的欢迎消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct Connected {
+    pub message: String,
+    /// 协议版本
+    pub version: String,
+    pub active: bool,
+    /// 摄像头是否已暂停
+    #[serde(default)]
+    pub camera_paused: bool,
+    pub config: ConnectedConfig,
+}
+
+/// 单只手的数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct HandData {
+    pub id: String,
+    /// `Left` / `Right`
+    pub handedness: String,
+    /// 21 个关键点，每个为归一化的 [x, y, z]
+    pub landmarks: Vec<[f64; 3]>,
+    pub gesture: String,
+    pub gesture_score: f64,
+    /// idle / entering / held / exiting
+    pub state: String,
+}
+
+/// `frame_data`：每帧的检测结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct FrameData {
+    #[cfg_attr(feature = "codegen", ts(type = "number"))]
+    pub frame_id: u64,
+    pub hands: Vec<HandData>,
+    pub inference_time_ms: f64,
+    pub active: bool,
+}
+
+/// 手势事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum GestureEventType {
+    Enter,
+    Hold,
+    Exit,
+    Slide,
+}
+
+/// `gesture_event`：手势进入、保持、退出与滑动
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct GestureEvent {
+    pub event_type: GestureEventType,
+    pub gesture: String,
+    pub hand_id: String,
+    pub timestamp: f64,
+    /// 保持时长（毫秒）
+    pub hold_duration: f64,
+    pub confidence: f64,
+    /// 附加信息（如滑动方向与距离）
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+}
+
+/// `active_changed` / `set_active`：控制激活状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct ActiveState {
+    pub active: bool,
+}
+
+/// `camera_paused` / `set_camera_paused`：摄像头暂停状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct CameraPaused {
+    pub paused: bool,
+}
+
+/// 手势触发的动作
+///
+/// 前半部分与 `core/action.py` 中的 `ActionType` 一一对应（`custom` 由 `event` 取代），
+/// 与按键、文本一起由后端注入；`launch`、`open_url`、`shell` 与 `event` 由宿主执行，
+/// 不会发送给后端
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[serde(deny_unknown_fields)]
+pub enum Action {
+    /// 移动鼠标（保持期间跟随食指）
+    MouseMove,
+    /// 左键单击（进入时按下，退出时松开）
+    MouseClick,
+    /// 拖动（进入时按下并跟随食指，退出时松开）
+    MouseDrag,
+    /// 滚动，正数向上（单位为滚轮格数）
+    MouseScroll {
+        amount: i32,
+    },
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MediaPlayPause,
+    MediaNext,
+    MediaPrev,
+    /// 切换窗口（左滑时反向）
+    SwitchWindow,
+    Screenshot,
+    /// 幻灯片下一页
+    SlideNext,
+    /// 幻灯片上一页
+    SlidePrev,
+    /// 组合键，如 `Ctrl+Shift+T`
+    KeyChord {
+        keys: String,
+    },
+    /// 输入一段文本
+    Text {
+        text: String,
+    },
+    /// 启动程序
+    Launch {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// 用默认浏览器打开链接
+    OpenUrl {
+        url: String,
+    },
+    /// 执行 shell 命令
+    Shell {
+        command: String,
+    },
+    /// 向前端发送 `gesture-action` 事件
+    Event {
+        name: String,
+    },
+}
+
+impl Action {
+    /// 是否由宿主执行
+    pub fn is_host(&self) -> bool {
+        matches!(
+            self,
+            Action::Launch { .. }
+                | Action::OpenUrl { .. }
+                | Action::Shell { .. }
+                | Action::Event { .. }
+        )
+    }
+
+    /// 是否为持续动作（占用整个手势周期，只能绑定在 hold 上）
+    pub fn is_pointer(&self) -> bool {
+        matches!(
+            self,
+            Action::MouseMove | Action::MouseClick | Action::MouseDrag
+        )
+    }
+}
+
+/// 一条动作绑定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ActionBinding {
+    /// 手势名；滑动时为方向（left / right / up / down）
+    pub gesture: String,
+    /// 触发时机
+    pub on: GestureEventType,
+    pub action: Action,
+    /// 保持期间重复触发的间隔（毫秒），只用于 hold 上的一次性动作
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_ms: Option<u32>,
+}
+
+/// `set_action_map`：当前手势配置中由后端执行的动作绑定
+///
+/// open 与 fist 固定用于激活/停用控制，后端会忽略它们的绑定
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct ActionMap {
+    pub bindings: Vec<ActionBinding>,
+}
+
+/// 鼠标按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// `input`：委托给宿主执行的输入注入
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum InputCommand {
+    /// 相对移动（像素）
+    MoveRelative { dx: i32, dy: i32 },
+    /// 绝对移动，坐标为映射区域（见 [`DisplayArea`]）内的归一化位置（0-1），由宿主换算到整个桌面
+    MoveAbsolute { x: f64, y: f64 },
+    /// 按下或松开鼠标按键
+    Button { button: MouseButton, pressed: bool },
+    /// 滚动，正数向上（单位为滚轮格数）
+    Scroll { amount: i32 },
+    /// 按下并松开组合键，如 `Ctrl+Shift+T`；媒体键为 `VolumeUp`、`MediaPlayPause` 等
+    Keys { keys: String },
+    /// 逐字符输入文本（`\n` 为回车，`\t` 为 Tab）
+    Text { text: String },
+}
+
+/// `set_input_delegate`：是否把输入注入交给宿主执行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct InputDelegate {
+    /// 为 true 时后端不再直接注入，改为发送 `input` 消息
+    pub enabled: bool,
+    /// 桌面尺寸（像素），用于换算相对移动；未知时为空
+    #[serde(default)]
+    pub screen_width: Option<u32>,
+    #[serde(default)]
+    pub screen_height: Option<u32>,
+}
+
+/// `set_display_area`：鼠标绝对定位映射的区域（虚拟桌面上的物理像素，可能为负）
+///
+/// 后端自己注入时把归一化位置换算到该区域；委托宿主注入时仍发送区域内的归一化位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct DisplayArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `set_pointer_calibration`：鼠标映射的校准变换
+///
+/// `matrix` 为按行排列的 3x3 单应矩阵，把画面中的归一化位置（食指指尖）变换为映射区域内的
+/// 归一化位置；为空时不校准。后端在相对与绝对定位前都先做变换，校准后绝对定位不再应用灵敏度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct PointerCalibration {
+    pub matrix: Option<[f64; 9]>,
+}
+
+/// `set_host_execution`：是否由宿主执行手势动作
+///
+/// 启用后后端只负责识别：不再处理 open / fist 激活与动作绑定，激活状态由宿主通过 `set_active` 设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct HostExecution {
+    pub enabled: bool,
+}
+
+/// `config_update`：运行时修改后端配置
+///
+/// `changes` 为部分配置，结构与 Python `Config` 一致（只允许 gesture、state_machine、action）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct ConfigUpdate {
+    #[cfg_attr(feature = "codegen", ts(type = "number"))]
+    pub request_id: u64,
+    pub changes: Map<String, Value>,
+}
+
+/// `config_ack`：`config_update` 的结果（只发给请求方）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct ConfigAck {
+    #[cfg_attr(feature = "codegen", ts(type = "number"))]
+    pub request_id: u64,
+    /// 已生效的字段路径（如 `gesture.pinch_distance_ratio`）
+    #[serde(default)]
+    pub applied: Vec<String>,
+    /// 校验失败的原因，失败时不会应用任何字段
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 后端发送的消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Connected(Connected),
+    FrameData(FrameData),
+    GestureEvent(GestureEvent),
+    ActiveChanged(ActiveState),
+    CameraPaused(CameraPaused),
+    ConfigAck(ConfigAck),
+    Input(InputCommand),
+    Pong(Empty),
+}
+
+/// 客户端发送的消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Ping(Empty),
+    SetActive(ActiveState),
+    SetCameraPaused(CameraPaused),
+    SetActionMap(ActionMap),
+    ConfigUpdate(ConfigUpdate),
+    SetInputDelegate(InputDelegate),
+    SetHostExecution(HostExecution),
+    SetDisplayArea(DisplayArea),
+    SetPointerCalibration(PointerCalibration),
+    /// 请求后端释放资源并退出
+    Shutdown(Empty),
+}
+
+/// 消息信封
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "codegen", derive(ts_rs::TS, schemars::JsonSchema))]
+pub struct Envelope<T> {
+    /// 毫秒时间戳（`frame_data` 与 `gesture_event` 为摄像头启动以来的毫秒数）
+    #[serde(default)]
+    pub timestamp: f64,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+/// 解码结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incoming {
+    Message(Envelope<ServerMessage>),
+    /// 未知类型（较新的后端），保留类型名
+    Unknown(String),
+}
+
+/// 解码一条后端消息
+pub fn decode(text: &str) -> Result<Incoming, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("无效的消息: {}", e))?;
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "消息缺少 type 字段".to_string())?
+        .to_string();
+
+    match serde_json::from_value(value) {
+        Ok(envelope) => Ok(Incoming::Message(envelope)),
+        Err(_) if !SERVER_MESSAGE_TYPES.contains(&kind.as_str()) => Ok(Incoming::Unknown(kind)),
+        Err(e) => Err(format!("无法解析 {} 消息: {}", kind, e)),
+    }
+}
+
+/// 编码一条客户端消息
+pub fn encode(message: ClientMessage, timestamp: f64) -> Result<String, String> {
+    serde_json::to_string(&Envelope { timestamp, message }).map_err(|e| e.to_string())
+}
+
+/// 后端可能发送的消息类型
+const SERVER_MESSAGE_TYPES: &[&str] = &[
+    "connected",
+    "frame_data",
+    "gesture_event",
+    "active_changed",
+    "camera_paused",
+    "config_ack",
+    "input",
+    "pong",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn own_version_is_compatible() {
+        assert_eq!(check_version(PROTOCOL_VERSION), Ok(()));
+        assert!(check_version("").is_err());
+    }
+
+    #[test]
+    fn checks_version_compatibility() {
+        let cases = [
+            // 同一版本与补丁版本
+            ("1.2.0", "1.2.0", true),
+            ("1.2.0", "1.2.7", true),
+            // 主版本号一致时次版本号可以不同
+            ("1.2.0", "1.5.0", true),
+            ("1.2.0", "1.0.3", true),
+            ("1.2.0", "2.2.0", false),
+            ("2.0.0", "1.9.0", false),
+            // 主版本号为 0 时次版本号也须一致
+            ("0.9.0", "0.9.4", true),
+            ("0.9.0", "0.10.0", false),
+            ("0.9.0", "0.8.0", false),
+            ("0.9.0", "1.9.0", false),
+            // 允许首尾空白与缺少补丁版本号
+            ("1.2.0", " 1.2 ", true),
+            // 只比较主次版本号，补丁版本中的预发布标记不影响兼容性
+            ("1.2.0", "1.2.0-beta", true),
+        ];
+        for (host, server, expected) in cases {
+            assert_eq!(
+                compatible(host, server).is_ok(),
+                expected,
+                "{host} / {server}"
+            );
+        }
+        assert_eq!(
+            compatible("1.2.0", "2.0.0").unwrap_err(),
+            "后端协议版本 2.0.0 与宿主 1.2.0 不兼容"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_versions() {
+        for server in ["", "1", "1.", "v1.2.0", "1.x.0", "-1.2.0"] {
+            assert_eq!(
+                compatible("1.2.0", server).unwrap_err(),
+                format!("无法识别后端协议版本: {}", server),
+                "{server:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_known_messages() {
+        let cases = [
+            (
+                r#"{"type":"active_changed","timestamp":12.5,"data":{"active":true}}"#,
+                12.5,
+                ServerMessage::ActiveChanged(ActiveState { active: true }),
+            ),
+            (
+                r#"{"type":"pong","data":{}}"#,
+                0.0,
+                ServerMessage::Pong(Empty {}),
+            ),
+            (
+                r#"{"type":"input","timestamp":1,"data":{"op":"text","text":"hi"}}"#,
+                1.0,
+                ServerMessage::Input(InputCommand::Text {
+                    text: "hi".to_string(),
+                }),
+            ),
+        ];
+        for (text, timestamp, message) in cases {
+            assert_eq!(
+                decode(text).unwrap(),
+                Incoming::Message(Envelope { timestamp, message }),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_types_are_kept() {
+        let text = r#"{"type":"hand_mesh","timestamp":3,"data":{"vertices":[]}}"#;
+        assert_eq!(
+            decode(text).unwrap(),
+            Incoming::Unknown("hand_mesh".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        let cases = [
+            ("{", "无效的消息"),
+            ("[1, 2]", "消息缺少 type 字段"),
+            (r#"{"data":{}}"#, "消息缺少 type 字段"),
+            (r#"{"type":7,"data":{}}"#, "消息缺少 type 字段"),
+            (
+                r#"{"type":"active_changed","data":{"active":"yes"}}"#,
+                "无法解析 active_changed 消息",
+            ),
+            (
+                r#"{"type":"input","data":{"op":"teleport"}}"#,
+                "无法解析 input 消息",
+            ),
+        ];
+        for (text, expected) in cases {
+            let error = decode(text).unwrap_err();
+            assert!(error.starts_with(expected), "{text}: {error}");
+        }
+    }
+
+    #[test]
+    fn known_types_match_server_messages() {
+        // 已知类型缺少内容时须报错，而不是当作较新后端的未知消息
+        for kind in SERVER_MESSAGE_TYPES {
+            let text = format!(r#"{{"type":"{}","data":7}}"#, kind);
+            assert!(decode(&text).is_err(), "{kind}");
+        }
+        let messages = [
+            ServerMessage::ActiveChanged(ActiveState { active: false }),
+            ServerMessage::Pong(Empty {}),
+        ];
+        for message in messages {
+            let value = serde_json::to_value(&message).unwrap();
+            let kind = value["type"].as_str().unwrap();
+            assert!(SERVER_MESSAGE_TYPES.contains(&kind), "{kind}");
+        }
+    }
+
+    #[test]
+    fn encodes_client_messages() {
+        let text = encode(ClientMessage::SetActive(ActiveState { active: true }), 5.0).unwrap();
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"type": "set_active", "timestamp": 5.0, "data": {"active": true}})
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Python 后端管理
+//!
+//! 负责 sidecar 进程的生命周期与崩溃恢复
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod ports;
+#[cfg(target_os = "linux")]
+pub(crate) mod procfs;
+mod readiness;
+mod recovery;
+mod shutdown;
+mod status;
+mod supervisor;
+
+pub use ports::BackendEndpoints;
+pub use recovery::RestartPolicy;
+pub use status::{BackendState, BackendStatus};
+pub use supervisor::BackendSupervisor;
+
+/// 当前 Unix 时间（毫秒），与后端消息的 timestamp 单位一致
+pub(crate) fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
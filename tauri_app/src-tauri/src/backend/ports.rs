@@ -0,0 +1,171 @@
+//! 端口分配
+//!
+//! 启动前为 WebSocket 与 MJPEG 服务挑选空闲端口。
+//! 优先使用设置中的端口，其次复用本次与上次成功的端口，最后由系统分配
+
+use std::fs;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// 后端监听地址
+pub const BACKEND_HOST: &str = "127.0.0.1";
+
+/// 记录上次成功端口的文件名（位于应用数据目录）
+const PORTS_FILE: &str = "backend_ports.json";
+
+/// 后端使用的端口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendPorts {
+    /// WebSocket 端口
+    pub ws: u16,
+    /// MJPEG 视频流端口
+    pub mjpeg: u16,
+}
+
+impl BackendPorts {
+    /// 两个端口当前是否都可用
+    fn is_free(&self) -> bool {
+        self.ws != self.mjpeg && port_is_free(self.ws) && port_is_free(self.mjpeg)
+    }
+
+    /// 对应的访问地址
+    pub fn endpoints(&self) -> BackendEndpoints {
+        BackendEndpoints {
+            ws_url: format!("ws://{}:{}", BACKEND_HOST, self.ws),
+            stream_url: format!("http://{}:{}/stream", BACKEND_HOST, self.mjpeg),
+            ws_port: self.ws,
+            mjpeg_port: self.mjpeg,
+        }
+    }
+}
+
+/// 后端访问地址（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendEndpoints {
+    pub ws_url: String,
+    pub stream_url: String,
+    pub ws_port: u16,
+    pub mjpeg_port: u16,
+}
+
+/// 分配端口：依次尝试设置中的端口、`preferred`、上次成功的端口，均被占用时由系统分配
+pub fn allocate(
+    app_handle: &AppHandle,
+    configured: BackendPorts,
+    preferred: Option<BackendPorts>,
+) -> Result<BackendPorts, String> {
+    let last = ports_file(app_handle).and_then(|path| load(&path));
+    pick([Some(configured), preferred, last])
+}
+
+/// 取第一组空闲的候选端口，均被占用时由系统分配
+fn pick(candidates: [Option<BackendPorts>; 3]) -> Result<BackendPorts, String> {
+    if let Some(ports) = candidates.into_iter().flatten().find(BackendPorts::is_free) {
+        return Ok(ports);
+    }
+
+    // 同时持有两个监听器，保证系统分配的端口不重复
+    let ws = bind_any()?;
+    let mjpeg = bind_any()?;
+    Ok(BackendPorts {
+        ws: local_port(&ws)?,
+        mjpeg: local_port(&mjpeg)?,
+    })
+}
+
+/// 记录成功使用的端口，供下次启动复用
+pub fn remember(app_handle: &AppHandle, ports: BackendPorts) {
+    let Some(path) = ports_file(app_handle) else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, serde_json::to_vec(&ports).unwrap_or_default()));
+    if let Err(e) = result {
+        eprintln!("[Tauri] 无法保存端口记录: {}", e);
+    }
+}
+
+fn ports_file(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path_resolver()
+        .app_data_dir()
+        .map(|dir| dir.join(PORTS_FILE))
+}
+
+fn load(path: &Path) -> Option<BackendPorts> {
+    let content = fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn port_is_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+fn bind_any() -> Result<TcpListener, String> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| format!("无法分配端口: {}", e))
+}
+
+fn local_port(listener: &TcpListener) -> Result<u16, String> {
+    listener
+        .local_addr()
+        .map(|addr| addr.port())
+        .map_err(|e| format!("无法分配端口: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 系统分配的一个端口（随即释放）
+    fn free_port() -> u16 {
+        local_port(&bind_any().unwrap()).unwrap()
+    }
+
+    fn free_ports() -> BackendPorts {
+        let ws = bind_any().unwrap();
+        let mjpeg = bind_any().unwrap();
+        BackendPorts {
+            ws: local_port(&ws).unwrap(),
+            mjpeg: local_port(&mjpeg).unwrap(),
+        }
+    }
+
+    #[test]
+    fn skips_ports_in_use() {
+        let held = bind_any().unwrap();
+        let busy = BackendPorts {
+            ws: local_port(&held).unwrap(),
+            mjpeg: free_port(),
+        };
+        let preferred = free_ports();
+        assert_eq!(pick([Some(busy), Some(preferred), None]), Ok(preferred));
+        assert_eq!(pick([Some(busy), None, Some(preferred)]), Ok(preferred));
+    }
+
+    #[test]
+    fn falls_back_to_system_ports() {
+        let held = bind_any().unwrap();
+        let port = local_port(&held).unwrap();
+        let busy = BackendPorts {
+            ws: port,
+            mjpeg: free_port(),
+        };
+        // 两个服务不能共用一个端口
+        let same = free_port();
+        let shared = BackendPorts {
+            ws: same,
+            mjpeg: same,
+        };
+
+        let ports = pick([Some(busy), Some(shared), None]).unwrap();
+        assert_ne!(ports.ws, ports.mjpeg);
+        assert_ne!(ports.ws, port);
+        assert_ne!(ports.mjpeg, port);
+    }
+}
@@ -0,0 +1,92 @@
+//! Linux /proc 读取
+//!
+//! 进程关闭与资源统计都需要 sidecar 的整个进程树（PyInstaller 单文件模式下
+//! 真正的 Python 进程是引导进程的子进程）
+
+use std::collections::HashMap;
+use std::fs;
+
+/// /proc/<pid>/stat 中用到的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stat {
+    /// 父进程 PID
+    pub ppid: u32,
+    /// 累计 CPU 时间 utime + stime（时钟周期）
+    pub cpu_ticks: u64,
+}
+
+/// 读取进程的 stat，进程不存在时返回空
+pub fn read_stat(pid: u32) -> Option<Stat> {
+    parse_stat(&fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)
+}
+
+fn parse_stat(stat: &str) -> Option<Stat> {
+    // 进程名可能包含空格和括号，从最后一个 ')' 之后开始按字段拆分
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(Stat {
+        ppid: fields.get(1)?.parse().ok()?,
+        cpu_ticks: utime + stime,
+    })
+}
+
+/// 进程及其所有后代进程（根进程在最前），根进程不存在时返回空
+pub fn process_tree(root: u32) -> Vec<(u32, Stat)> {
+    let Some(root_stat) = read_stat(root) else {
+        return Vec::new();
+    };
+
+    // 建立父子关系表
+    let mut children: HashMap<u32, Vec<(u32, Stat)>> = HashMap::new();
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        else {
+            continue;
+        };
+        if let Some(stat) = read_stat(pid) {
+            children.entry(stat.ppid).or_default().push((pid, stat));
+        }
+    }
+
+    let mut tree = vec![(root, root_stat)];
+    let mut next = 0;
+    while let Some(&(pid, _)) = tree.get(next) {
+        if let Some(list) = children.get(&pid) {
+            tree.extend(list.iter().copied());
+        }
+        next += 1;
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stat_with_parentheses_in_name() {
+        let stat = "4242 (python (main) x) S 4200 4242 4200 0 -1 4194560 \
+                    9347 0 0 0 150 25 0 0 20 0 4 0 123456 987654321 2500";
+        assert_eq!(
+            parse_stat(stat),
+            Some(Stat {
+                ppid: 4200,
+                cpu_ticks: 175,
+            })
+        );
+        assert_eq!(parse_stat("4242 (python) S 4200"), None);
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[test]
+    fn tree_starts_with_root() {
+        let pid = std::process::id();
+        let tree = process_tree(pid);
+        assert_eq!(tree.first().map(|(root, _)| *root), Some(pid));
+        assert!(process_tree(u32::MAX).is_empty());
+    }
+}
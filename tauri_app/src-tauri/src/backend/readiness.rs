@@ -0,0 +1,67 @@
+//! 就绪探测
+//!
+//! 后端需要数秒打开摄像头、加载 MediaPipe 才会监听 WebSocket。
+//! 反复尝试连接，收到 `connected` 欢迎消息后才认为后端可用
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use phantom_protocol::{self as protocol, Envelope, Incoming, ServerMessage};
+use tokio::time::{sleep, timeout, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// 两次连接尝试之间的间隔
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 单次连接等待欢迎消息的时间
+const WELCOME_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 等待后端就绪，超时或进程已退出（`is_alive` 返回 false）时返回错误
+pub async fn wait_until_ready(
+    url: &str,
+    deadline: Duration,
+    is_alive: impl Fn() -> bool,
+) -> Result<(), String> {
+    let started = Instant::now();
+    loop {
+        if !is_alive() {
+            return Err("后端进程已退出".to_string());
+        }
+
+        match probe_once(url).await {
+            Ok(()) => return Ok(()),
+            Err(e) if started.elapsed() >= deadline => {
+                return Err(format!("等待后端就绪超时: {}", e));
+            }
+            Err(_) => sleep(RETRY_INTERVAL).await,
+        }
+    }
+}
+
+/// 连接一次并等待 `connected` 欢迎消息
+async fn probe_once(url: &str) -> Result<(), String> {
+    let (mut ws, _) = connect_async(url)
+        .await
+        .map_err(|e| format!("无法连接 {}: {}", url, e))?;
+
+    let result = timeout(WELCOME_TIMEOUT, async {
+        while let Some(message) = ws.next().await {
+            let message = message.map_err(|e| format!("读取消息失败: {}", e))?;
+            if let Message::Text(text) = message {
+                if let Incoming::Message(Envelope {
+                    message: ServerMessage::Connected(_),
+                    ..
+                }) = protocol::decode(&text)?
+                {
+                    return Ok(());
+                }
+            }
+        }
+        Err("连接在收到欢迎消息前关闭".to_string())
+    })
+    .await
+    .unwrap_or_else(|_| Err("等待欢迎消息超时".to_string()));
+
+    let _ = ws.close(None).await;
+    result
+}
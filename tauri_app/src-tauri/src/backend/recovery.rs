@@ -0,0 +1,124 @@
+//! 崩溃恢复策略
+//!
+//! 后端意外退出后按指数退避自动重启，连续失败超过上限时熔断
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// 自动重启策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    /// 是否启用自动重启
+    pub enabled: bool,
+    /// 连续失败的最大重试次数，超过后停止重启
+    pub max_retries: u32,
+    /// 首次重试延迟（毫秒）
+    pub initial_delay_ms: u64,
+    /// 每次重试延迟的倍数
+    pub multiplier: f64,
+    /// 单次重试延迟上限（毫秒）
+    pub max_delay_ms: u64,
+    /// 稳定运行超过此时间（毫秒）后清零失败计数
+    pub reset_after_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_retries: 5,
+            initial_delay_ms: 1000,
+            multiplier: 2.0,
+            max_delay_ms: 30000,
+            reset_after_ms: 60000,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// 校验参数范围
+    pub fn validate(&self) -> Result<(), String> {
+        // 用取反的比较使 NaN 也不能通过
+        if !(self.multiplier >= 1.0 && self.multiplier.is_finite()) {
+            return Err("multiplier 必须是不小于 1 的有限数".to_string());
+        }
+        if self.initial_delay_ms > self.max_delay_ms {
+            return Err("initial_delay_ms 不能大于 max_delay_ms".to_string());
+        }
+        Ok(())
+    }
+
+    /// 第 attempt 次重试（从 1 开始）的延迟
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let delay = self.initial_delay_ms as f64 * self.multiplier.powi(exponent);
+        Duration::from_millis(delay.min(self.max_delay_ms as f64) as u64)
+    }
+}
+
+/// 崩溃后的处理决定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecoveryDecision {
+    /// 延迟后进行第 attempt 次重启
+    Retry { attempt: u32, delay: Duration },
+    /// 连续失败过多，放弃重启
+    GiveUp { failures: u32 },
+    /// 未启用自动重启
+    Disabled,
+}
+
+/// 连续崩溃计数
+#[derive(Debug, Default)]
+pub struct CrashTracker {
+    failures: u32,
+}
+
+impl CrashTracker {
+    /// 记录一次崩溃，uptime 为该进程的运行时长
+    pub fn record_crash(&mut self, policy: &RestartPolicy, uptime: Duration) -> RecoveryDecision {
+        if !policy.enabled {
+            return RecoveryDecision::Disabled;
+        }
+
+        // 稳定运行足够久，视为新一轮故障
+        if uptime >= Duration::from_millis(policy.reset_after_ms) {
+            self.failures = 0;
+        }
+
+        self.failures += 1;
+        if self.failures > policy.max_retries {
+            return RecoveryDecision::GiveUp {
+                failures: self.failures,
+            };
+        }
+
+        RecoveryDecision::Retry {
+            attempt: self.failures,
+            delay: policy.delay_for(self.failures),
+        }
+    }
+
+    /// 清零失败计数（手动启动时调用）
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_invalid_multiplier() {
+        for multiplier in [0.5, f64::NAN, f64::INFINITY] {
+            let policy = RestartPolicy {
+                multiplier,
+                ..RestartPolicy::default()
+            };
+            assert!(policy.validate().is_err(), "multiplier = {multiplier}");
+        }
+        assert!(RestartPolicy::default().validate().is_ok());
+    }
+}
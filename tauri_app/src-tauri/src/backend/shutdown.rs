@@ -0,0 +1,136 @@
+//! 后端关闭
+//!
+//! 优先通过 WebSocket 请求后端自行退出（释放摄像头与按住的鼠标），
+//! 超时后强制结束整个进程树
+
+use std::process::Command as StdCommand;
+
+use futures_util::SinkExt;
+use phantom_protocol::{self as protocol, ClientMessage, Empty};
+use tauri::api::process::CommandChild;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::unix_millis;
+
+/// 连接并发送关闭请求的最长时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 通过 WebSocket 发送关闭请求
+pub async fn request_shutdown(url: &str) -> Result<(), String> {
+    let message = protocol::encode(ClientMessage::Shutdown(Empty {}), unix_millis() as f64)?;
+
+    timeout(REQUEST_TIMEOUT, async {
+        let (mut ws, _) = connect_async(url)
+            .await
+            .map_err(|e| format!("无法连接 {}: {}", url, e))?;
+        ws.send(Message::Text(message))
+            .await
+            .map_err(|e| format!("发送关闭请求失败: {}", e))?;
+        let _ = ws.close(None).await;
+        Ok(())
+    })
+    .await
+    .unwrap_or_else(|_| Err("发送关闭请求超时".to_string()))
+}
+
+/// 强制结束后端进程及其子进程
+///
+/// PyInstaller 单文件模式下 sidecar 是一个引导进程，真正的 Python 进程是它的子进程，
+/// 只结束引导进程会让子进程继续占用摄像头和端口
+pub fn kill_process_tree(child: CommandChild) {
+    #[cfg(windows)]
+    let result = {
+        use std::os::windows::process::CommandExt;
+        let pid = child.pid().to_string();
+        // /T 同时结束子进程；CREATE_NO_WINDOW 避免弹出控制台窗口
+        StdCommand::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid])
+            .creation_flags(0x0800_0000)
+            .status()
+    };
+
+    // 先记下整棵进程树再逐个结束：父进程先退出后，孙进程会被 init 收养而无法再找到
+    #[cfg(not(windows))]
+    let result = {
+        let pids: Vec<String> = descendants(child.pid())
+            .iter()
+            .map(|pid| pid.to_string())
+            .collect();
+        if pids.is_empty() {
+            Ok(Default::default())
+        } else {
+            StdCommand::new("kill").arg("-KILL").args(&pids).status()
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("[Tauri] 无法结束后端子进程: {}", e);
+    }
+
+    // Windows 下根进程可能已被 taskkill 结束，失败仅记录
+    if let Err(e) = child.kill() {
+        eprintln!("[Tauri] 无法结束后端进程: {}", e);
+    }
+}
+
+/// 进程的所有后代进程（不含自身）
+#[cfg(target_os = "linux")]
+fn descendants(root: u32) -> Vec<u32> {
+    super::procfs::process_tree(root)
+        .into_iter()
+        .skip(1)
+        .map(|(pid, _)| pid)
+        .collect()
+}
+
+/// 进程的所有后代进程（不含自身），逐层通过 pgrep -P 查找
+#[cfg(all(unix, not(target_os = "linux")))]
+fn descendants(root: u32) -> Vec<u32> {
+    let mut result = Vec::new();
+    let mut pending = vec![root];
+    while let Some(pid) = pending.pop() {
+        let Ok(output) = StdCommand::new("pgrep")
+            .args(["-P", &pid.to_string()])
+            .output()
+        else {
+            continue;
+        };
+        let children: Vec<u32> = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .filter_map(|pid| pid.parse().ok())
+            .collect();
+        result.extend(children.iter().copied());
+        pending.extend(children);
+    }
+    result
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn descendants_include_grandchildren() {
+        let mut child = StdCommand::new("sh")
+            .args(["-c", "sh -c 'sleep 30' & wait"])
+            .spawn()
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut pids = descendants(child.id());
+        while pids.len() < 2 && Instant::now() < deadline {
+            sleep(Duration::from_millis(20));
+            pids = descendants(child.id());
+        }
+
+        let _ = StdCommand::new("kill")
+            .arg("-KILL")
+            .args(pids.iter().map(|pid| pid.to_string()))
+            .status();
+        let _ = child.kill();
+        let _ = child.wait();
+        assert!(pids.len() >= 2, "{pids:?}");
+    }
+}
@@ -0,0 +1,98 @@
+//! 后端状态模型
+//!
+//! 由进程事件驱动更新，每次状态迁移通过 `backend-status` 事件推送给前端
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::unix_millis;
+use crate::AppState;
+
+/// 保留的最近 stderr 行数
+const STDERR_TAIL_LINES: usize = 20;
+
+/// 后端进程状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendState {
+    /// 正在启动（进程已创建，尚未产生输出）
+    Starting,
+    /// 进程运行中，WebSocket 尚未就绪
+    Running,
+    /// WebSocket 已可连接
+    Ready,
+    /// 进程意外退出
+    Crashed,
+    /// 已停止（主动停止或正常退出）
+    Stopped,
+}
+
+/// 进程退出信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitInfo {
+    /// 退出码
+    pub code: Option<i32>,
+    /// 终止信号（仅 Unix）
+    pub signal: Option<i32>,
+}
+
+/// 后端状态快照
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub state: BackendState,
+    pub pid: Option<u32>,
+    /// 上一次退出信息
+    pub last_exit: Option<ExitInfo>,
+    /// 上一次启动时间（Unix 毫秒）
+    pub last_started_at: Option<u64>,
+    /// 重启次数（不含首次启动）
+    pub restart_count: u32,
+    /// 最近的 stderr 输出
+    pub stderr_tail: VecDeque<String>,
+}
+
+impl BackendStatus {
+    pub fn new() -> Self {
+        Self {
+            state: BackendState::Stopped,
+            pid: None,
+            last_exit: None,
+            last_started_at: None,
+            restart_count: 0,
+            stderr_tail: VecDeque::with_capacity(STDERR_TAIL_LINES),
+        }
+    }
+
+    /// 迁移到新状态并通知前端
+    pub fn transition(&mut self, app_handle: &AppHandle, state: BackendState) {
+        if self.state == state {
+            return;
+        }
+
+        self.state = state;
+        let _ = app_handle.emit_all("backend-status", self.clone());
+        app_handle
+            .state::<AppState>()
+            .tray
+            .update(app_handle, |view| view.backend = state);
+    }
+
+    /// 记录一行 stderr
+    pub fn push_stderr(&mut self, line: String) {
+        if self.stderr_tail.len() == STDERR_TAIL_LINES {
+            self.stderr_tail.pop_front();
+        }
+        self.stderr_tail.push_back(line);
+    }
+
+    /// 记录一次启动
+    pub fn mark_started(&mut self, pid: u32, is_restart: bool) {
+        self.pid = Some(pid);
+        self.last_started_at = Some(unix_millis());
+        if is_restart {
+            self.restart_count += 1;
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! 后端进程监督
+//!
+//! 持有 sidecar 子进程句柄，提供启动、停止、重启操作，
+//! 并根据进程事件维护后端状态，意外退出时按策略自动重启
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::api::process::{Command, CommandChild, CommandEvent, TerminatedPayload};
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+use super::ports::{self, BackendEndpoints, BackendPorts};
+use super::readiness;
+use super::recovery::{CrashTracker, RecoveryDecision, RestartPolicy};
+use super::shutdown;
+use super::status::{BackendState, BackendStatus, ExitInfo};
+use crate::logs::LogSource;
+use crate::AppState;
+
+/// sidecar 名称（对应 tauri.conf.json 中的 externalBin）
+const SIDECAR_NAME: &str = "PhantomHandBackend";
+
+/// 等待后端就绪的最长时间（含摄像头初始化与模型加载）
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 等待旧进程退出的最长时间
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+struct Inner {
+    child: Option<CommandChild>,
+    status: BackendStatus,
+    /// 每次启动递增，用于忽略已被替换的旧进程的事件
+    generation: u64,
+    /// 当前进程退出时收到通知
+    exited: Option<oneshot::Receiver<()>>,
+    /// 当前进程的启动时间
+    started_at: Option<Instant>,
+    /// 当前（或上一次）进程使用的端口
+    ports: Option<BackendPorts>,
+    /// 自动重启策略
+    policy: RestartPolicy,
+    /// 连续崩溃计数
+    crashes: CrashTracker,
+}
+
+/// 后端进程监督器
+pub struct BackendSupervisor {
+    inner: Mutex<Inner>,
+}
+
+impl BackendSupervisor {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                child: None,
+                status: BackendStatus::new(),
+                generation: 0,
+                exited: None,
+                started_at: None,
+                ports: None,
+                policy: RestartPolicy::default(),
+                crashes: CrashTracker::default(),
+            }),
+        }
+    }
+
+    /// 获取当前状态
+    pub fn status(&self) -> BackendStatus {
+        self.inner.lock().unwrap().status.clone()
+    }
+
+    /// 获取后端访问地址
+    pub fn endpoints(&self) -> Option<BackendEndpoints> {
+        self.inner
+            .lock()
+            .unwrap()
+            .ports
+            .map(|ports| ports.endpoints())
+    }
+
+    /// 获取自动重启策略
+    pub fn restart_policy(&self) -> RestartPolicy {
+        self.inner.lock().unwrap().policy.clone()
+    }
+
+    /// 更新自动重启策略
+    pub fn set_restart_policy(&self, policy: RestartPolicy) -> Result<(), String> {
+        policy.validate()?;
+        self.inner.lock().unwrap().policy = policy;
+        Ok(())
+    }
+
+    /// 启动后端（已有进程时返回错误），同时清零崩溃计数
+    pub fn start(&self, app_handle: &AppHandle) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.child.is_some() {
+            return Err("后端已在运行".to_string());
+        }
+
+        inner.crashes.reset();
+        app_handle
+            .state::<AppState>()
+            .tray
+            .update(app_handle, |view| view.failure = None);
+        spawn_process(&mut inner, app_handle)
+    }
+
+    /// 停止后端，并等待进程退出；返回是否结束了运行中的进程
+    pub async fn stop(&self, app_handle: &AppHandle) -> Result<bool, String> {
+        let Some((child, exited)) = self.detach(app_handle) else {
+            return Ok(false);
+        };
+
+        println!("[Tauri] 正在停止后端 (pid={})", child.pid());
+        shutdown::kill_process_tree(child);
+        wait_exited(exited, STOP_TIMEOUT).await?;
+
+        println!("[Tauri] 后端已停止");
+        Ok(true)
+    }
+
+    /// 优雅关闭：请求后端自行退出，超过 grace 仍未退出则强制结束进程树
+    pub async fn shutdown(&self, app_handle: &AppHandle, grace: Duration) -> Result<(), String> {
+        let ws_url = self.endpoints().map(|endpoints| endpoints.ws_url);
+        let Some((child, mut exited)) = self.detach(app_handle) else {
+            return Ok(());
+        };
+
+        println!("[Tauri] 正在关闭后端 (pid={})", child.pid());
+        let requested = match ws_url {
+            Some(url) => shutdown::request_shutdown(&url).await,
+            None => Err("后端地址未知".to_string()),
+        };
+        match requested {
+            Ok(()) => {
+                if timeout(grace, &mut exited).await.is_ok() {
+                    println!("[Tauri] 后端已退出");
+                    return Ok(());
+                }
+                eprintln!("[Tauri] 后端未在 {}ms 内退出，强制结束", grace.as_millis());
+            }
+            Err(e) => eprintln!("[Tauri] {}", e),
+        }
+
+        shutdown::kill_process_tree(child);
+        wait_exited(exited, STOP_TIMEOUT).await
+    }
+
+    /// 取出当前进程句柄并标记为已停止，之后的退出事件不再触发自动重启
+    fn detach(&self, app_handle: &AppHandle) -> Option<(CommandChild, oneshot::Receiver<()>)> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.status.pid = None;
+        inner.status.transition(app_handle, BackendState::Stopped);
+        inner.child.take().zip(inner.exited.take())
+    }
+
+    /// 重启后端：先停止旧进程，再启动新进程
+    pub async fn restart(&self, app_handle: &AppHandle) -> Result<(), String> {
+        self.stop(app_handle).await?;
+        self.start(app_handle)
+    }
+
+    /// 进程产生输出：首次输出时标记为运行中
+    fn on_output(&self, app_handle: &AppHandle, generation: u64, stderr: Option<String>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation {
+            return;
+        }
+
+        if let Some(line) = stderr {
+            inner.status.push_stderr(line);
+        }
+        if inner.status.state == BackendState::Starting {
+            inner.status.transition(app_handle, BackendState::Running);
+        }
+    }
+
+    /// 进程退出事件
+    fn on_terminated(&self, app_handle: &AppHandle, generation: u64, payload: TerminatedPayload) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation {
+            return;
+        }
+
+        inner.status.last_exit = Some(ExitInfo {
+            code: payload.code,
+            signal: payload.signal,
+        });
+        // 主动停止的进程：状态已由 stop() 更新
+        if inner.child.is_none() {
+            return;
+        }
+
+        inner.child = None;
+        inner.status.pid = None;
+        inner.exited = None;
+        if payload.code == Some(0) {
+            inner.status.transition(app_handle, BackendState::Stopped);
+            return;
+        }
+
+        inner.status.transition(app_handle, BackendState::Crashed);
+        let uptime = inner
+            .started_at
+            .map(|started| started.elapsed())
+            .unwrap_or_default();
+        handle_crash(&mut inner, app_handle, uptime);
+    }
+
+    /// 是否仍是该次启动的进程且未退出
+    fn is_alive(&self, generation: u64) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.generation == generation && inner.child.is_some()
+    }
+
+    /// 就绪探测结束
+    fn on_probe_result(&self, app_handle: &AppHandle, generation: u64, result: Result<(), String>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation || inner.child.is_none() {
+            return;
+        }
+
+        match result {
+            Ok(()) => {
+                println!("[Tauri] 后端已就绪");
+                inner.status.transition(app_handle, BackendState::Ready);
+                if let Some(ports) = inner.ports {
+                    ports::remember(app_handle, ports);
+                    let state = app_handle.state::<AppState>();
+                    state.bridge.connect(app_handle, ports.endpoints().ws_url);
+                }
+                let _ = app_handle.emit_all("backend-ready", ());
+            }
+            Err(e) => {
+                eprintln!("[Tauri] {}", e);
+                let _ = app_handle.emit_all("backend-error", e);
+            }
+        }
+    }
+
+    /// 退避延迟结束后自动重启
+    fn auto_restart(&self, app_handle: &AppHandle, generation: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        // 等待期间用户已手动启动或停止
+        if inner.generation != generation || inner.status.state != BackendState::Crashed {
+            return;
+        }
+
+        if let Err(e) = spawn_process(&mut inner, app_handle) {
+            eprintln!("[Tauri] 自动重启失败: {}", e);
+            handle_crash(&mut inner, app_handle, Duration::ZERO);
+        }
+    }
+}
+
+/// 启动 sidecar 进程并监听其输出
+fn spawn_process(inner: &mut Inner, app_handle: &AppHandle) -> Result<(), String> {
+    let settings = &app_handle.state::<AppState>().settings;
+    let configured = settings.read(|settings| BackendPorts {
+        ws: settings.server.port,
+        mjpeg: settings.server.mjpeg_port,
+    });
+    // 后端配置写入失败时使用后端的默认配置
+    let config = settings
+        .write_backend_config()
+        .map_err(|e| eprintln!("[Tauri] {}", e))
+        .ok();
+    let spawned = ports::allocate(app_handle, configured, inner.ports).and_then(|ports| {
+        inner.ports = Some(ports);
+        spawn_sidecar(ports, config.as_deref())
+    });
+    let (rx, child) = match spawned {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            inner.status.transition(app_handle, BackendState::Crashed);
+            return Err(e);
+        }
+    };
+
+    let is_restart = inner.generation > 0;
+    inner.generation += 1;
+    let generation = inner.generation;
+    let (exit_tx, exit_rx) = oneshot::channel();
+
+    let pid = child.pid();
+    inner.child = Some(child);
+    inner.exited = Some(exit_rx);
+    inner.started_at = Some(Instant::now());
+    inner.status.mark_started(pid, is_restart);
+    inner.status.transition(app_handle, BackendState::Starting);
+    println!("[Tauri] 后端进程已创建 (pid={})", pid);
+
+    // 等待 WebSocket 就绪
+    let ws_url = inner
+        .ports
+        .map(|ports| ports.endpoints().ws_url)
+        .unwrap_or_default();
+    let probe_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = readiness::wait_until_ready(&ws_url, READY_TIMEOUT, || {
+            probe_handle
+                .state::<AppState>()
+                .backend
+                .is_alive(generation)
+        })
+        .await;
+        let state = probe_handle.state::<AppState>();
+        state
+            .backend
+            .on_probe_result(&probe_handle, generation, result);
+    });
+
+    // 在后台监听后端输出
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut rx = rx;
+        let mut exit_tx = Some(exit_tx);
+        while let Some(event) = rx.recv().await {
+            let state = app_handle.state::<AppState>();
+            match event {
+                CommandEvent::Stdout(line) => {
+                    state.logs.push(&app_handle, LogSource::Stdout, &line);
+                    state.metrics.on_stdout(&line);
+                    state.backend.on_output(&app_handle, generation, None);
+                }
+                CommandEvent::Stderr(line) => {
+                    state.logs.push(&app_handle, LogSource::Stderr, &line);
+                    state.backend.on_output(&app_handle, generation, Some(line));
+                }
+                CommandEvent::Error(err) => {
+                    eprintln!("[Backend Fatal] {}", err);
+                    let _ = app_handle.emit_all("backend-error", err);
+                }
+                CommandEvent::Terminated(payload) => {
+                    println!("[Backend] 进程退出: {:?}", payload);
+                    state
+                        .backend
+                        .on_terminated(&app_handle, generation, payload);
+                    if let Some(tx) = exit_tx.take() {
+                        let _ = tx.send(());
+                    }
+                    let _ = app_handle.emit_all("backend-stopped", ());
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// 等待进程退出通知
+async fn wait_exited(exited: oneshot::Receiver<()>, limit: Duration) -> Result<(), String> {
+    timeout(limit, exited)
+        .await
+        .map(|_| ())
+        .map_err(|_| "等待后端退出超时".to_string())
+}
+
+/// 以指定端口创建 sidecar 进程
+fn spawn_sidecar(
+    ports: BackendPorts,
+    config: Option<&Path>,
+) -> Result<(Receiver<CommandEvent>, CommandChild), String> {
+    let mut args = vec![
+        "--port".to_string(),
+        ports.ws.to_string(),
+        "--mjpeg-port".to_string(),
+        ports.mjpeg.to_string(),
+    ];
+    if let Some(config) = config {
+        args.push("--config".to_string());
+        args.push(config.to_string_lossy().into_owned());
+    }
+
+    Command::new_sidecar(SIDECAR_NAME)
+        .map_err(|e| format!("无法创建 sidecar: {}", e))?
+        .args(args)
+        // 关闭 Python 输出缓冲，保证日志与状态及时到达
+        .envs(HashMap::from([(
+            "PYTHONUNBUFFERED".to_string(),
+            "1".to_string(),
+        )]))
+        .spawn()
+        .map_err(|e| format!("无法启动后端: {}", e))
+}
+
+/// 按策略处理一次崩溃：安排重启或熔断
+fn handle_crash(inner: &mut Inner, app_handle: &AppHandle, uptime: Duration) {
+    let decision = inner.crashes.record_crash(&inner.policy, uptime);
+    match decision {
+        RecoveryDecision::Retry { attempt, delay } => {
+            println!(
+                "[Tauri] 后端异常退出，{}ms 后进行第 {} 次重启",
+                delay.as_millis(),
+                attempt
+            );
+            let generation = inner.generation;
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let state = app_handle.state::<AppState>();
+                state.backend.auto_restart(&app_handle, generation);
+            });
+        }
+        RecoveryDecision::GiveUp { failures } => {
+            let message = format!("后端连续崩溃 {} 次，已停止自动重启", failures);
+            eprintln!("[Tauri] {}", message);
+            let failure = message.clone();
+            app_handle
+                .state::<AppState>()
+                .tray
+                .update(app_handle, |view| view.failure = Some(failure));
+            let _ = app_handle.emit_all("backend-failed", message);
+        }
+        RecoveryDecision::Disabled => {}
+    }
+}
@@ -0,0 +1,460 @@
+//! 后端 WebSocket 桥接
+//!
+//! Rust 宿主作为后端的 WebSocket 客户端：解码后端消息并转发为 Tauri 事件，
+//! 使托盘、通知与快捷键能感知手势状态。断线后自动重连，并按 `server.heartbeat_interval`
+//! 发送 ping，超过 `server.connection_timeout` 未收到任何消息视为断开。
+//! 消息类型定义在 `phantom-protocol` crate 中
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures_util::{SinkExt, StreamExt};
+use phantom_protocol::{
+    self as protocol, ActionMap, ActiveState, CameraPaused, ClientMessage, ConfigUpdate,
+    DisplayArea, Empty, Envelope, FrameData, HostExecution, Incoming, InputDelegate,
+    PointerCalibration, ServerMessage,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval, sleep, timeout, Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::backend::{unix_millis, BackendState};
+use crate::mapping;
+use crate::settings::{self, SettingsApplied};
+use crate::AppState;
+
+/// 建立连接的最长时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 首次重连延迟
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// 重连延迟上限
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// 桥接状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BridgeStatus {
+    pub connected: bool,
+    pub url: Option<String>,
+    /// 欢迎消息中的后端协议版本
+    pub server_version: Option<String>,
+    /// 协议版本不兼容时的错误，出现后不再重连
+    pub protocol_error: Option<String>,
+    /// 后端的控制激活状态
+    pub active: Option<bool>,
+    /// 后端摄像头是否已暂停
+    pub camera_paused: Option<bool>,
+    /// 重连次数
+    pub reconnects: u32,
+}
+
+struct Inner {
+    status: BridgeStatus,
+    /// 每次 connect 递增，旧连接据此退出
+    generation: u64,
+    /// 当前连接的发送队列
+    outgoing: Option<mpsc::UnboundedSender<String>>,
+    /// 等待下一条 `active_changed` 的调用方
+    active_waiters: Vec<oneshot::Sender<bool>>,
+    /// 上一个 `config_update` 请求 ID
+    last_request_id: u64,
+    /// 等待确认的 `config_update` 及其需要重启才能生效的字段
+    pending_configs: HashMap<u64, Vec<String>>,
+}
+
+/// 后端 WebSocket 客户端
+pub struct BackendBridge {
+    inner: Mutex<Inner>,
+}
+
+impl BackendBridge {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                status: BridgeStatus::default(),
+                generation: 0,
+                outgoing: None,
+                active_waiters: Vec::new(),
+                last_request_id: 0,
+                pending_configs: HashMap::new(),
+            }),
+        }
+    }
+
+    /// 获取当前状态
+    pub fn status(&self) -> BridgeStatus {
+        self.inner.lock().unwrap().status.clone()
+    }
+
+    /// 连接到后端（替换已有连接）
+    pub fn connect(&self, app_handle: &AppHandle, url: String) {
+        let generation = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.generation += 1;
+            inner.outgoing = None;
+            inner.active_waiters.clear();
+            inner.pending_configs.clear();
+            inner.status = BridgeStatus {
+                url: Some(url.clone()),
+                ..BridgeStatus::default()
+            };
+            inner.generation
+        };
+
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(run(app_handle, generation, url));
+    }
+
+    /// 设置后端的控制激活状态
+    pub fn set_active(&self, active: bool) -> Result<(), String> {
+        self.send(ClientMessage::SetActive(ActiveState { active }))
+    }
+
+    /// 停用控制并等待后端确认（后端收到 `set_active` 后总会广播 `active_changed`）
+    pub async fn deactivate(&self, wait: Duration) -> Result<(), String> {
+        let (tx, rx) = oneshot::channel();
+        self.inner.lock().unwrap().active_waiters.push(tx);
+        self.set_active(false)?;
+
+        match timeout(wait, rx).await {
+            Ok(Ok(false)) => Ok(()),
+            Ok(Ok(true)) => Err("后端仍处于激活状态".to_string()),
+            Ok(Err(_)) => Err("连接已断开".to_string()),
+            Err(_) => Err(format!("后端未在 {}ms 内确认停用", wait.as_millis())),
+        }
+    }
+
+    /// 暂停或恢复后端摄像头
+    pub fn set_camera_paused(&self, paused: bool) -> Result<(), String> {
+        self.send(ClientMessage::SetCameraPaused(CameraPaused { paused }))
+    }
+
+    /// 替换后端的动作映射
+    pub fn set_action_map(&self, map: ActionMap) -> Result<(), String> {
+        self.send(ClientMessage::SetActionMap(map))
+    }
+
+    /// 告知后端是否把输入注入交给宿主
+    pub fn set_input_delegate(&self, delegate: InputDelegate) -> Result<(), String> {
+        self.send(ClientMessage::SetInputDelegate(delegate))
+    }
+
+    /// 告知后端是否由宿主执行手势动作
+    pub fn set_host_execution(&self, execution: HostExecution) -> Result<(), String> {
+        self.send(ClientMessage::SetHostExecution(execution))
+    }
+
+    /// 设置后端鼠标绝对定位映射的区域
+    pub fn set_display_area(&self, area: DisplayArea) -> Result<(), String> {
+        self.send(ClientMessage::SetDisplayArea(area))
+    }
+
+    /// 设置后端鼠标映射的校准变换，为空时不校准
+    pub fn set_pointer_calibration(&self, matrix: Option<[f64; 9]>) -> Result<(), String> {
+        self.send(ClientMessage::SetPointerCalibration(PointerCalibration {
+            matrix,
+        }))
+    }
+
+    /// 把配置变化推送给后端，结果通过 `settings-applied` 事件通知，返回请求 ID
+    pub fn update_config(
+        &self,
+        changes: Map<String, Value>,
+        restart_required: Vec<String>,
+    ) -> Result<u64, String> {
+        let request_id = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.last_request_id += 1;
+            let request_id = inner.last_request_id;
+            inner.pending_configs.insert(request_id, restart_required);
+            request_id
+        };
+
+        let sent = self.send(ClientMessage::ConfigUpdate(ConfigUpdate {
+            request_id,
+            changes,
+        }));
+        if let Err(e) = sent {
+            self.inner
+                .lock()
+                .unwrap()
+                .pending_configs
+                .remove(&request_id);
+            return Err(e);
+        }
+        Ok(request_id)
+    }
+
+    /// 发送一条消息
+    fn send(&self, message: ClientMessage) -> Result<(), String> {
+        let text = protocol::encode(message, unix_millis() as f64)?;
+        self.inner
+            .lock()
+            .unwrap()
+            .outgoing
+            .as_ref()
+            .ok_or_else(|| "未连接到后端".to_string())?
+            .send(text)
+            .map_err(|_| "未连接到后端".to_string())
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.inner.lock().unwrap().generation == generation
+    }
+
+    /// 更新状态并推送 `bridge-status` 事件
+    fn update(&self, app_handle: &AppHandle, generation: u64, f: impl FnOnce(&mut Inner)) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation {
+            return;
+        }
+        f(&mut inner);
+        let _ = app_handle.emit_all("bridge-status", inner.status.clone());
+    }
+
+    /// 处理一条后端消息；协议版本不兼容时返回错误
+    fn on_message(
+        &self,
+        app_handle: &AppHandle,
+        generation: u64,
+        message: ServerMessage,
+    ) -> Result<(), String> {
+        match message {
+            ServerMessage::Connected(connected) => {
+                let checked = protocol::check_version(&connected.version);
+                self.update(app_handle, generation, |inner| {
+                    inner.status.server_version = Some(connected.version.clone());
+                    inner.status.active = Some(connected.active);
+                    inner.status.camera_paused = Some(connected.camera_paused);
+                    inner.status.protocol_error = checked.clone().err();
+                });
+                if let Err(e) = checked {
+                    let _ = app_handle.emit_all("backend-error", &e);
+                    return Err(e);
+                }
+
+                let state = app_handle.state::<AppState>();
+                state.tray.update(app_handle, |view| {
+                    view.connected = true;
+                    view.active = connected.active;
+                    view.camera_paused = connected.camera_paused;
+                });
+                // 后端每次启动都使用默认映射且自己注入、执行动作，连接后推送当前手势配置、
+                // 注入与执行方式；后端启动后设置可能又有修改，同时同步一次识别参数
+                state.executor.set_active(app_handle, connected.active);
+                self.set_input_delegate(state.input.delegate())?;
+                self.set_host_execution(state.executor.host_execution())?;
+                if let Some(area) = state.display.area() {
+                    self.set_display_area(area)?;
+                }
+                self.set_pointer_calibration(state.calibration.matrix())?;
+                let current = state.settings.get();
+                let active = state.focus.resolve(&current);
+                self.set_action_map(mapping::action_map(mapping::bindings(
+                    &current,
+                    &active.profile,
+                )))?;
+                self.update_config(settings::live_config(&current), Vec::new())?;
+                let _ = app_handle.emit_all("bridge-connected", connected);
+            }
+            ServerMessage::FrameData(frame) => {
+                let state = app_handle.state::<AppState>();
+                let frame = state.filter.apply(app_handle, frame);
+                state.metrics.record_inference(frame.inference_time_ms);
+                let gesture = dominant_gesture(&frame);
+                state.tray.update(app_handle, |view| view.gesture = gesture);
+                state.calibration.on_frame(app_handle, &frame);
+                state.executor.on_frame(app_handle, &frame);
+                let _ = app_handle.emit_all("bridge-frame", frame);
+            }
+            ServerMessage::GestureEvent(event) => {
+                let state = app_handle.state::<AppState>();
+                state.executor.on_gesture_event(app_handle, &event);
+                mapping::on_gesture_event(app_handle, &event);
+                let _ = app_handle.emit_all("bridge-gesture", event);
+            }
+            ServerMessage::ActiveChanged(changed) => {
+                self.update(app_handle, generation, |inner| {
+                    inner.status.active = Some(changed.active);
+                    for waiter in inner.active_waiters.drain(..) {
+                        let _ = waiter.send(changed.active);
+                    }
+                });
+                let state = app_handle.state::<AppState>();
+                state.executor.set_active(app_handle, changed.active);
+                state
+                    .tray
+                    .update(app_handle, |view| view.active = changed.active);
+                let _ = app_handle.emit_all("bridge-active-changed", changed);
+            }
+            ServerMessage::CameraPaused(paused) => {
+                self.update(app_handle, generation, |inner| {
+                    inner.status.camera_paused = Some(paused.paused);
+                });
+                let state = app_handle.state::<AppState>();
+                state.tray.update(app_handle, |view| {
+                    view.camera_paused = paused.paused;
+                    view.gesture = None;
+                });
+                let _ = app_handle.emit_all("bridge-camera-paused", paused);
+            }
+            ServerMessage::ConfigAck(ack) => {
+                let restart_required = self
+                    .inner
+                    .lock()
+                    .unwrap()
+                    .pending_configs
+                    .remove(&ack.request_id)
+                    .unwrap_or_default();
+                if let Some(e) = &ack.error {
+                    eprintln!("[Tauri] 后端拒绝配置更新: {}", e);
+                }
+                let applied = SettingsApplied {
+                    request_id: Some(ack.request_id),
+                    applied: ack.applied,
+                    restart_required,
+                    deferred: false,
+                    error: ack.error,
+                };
+                let _ = app_handle.emit_all("settings-applied", applied);
+            }
+            ServerMessage::Input(command) => {
+                let state = app_handle.state::<AppState>();
+                if let Err(e) = state.input.execute(&state.display.map(&command)) {
+                    eprintln!("[Tauri] 输入注入失败: {}", e);
+                }
+            }
+            // 仅用于保活
+            ServerMessage::Pong(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// 后端仍在运行、连接未被替换且协议兼容时持续重连
+fn should_run(app_handle: &AppHandle, generation: u64) -> bool {
+    let state = app_handle.state::<AppState>();
+    state.bridge.is_current(generation)
+        && state.bridge.status().protocol_error.is_none()
+        && matches!(
+            state.backend.status().state,
+            BackendState::Running | BackendState::Ready
+        )
+}
+
+async fn run(app_handle: AppHandle, generation: u64, url: String) {
+    let mut delay = RECONNECT_INITIAL_DELAY;
+    while should_run(&app_handle, generation) {
+        if let Err(e) = session(&app_handle, generation, &url, &mut delay).await {
+            eprintln!("[Tauri] 后端连接中断: {}", e);
+        }
+
+        let state = app_handle.state::<AppState>();
+        state.bridge.update(&app_handle, generation, |inner| {
+            inner.outgoing = None;
+            inner.active_waiters.clear();
+            inner.pending_configs.clear();
+            inner.status.connected = false;
+        });
+        if state.bridge.is_current(generation) {
+            state.tray.update(&app_handle, |view| {
+                view.connected = false;
+                view.gesture = None;
+            });
+            // 后端断开时可能还没来得及松开委托按下的鼠标按键
+            state.executor.reset(&app_handle);
+            state.input.release_all();
+        }
+        if !should_run(&app_handle, generation) {
+            break;
+        }
+
+        sleep(delay).await;
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+        state.bridge.update(&app_handle, generation, |inner| {
+            inner.status.reconnects += 1;
+        });
+    }
+}
+
+/// 一次连接：收发消息直到断开
+async fn session(
+    app_handle: &AppHandle,
+    generation: u64,
+    url: &str,
+    delay: &mut Duration,
+) -> Result<(), String> {
+    let (ws, _) = timeout(CONNECT_TIMEOUT, connect_async(url))
+        .await
+        .map_err(|_| format!("连接 {} 超时", url))?
+        .map_err(|e| format!("无法连接 {}: {}", url, e))?;
+    let (mut sink, mut stream) = ws.split();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let state = app_handle.state::<AppState>();
+    state.bridge.update(app_handle, generation, |inner| {
+        inner.outgoing = Some(tx);
+        inner.status.connected = true;
+    });
+    println!("[Tauri] 已连接后端 WebSocket: {}", url);
+    *delay = RECONNECT_INITIAL_DELAY;
+
+    // 每次连接时读取，修改后在下次重连时生效
+    let server = state.settings.get().server;
+    let heartbeat_timeout = Duration::from_millis(server.connection_timeout.into());
+    let mut heartbeat = interval(Duration::from_millis(server.heartbeat_interval.into()));
+    let mut last_seen = Instant::now();
+    loop {
+        tokio::select! {
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    last_seen = Instant::now();
+                    match protocol::decode(&text) {
+                        Ok(Incoming::Message(Envelope { message, .. })) => {
+                            state.bridge.on_message(app_handle, generation, message)?;
+                        }
+                        Ok(Incoming::Unknown(kind)) => {
+                            println!("[Tauri] 忽略未知消息类型: {}", kind);
+                        }
+                        Err(e) => eprintln!("[Tauri] {}", e),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => last_seen = Instant::now(),
+                Some(Err(e)) => return Err(e.to_string()),
+            },
+            Some(text) = rx.recv() => {
+                sink.send(Message::Text(text))
+                    .await
+                    .map_err(|e| format!("发送失败: {}", e))?;
+            }
+            _ = heartbeat.tick() => {
+                if !state.bridge.is_current(generation) {
+                    let _ = sink.close().await;
+                    return Ok(());
+                }
+                if last_seen.elapsed() > heartbeat_timeout {
+                    return Err("心跳超时".to_string());
+                }
+                let ping = protocol::encode(ClientMessage::Ping(Empty {}), unix_millis() as f64)?;
+                sink.send(Message::Text(ping))
+                    .await
+                    .map_err(|e| format!("发送心跳失败: {}", e))?;
+            }
+        }
+    }
+}
+
+/// 置信度最高的手势（忽略 idle）
+fn dominant_gesture(frame: &FrameData) -> Option<String> {
+    frame
+        .hands
+        .iter()
+        .filter(|hand| hand.gesture != "idle")
+        .max_by(|a, b| a.gesture_score.total_cmp(&b.gesture_score))
+        .map(|hand| hand.gesture.clone())
+}
@@ -0,0 +1,177 @@
+//! 四点单应变换
+//!
+//! 由四组对应点求解 3x3 单应矩阵（`h33` 固定为 1，解 8 元线性方程组），
+//! 矩阵按行排列为 9 个元素，与协议中的 `PointerCalibration` 一致
+
+/// 按行排列的 3x3 矩阵
+pub type Matrix = [f64; 9];
+
+/// 主元小于该值时视为方程组奇异
+const EPSILON: f64 = 1e-12;
+
+/// 求把 `from` 中的四个点依次变换到 `to` 的单应矩阵，点共线等退化情况返回空
+pub fn solve(from: &[(f64, f64); 4], to: &[(f64, f64); 4]) -> Option<Matrix> {
+    // 每组对应点给出两个方程：
+    // u = (h0 x + h1 y + h2) / (h6 x + h7 y + 1)
+    // v = (h3 x + h4 y + h5) / (h6 x + h7 y + 1)
+    let mut rows = [[0.0; 9]; 8];
+    for (i, (&(x, y), &(u, v))) in from.iter().zip(to).enumerate() {
+        rows[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+        rows[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+    }
+
+    // 列主元高斯消元
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))?;
+        if rows[pivot][col].abs() < EPSILON {
+            return None;
+        }
+        rows.swap(col, pivot);
+        let pivot = rows[col];
+        for (index, row) in rows.iter_mut().enumerate() {
+            let factor = row[col] / pivot[col];
+            if index == col || factor == 0.0 {
+                continue;
+            }
+            for (value, p) in row[col..].iter_mut().zip(&pivot[col..]) {
+                *value -= factor * p;
+            }
+        }
+    }
+
+    let mut matrix = [0.0; 9];
+    for (i, row) in rows.iter().enumerate() {
+        matrix[i] = row[8] / row[i];
+    }
+    matrix[8] = 1.0;
+    matrix.iter().all(|v| v.is_finite()).then_some(matrix)
+}
+
+/// 变换一个点，点在无穷远处时返回空
+pub fn apply(matrix: &Matrix, (x, y): (f64, f64)) -> Option<(f64, f64)> {
+    let w = matrix[6] * x + matrix[7] * y + matrix[8];
+    if w.abs() < EPSILON {
+        return None;
+    }
+    Some((
+        (matrix[0] * x + matrix[1] * y + matrix[2]) / w,
+        (matrix[3] * x + matrix[4] * y + matrix[5]) / w,
+    ))
+}
+
+/// 四个点是否依次围成凸四边形（顺时针或逆时针均可）
+pub fn is_convex(points: &[(f64, f64); 4]) -> bool {
+    let cross = |i: usize| {
+        let (a, b, c) = (points[i], points[(i + 1) % 4], points[(i + 2) % 4]);
+        (b.0 - a.0) * (c.1 - b.1) - (b.1 - a.1) * (c.0 - b.0)
+    };
+    let signs: Vec<f64> = (0..4).map(cross).collect();
+    signs.iter().all(|&s| s > 0.0) || signs.iter().all(|&s| s < 0.0)
+}
+
+/// 四边形的面积
+pub fn area(points: &[(f64, f64); 4]) -> f64 {
+    let twice: f64 = (0..4)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % 4]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    twice.abs() / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: [(f64, f64); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+    /// 相机画面中常见的透视四边形
+    const QUAD: [(f64, f64); 4] = [(0.12, 0.2), (0.86, 0.14), (0.93, 0.81), (0.08, 0.9)];
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        let error = (actual.0 - expected.0)
+            .abs()
+            .max((actual.1 - expected.1).abs());
+        assert!(error < 1e-9, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn solved_matrix_maps_corners_exactly() {
+        for (from, to) in [(QUAD, UNIT), (UNIT, QUAD), (UNIT, UNIT)] {
+            let matrix = solve(&from, &to).unwrap();
+            for (&point, &expected) in from.iter().zip(&to) {
+                assert_close(apply(&matrix, point).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn identity_for_identical_points() {
+        let matrix = solve(&QUAD, &QUAD).unwrap();
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        for (value, expected) in matrix.iter().zip(identity) {
+            assert!((value - expected).abs() < 1e-9, "{matrix:?}");
+        }
+        assert_close(apply(&matrix, (0.5, 0.5)).unwrap(), (0.5, 0.5));
+    }
+
+    #[test]
+    fn degenerate_points_have_no_solution() {
+        let cases = [
+            // 四点共线
+            [(0.0, 0.0), (0.25, 0.25), (0.5, 0.5), (1.0, 1.0)],
+            // 三点共线
+            [(0.0, 0.0), (0.5, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            // 重复的点
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            [(0.3, 0.3); 4],
+        ];
+        for from in cases {
+            assert_eq!(solve(&from, &UNIT), None, "{from:?}");
+        }
+    }
+
+    #[test]
+    fn points_at_infinity_are_rejected() {
+        // w = 1 - x，x = 1 的点落在无穷远处
+        let matrix = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 1.0];
+        assert_eq!(apply(&matrix, (1.0, 0.5)), None);
+        assert_close(apply(&matrix, (0.5, 0.5)).unwrap(), (1.0, 1.0));
+    }
+
+    #[test]
+    fn convexity_accepts_both_orientations() {
+        let mut reversed = QUAD;
+        reversed.reverse();
+        assert!(is_convex(&UNIT));
+        assert!(is_convex(&QUAD));
+        assert!(is_convex(&reversed));
+    }
+
+    #[test]
+    fn convexity_rejects_bow_tie_and_concave() {
+        let cases = [
+            // 交换后两个角，边相交成蝴蝶结
+            [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)],
+            // 一个角凹进内部
+            [(0.0, 0.0), (1.0, 0.0), (0.3, 0.3), (0.0, 1.0)],
+            // 三点共线
+            [(0.0, 0.0), (0.5, 0.0), (1.0, 0.0), (0.0, 1.0)],
+        ];
+        for points in cases {
+            assert!(!is_convex(&points), "{points:?}");
+        }
+    }
+
+    #[test]
+    fn area_is_independent_of_orientation() {
+        let mut reversed = UNIT;
+        reversed.reverse();
+        assert_eq!(area(&UNIT), 1.0);
+        assert_eq!(area(&reversed), 1.0);
+        // 一个角落在边上时退化为三角形
+        let triangle = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (0.0, 2.0)];
+        assert_eq!(area(&triangle), 2.0);
+    }
+}
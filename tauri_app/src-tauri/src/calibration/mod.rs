@@ -0,0 +1,518 @@
+//! 鼠标映射校准
+//!
+//! 未校准时整个摄像头画面映射到屏幕，手需要伸到画面边缘才能到达屏幕角落。
+//! 校准时依次在映射区域（见 [`crate::display`]）中显示四个目标点，用户用食指指向目标并保持不动，
+//! 停留足够时间后自动记录指尖位置（也可通过 `capture_calibration_point` 手动记录），
+//! 四个点都记录后求出从画面到映射区域的单应变换（见 [`homography`]）。
+//!
+//! 校准按摄像头、显示器布局与映射的显示器分别保存在配置目录的 `calibration.json` 中；
+//! 当前生效的变换同步给后端（`set_pointer_calibration`），宿主执行动作时同样先做变换
+
+mod homography;
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use phantom_protocol::{DisplayArea, FrameData};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+pub use homography::Matrix;
+
+use crate::backend::unix_millis;
+use crate::display::DisplayStatus;
+use crate::executor::INDEX_TIP;
+use crate::settings::{self, Settings};
+use crate::AppState;
+
+/// 校准文件名
+const FILE_NAME: &str = "calibration.json";
+
+/// 目标点在映射区域内的位置（左上、右上、右下、左下），留出边距便于指向
+const TARGETS: [(f64, f64); 4] = [(0.1, 0.1), (0.9, 0.1), (0.9, 0.9), (0.1, 0.9)];
+
+/// 指尖保持不动多久后自动记录
+const DWELL: Duration = Duration::from_millis(1200);
+
+/// 记录一个点至少需要的帧数
+const MIN_SAMPLES: usize = 8;
+
+/// 视为不动的最大偏移（画面归一化坐标）
+const MAX_SPREAD: f64 = 0.015;
+
+/// 与已记录的点的最小距离，避免刚记录完就把同一位置记为下一个点
+const MIN_SEPARATION: f64 = 0.05;
+
+/// 四个点围成的最小面积（占画面的比例）
+const MIN_AREA: f64 = 0.005;
+
+/// 校准对应的摄像头与显示器
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalibrationKey {
+    /// 摄像头设备号、分辨率与是否镜像，如 `0 1280x720 mirror`
+    pub camera: String,
+    /// 显示器布局：所有显示器的名称、尺寸与位置
+    pub layout: String,
+    /// 映射的显示器，映射到整个桌面时为空
+    pub monitor: Option<String>,
+}
+
+/// 保存的校准
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calibration {
+    #[serde(flatten)]
+    pub key: CalibrationKey,
+    /// 从画面归一化位置到映射区域归一化位置的单应矩阵（按行排列）
+    pub matrix: Matrix,
+    /// 记录的指尖位置，与目标点依次对应
+    pub points: Vec<[f64; 2]>,
+    /// 校准时间（Unix 毫秒）
+    pub created: u64,
+}
+
+/// 校准目标点
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationTarget {
+    /// 映射区域内的归一化位置
+    pub x: f64,
+    pub y: f64,
+    /// 桌面上的像素位置
+    pub screen_x: i32,
+    pub screen_y: i32,
+}
+
+/// 进行中的校准
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStatus {
+    pub targets: Vec<CalibrationTarget>,
+    /// 已记录的点数（即当前目标的序号）
+    pub captured: usize,
+    /// 当前目标的停留进度（0-1）
+    pub dwell: f64,
+    /// 最近一帧的指尖位置（画面归一化坐标），没有检测到手时为空
+    pub fingertip: Option<[f64; 2]>,
+}
+
+/// 校准状态（`get_calibration_status` 命令与 `calibration-changed` 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationStatus {
+    /// 当前的摄像头与显示器，显示器尚未枚举时为空
+    pub key: Option<CalibrationKey>,
+    /// 当前是否有生效的校准
+    pub calibrated: bool,
+    pub session: Option<SessionStatus>,
+    /// 最近一次校准失败的原因
+    pub error: Option<String>,
+    /// 所有保存的校准
+    pub saved: Vec<Calibration>,
+}
+
+struct Session {
+    key: CalibrationKey,
+    area: DisplayArea,
+    points: Vec<(f64, f64)>,
+    /// 指尖保持不动以来的位置，检测不到手时清空
+    samples: VecDeque<(Instant, (f64, f64))>,
+    fingertip: Option<(f64, f64)>,
+}
+
+struct Inner {
+    key: Option<CalibrationKey>,
+    area: Option<DisplayArea>,
+    /// 当前生效的变换
+    matrix: Option<Matrix>,
+    session: Option<Session>,
+    error: Option<String>,
+    saved: Vec<Calibration>,
+    /// 配置目录，未初始化时为空（只保存在内存中）
+    dir: Option<PathBuf>,
+}
+
+/// 鼠标映射校准
+pub struct CalibrationStore {
+    inner: Mutex<Inner>,
+}
+
+impl CalibrationStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                key: None,
+                area: None,
+                matrix: None,
+                session: None,
+                error: None,
+                saved: Vec::new(),
+                dir: None,
+            }),
+        }
+    }
+
+    /// 读取保存的校准（启动时调用）
+    pub fn init(&self, dir: &Path) {
+        let path = dir.join(FILE_NAME);
+        let saved = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                eprintln!("[Tauri] 校准文件无效: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.saved = saved;
+        inner.dir = Some(dir.to_path_buf());
+    }
+
+    pub fn status(&self) -> CalibrationStatus {
+        status(&self.inner.lock().unwrap())
+    }
+
+    /// 当前生效的变换（连接后端时推送）
+    pub fn matrix(&self) -> Option<Matrix> {
+        self.inner.lock().unwrap().matrix
+    }
+
+    /// 用当前生效的校准变换指尖位置，返回变换后的位置与是否已校准
+    pub fn transform(&self, pos: (f64, f64)) -> Option<((f64, f64), bool)> {
+        match self.matrix() {
+            Some(matrix) => homography::apply(&matrix, pos).map(|pos| (pos, true)),
+            None => Some((pos, false)),
+        }
+    }
+
+    /// 按当前的摄像头设置与映射区域选择生效的校准（摄像头设置或映射区域变化后调用）；
+    /// 变化时取消进行中的校准
+    pub fn refresh(&self, app_handle: &AppHandle) {
+        let state = app_handle.state::<AppState>();
+        let current = current(&state.settings.get(), &state.display.status());
+        let (key, area) = current.unzip();
+        self.update(app_handle, |inner| {
+            if inner.key != key && inner.session.take().is_some() {
+                eprintln!("[Tauri] 摄像头或显示器已变化，校准已取消");
+                inner.error = Some("摄像头或显示器已变化，校准已取消".to_string());
+            }
+            inner.key = key;
+            inner.area = area;
+        });
+    }
+
+    /// 开始校准当前的摄像头与映射区域（已有的校准在完成后才被替换）
+    pub fn start(&self, app_handle: &AppHandle) -> Result<(), String> {
+        self.refresh(app_handle);
+        self.update(app_handle, |inner| {
+            let (Some(key), Some(area)) = (inner.key.clone(), inner.area) else {
+                return Err("无法确定鼠标映射区域，请等待显示器枚举完成".to_string());
+            };
+            println!("[Tauri] 开始校准鼠标映射");
+            inner.error = None;
+            inner.session = Some(Session {
+                key,
+                area,
+                points: Vec::new(),
+                samples: VecDeque::new(),
+                fingertip: None,
+            });
+            Ok(())
+        })
+    }
+
+    /// 立即记录当前目标（不等待停留时间）
+    pub fn capture(&self, app_handle: &AppHandle) -> Result<(), String> {
+        self.update(app_handle, |inner| {
+            let session = inner
+                .session
+                .as_ref()
+                .ok_or_else(|| "没有进行中的校准".to_string())?;
+            if session.samples.len() < MIN_SAMPLES {
+                return Err("没有检测到稳定的指尖，请将食指对准目标并保持不动".to_string());
+            }
+            let point = mean(&session.samples);
+            if !separated(&session.points, point) {
+                return Err("指尖离上一个目标太近，请指向当前目标".to_string());
+            }
+            record(inner, point);
+            Ok(())
+        })
+    }
+
+    pub fn cancel(&self, app_handle: &AppHandle) {
+        self.update(app_handle, |inner| {
+            if inner.session.take().is_some() {
+                println!("[Tauri] 已取消校准");
+            }
+        });
+    }
+
+    /// 删除当前摄像头与映射区域的校准
+    pub fn clear(&self, app_handle: &AppHandle) -> Result<(), String> {
+        self.update(app_handle, |inner| {
+            let Some(key) = inner.key.clone() else {
+                return Ok(());
+            };
+            let count = inner.saved.len();
+            inner.saved.retain(|calibration| calibration.key != key);
+            if inner.saved.len() == count {
+                return Ok(());
+            }
+            println!("[Tauri] 已删除当前摄像头与显示器的校准");
+            save(inner)
+        })
+    }
+
+    /// 处理一帧检测结果：校准时记录第一只手的食指指尖，保持不动足够时间后记录当前目标
+    pub fn on_frame(&self, app_handle: &AppHandle, frame: &FrameData) {
+        if self.inner.lock().unwrap().session.is_none() {
+            return;
+        }
+        let tip = frame
+            .hands
+            .first()
+            .and_then(|hand| hand.landmarks.get(INDEX_TIP))
+            .map(|tip| (tip[0], tip[1]));
+
+        self.update(app_handle, |inner| {
+            let Some(session) = inner.session.as_mut() else {
+                return;
+            };
+            let now = Instant::now();
+            session.fingertip = tip;
+            let Some(tip) = tip else {
+                session.samples.clear();
+                return;
+            };
+
+            // 只保留与当前位置足够接近的连续样本，最早的一个样本标记开始停留的时间
+            session.samples.push_back((now, tip));
+            while session
+                .samples
+                .front()
+                .is_some_and(|&(_, pos)| distance(pos, tip) > MAX_SPREAD)
+            {
+                session.samples.pop_front();
+            }
+            while session
+                .samples
+                .get(1)
+                .is_some_and(|&(time, _)| now - time >= DWELL)
+            {
+                session.samples.pop_front();
+            }
+
+            if dwell(session, now) >= 1.0 {
+                let point = mean(&session.samples);
+                if separated(&session.points, point) {
+                    record(inner, point);
+                }
+            }
+        });
+    }
+
+    /// 修改状态并重新选择生效的校准；推送 `calibration-changed` 事件，变换变化时同步给后端
+    fn update<T>(&self, app_handle: &AppHandle, f: impl FnOnce(&mut Inner) -> T) -> T {
+        let (result, status, changed) = {
+            let mut inner = self.inner.lock().unwrap();
+            let previous = inner.matrix;
+            let result = f(&mut inner);
+            let matrix = inner.key.as_ref().and_then(|key| {
+                inner
+                    .saved
+                    .iter()
+                    .find(|calibration| &calibration.key == key)
+                    .map(|calibration| calibration.matrix)
+            });
+            inner.matrix = matrix;
+
+            let changed = inner.matrix != previous;
+            if changed {
+                match (&inner.matrix, &inner.key) {
+                    (Some(_), Some(key)) => println!(
+                        "[Tauri] 已启用鼠标映射校准: {}",
+                        key.monitor.as_deref().unwrap_or("整个桌面")
+                    ),
+                    _ => println!("[Tauri] 当前摄像头与显示器没有校准，按整个画面映射"),
+                }
+            }
+            (result, status(&inner), changed.then_some(inner.matrix))
+        };
+
+        let _ = app_handle.emit_all("calibration-changed", status);
+        let state = app_handle.state::<AppState>();
+        if let (Some(matrix), true) = (changed, state.bridge.status().connected) {
+            if let Err(e) = state.bridge.set_pointer_calibration(matrix) {
+                eprintln!("[Tauri] 无法推送鼠标映射校准: {}", e);
+            }
+        }
+        result
+    }
+}
+
+/// 记录当前目标的指尖位置，四个点都记录后完成校准
+fn record(inner: &mut Inner, point: (f64, f64)) {
+    let Some(session) = inner.session.as_mut() else {
+        return;
+    };
+    session.points.push(point);
+    session.samples.clear();
+    println!(
+        "[Tauri] 已记录校准点 {}/{}: ({:.3}, {:.3})",
+        session.points.len(),
+        TARGETS.len(),
+        point.0,
+        point.1
+    );
+    if session.points.len() < TARGETS.len() {
+        return;
+    }
+
+    let Some(session) = inner.session.take() else {
+        return;
+    };
+    let points = [
+        session.points[0],
+        session.points[1],
+        session.points[2],
+        session.points[3],
+    ];
+    match solve(&points) {
+        Ok(matrix) => {
+            let calibration = Calibration {
+                key: session.key,
+                matrix,
+                points: points.iter().map(|&(x, y)| [x, y]).collect(),
+                created: unix_millis(),
+            };
+            inner.saved.retain(|saved| saved.key != calibration.key);
+            inner.saved.push(calibration);
+            println!("[Tauri] 校准完成");
+            inner.error = save(inner).err();
+            if let Some(e) = &inner.error {
+                eprintln!("[Tauri] {}", e);
+            }
+        }
+        Err(e) => {
+            // 从第一个目标重新开始
+            eprintln!("[Tauri] 校准失败: {}", e);
+            inner.error = Some(e);
+            inner.session = Some(Session {
+                points: Vec::new(),
+                ..session
+            });
+        }
+    }
+}
+
+/// 求解并检查四个点得到的变换
+fn solve(points: &[(f64, f64); 4]) -> Result<Matrix, String> {
+    if !homography::is_convex(points) {
+        return Err("四个点没有围成凸四边形，请按顺序指向目标".to_string());
+    }
+    if homography::area(points) < MIN_AREA {
+        return Err("指向各个目标时手指移动的范围太小，请加大动作".to_string());
+    }
+    homography::solve(points, &TARGETS).ok_or_else(|| "无法求解校准变换".to_string())
+}
+
+/// 当前目标的停留进度
+fn dwell(session: &Session, now: Instant) -> f64 {
+    let Some(&(since, _)) = session.samples.front() else {
+        return 0.0;
+    };
+    if session.samples.len() < MIN_SAMPLES {
+        return 0.0;
+    }
+    ((now - since).as_secs_f64() / DWELL.as_secs_f64()).min(1.0)
+}
+
+fn mean(samples: &VecDeque<(Instant, (f64, f64))>) -> (f64, f64) {
+    let count = samples.len().max(1) as f64;
+    let (x, y) = samples
+        .iter()
+        .fold((0.0, 0.0), |(x, y), (_, pos)| (x + pos.0, y + pos.1));
+    (x / count, y / count)
+}
+
+fn separated(points: &[(f64, f64)], point: (f64, f64)) -> bool {
+    points
+        .iter()
+        .all(|&other| distance(other, point) >= MIN_SEPARATION)
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// 当前的摄像头与映射区域
+fn current(settings: &Settings, display: &DisplayStatus) -> Option<(CalibrationKey, DisplayArea)> {
+    let target = display.target.as_ref()?;
+    let camera = &settings.camera;
+    let mut layout: Vec<_> = display
+        .monitors
+        .iter()
+        .map(|monitor| {
+            let area = monitor.area;
+            format!(
+                "{} {}x{}{:+}{:+}",
+                monitor.name, area.width, area.height, area.x, area.y
+            )
+        })
+        .collect();
+    layout.sort();
+
+    let key = CalibrationKey {
+        camera: format!(
+            "{} {}x{}{}",
+            camera.device_id,
+            camera.width,
+            camera.height,
+            if camera.mirror { " mirror" } else { "" }
+        ),
+        layout: layout.join(", "),
+        monitor: target.monitor.clone(),
+    };
+    Some((key, target.area))
+}
+
+fn status(inner: &Inner) -> CalibrationStatus {
+    let now = Instant::now();
+    let session = inner.session.as_ref().map(|session| {
+        let area = session.area;
+        let pixel = |value: f64, start: i32, size: u32| {
+            start + (value * f64::from(size.max(1) - 1)).round() as i32
+        };
+        SessionStatus {
+            targets: TARGETS
+                .iter()
+                .map(|&(x, y)| CalibrationTarget {
+                    x,
+                    y,
+                    screen_x: pixel(x, area.x, area.width),
+                    screen_y: pixel(y, area.y, area.height),
+                })
+                .collect(),
+            captured: session.points.len(),
+            dwell: dwell(session, now),
+            fingertip: session.fingertip.map(|(x, y)| [x, y]),
+        }
+    });
+    CalibrationStatus {
+        key: inner.key.clone(),
+        calibrated: inner.matrix.is_some(),
+        session,
+        error: inner.error.clone(),
+        saved: inner.saved.clone(),
+    }
+}
+
+fn save(inner: &Inner) -> Result<(), String> {
+    let Some(dir) = &inner.dir else {
+        return Ok(());
+    };
+    let path = dir.join(FILE_NAME);
+    let text = serde_json::to_string_pretty(&inner.saved).map_err(|e| e.to_string())?;
+    settings::write_atomic(&path, text.as_bytes())
+        .map_err(|e| format!("无法保存校准 {}: {}", path.display(), e))
+}
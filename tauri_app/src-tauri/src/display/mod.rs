@@ -0,0 +1,606 @@
+//! 显示器拓扑与鼠标映射区域
+//!
+//! 枚举所有显示器的位置、尺寸与缩放比例（Tauri 的显示器接口；Linux 上优先使用 X11 RandR，
+//! 见 [`x11`]），按 `host.display` 决定鼠标绝对定位映射到哪个区域：整个虚拟桌面、
+//! 指针所在的显示器、前台窗口所在的显示器或指定的显示器。
+//! 后台线程监听热插拔与指针、前台窗口的移动，区域变化时推送给后端（`set_display_area`），
+//! 由宿主注入的绝对移动在这里换算到整个桌面（见 [`DisplayTopology::map`]）
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use phantom_protocol::{DisplayArea, InputCommand};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// 监听线程的刷新间隔
+const TICK: Duration = Duration::from_millis(250);
+
+/// 没有布局变化事件时，每隔多少次刷新重新枚举一次显示器
+const LAYOUT_POLL_TICKS: u32 = 8;
+
+/// 鼠标映射方式（`host.display.mode`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    /// 整个虚拟桌面
+    #[default]
+    Desktop,
+    /// 指针所在的显示器
+    UnderCursor,
+    /// 前台窗口所在的显示器
+    FocusedWindow,
+    /// `host.display.monitor` 指定的显示器
+    Monitor,
+}
+
+/// 鼠标映射设置（`host.display`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+    /// 指定的显示器名称；不存在（如已拔出）时映射到主显示器
+    pub monitor: Option<String>,
+}
+
+impl DisplaySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let empty = self
+            .monitor
+            .as_deref()
+            .is_none_or(|name| name.trim().is_empty());
+        if self.mode == DisplayMode::Monitor && empty {
+            return Err("monitor: 映射到指定显示器时不能为空".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 一个显示器
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Monitor {
+    pub name: String,
+    /// 在虚拟桌面上的位置与尺寸（物理像素）
+    #[serde(flatten)]
+    pub area: DisplayArea,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+/// 当前的映射区域
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayTarget {
+    /// 目标显示器，映射到整个桌面时为空
+    pub monitor: Option<String>,
+    pub area: DisplayArea,
+}
+
+/// 显示器状态（`get_display_status` 命令与 `display-changed` 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayStatus {
+    pub monitors: Vec<Monitor>,
+    /// 所有显示器的外接矩形
+    pub desktop: Option<DisplayArea>,
+    pub target: Option<DisplayTarget>,
+    /// 显示器信息来源（randr / tauri）
+    pub source: Option<&'static str>,
+    /// 无法枚举显示器或监听变化的原因
+    pub error: Option<String>,
+}
+
+struct Inner {
+    settings: DisplaySettings,
+    monitors: Vec<Monitor>,
+    source: Option<&'static str>,
+    error: Option<String>,
+    /// 指针与前台窗口中心的位置（只在需要时查询）
+    cursor: Option<(i32, i32)>,
+    focused: Option<(i32, i32)>,
+    target: Option<DisplayTarget>,
+}
+
+/// 显示器拓扑
+pub struct DisplayTopology {
+    inner: Mutex<Inner>,
+}
+
+impl DisplayTopology {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                settings: DisplaySettings::default(),
+                monitors: Vec::new(),
+                source: None,
+                error: None,
+                cursor: None,
+                focused: None,
+                target: None,
+            }),
+        }
+    }
+
+    pub fn status(&self) -> DisplayStatus {
+        status(&self.inner.lock().unwrap())
+    }
+
+    /// 应用设置并重新确定映射区域
+    pub fn configure(&self, app_handle: &AppHandle, settings: &DisplaySettings) {
+        self.update(app_handle, |inner| inner.settings = settings.clone());
+    }
+
+    /// 当前的映射区域（连接后端时推送）
+    pub fn area(&self) -> Option<DisplayArea> {
+        let inner = self.inner.lock().unwrap();
+        inner.target.as_ref().map(|target| target.area)
+    }
+
+    /// 把映射区域内的绝对移动换算为整个桌面上的归一化位置，其他输入原样返回
+    pub fn map(&self, command: &InputCommand) -> InputCommand {
+        let InputCommand::MoveAbsolute { x, y } = *command else {
+            return command.clone();
+        };
+        let inner = self.inner.lock().unwrap();
+        let (Some(target), Some(desktop)) = (&inner.target, desktop(&inner.monitors)) else {
+            return command.clone();
+        };
+        let area = target.area;
+        if area == desktop {
+            return command.clone();
+        }
+
+        let scale = |value: f64, start: i32, size: u32, origin: i32, total: u32| {
+            let pixel = f64::from(start) + value.clamp(0.0, 1.0) * f64::from(size.max(1) - 1);
+            (pixel - f64::from(origin)) / f64::from(total.max(2) - 1)
+        };
+        InputCommand::MoveAbsolute {
+            x: scale(x, area.x, area.width, desktop.x, desktop.width),
+            y: scale(y, area.y, area.height, desktop.y, desktop.height),
+        }
+    }
+
+    /// 修改状态并重新确定映射区域；显示器或区域变化时推送 `display-changed` 事件，
+    /// 区域变化时同步给后端
+    fn update(&self, app_handle: &AppHandle, f: impl FnOnce(&mut Inner)) {
+        let (status, pushed) = {
+            let mut inner = self.inner.lock().unwrap();
+            let monitors = inner.monitors.clone();
+            let previous = inner.target.clone();
+            f(&mut inner);
+            inner.target = resolve(&inner);
+
+            let changed = inner.target != previous;
+            if inner.monitors == monitors && !changed {
+                return;
+            }
+            if changed {
+                log_target(&inner);
+            }
+            let pushed = changed
+                .then(|| inner.target.as_ref().map(|target| target.area))
+                .flatten();
+            (status(&inner), pushed)
+        };
+
+        let _ = app_handle.emit_all("display-changed", status);
+        let state = app_handle.state::<AppState>();
+        if let (Some(area), true) = (pushed, state.bridge.status().connected) {
+            if let Err(e) = state.bridge.set_display_area(area) {
+                eprintln!("[Tauri] 无法推送鼠标映射区域: {}", e);
+            }
+        }
+        // 校准按显示器布局与映射的显示器保存
+        state.calibration.refresh(app_handle);
+    }
+
+    fn needs_pointer(&self) -> (bool, bool) {
+        let mode = self.inner.lock().unwrap().settings.mode;
+        (
+            mode == DisplayMode::UnderCursor,
+            mode == DisplayMode::FocusedWindow,
+        )
+    }
+}
+
+/// 启动显示器监听线程
+pub fn spawn_watcher(app_handle: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("display-watcher".to_string())
+        .spawn(move || watch(&app_handle));
+    if let Err(e) = spawned {
+        eprintln!("[Tauri] 无法启动显示器监听线程: {}", e);
+    }
+}
+
+/// 定时刷新：布局变化（RandR 事件或定时枚举）时更新显示器列表，
+/// 按映射方式查询指针或前台窗口的位置
+fn watch(app_handle: &AppHandle) {
+    let probe = Probe::open();
+    let state = app_handle.state::<AppState>();
+    let mut tick: u32 = 0;
+    loop {
+        let changed = match probe.poll_changed() {
+            Some(changed) => tick == 0 || changed,
+            None => tick.is_multiple_of(LAYOUT_POLL_TICKS),
+        };
+        if changed {
+            refresh(app_handle, &probe, tick > 0);
+        }
+
+        let (cursor, focused) = state.display.needs_pointer();
+        let cursor = cursor.then(|| probe.cursor()).flatten();
+        let focused = focused.then(|| probe.focused_window_center()).flatten();
+        state.display.update(app_handle, |inner| {
+            inner.cursor = cursor;
+            inner.focused = focused;
+        });
+
+        tick = tick.wrapping_add(1);
+        std::thread::sleep(TICK);
+    }
+}
+
+/// 重新枚举显示器；布局变化后重新打开注入后端（X11 根窗口尺寸随之变化）
+fn refresh(app_handle: &AppHandle, probe: &Probe, reconfigure: bool) {
+    let state = app_handle.state::<AppState>();
+    let enumerated = match probe.monitors() {
+        Some(Ok(mut monitors)) => {
+            // RandR 没有缩放比例，按位置从 Tauri 的结果中补充
+            if let Ok(scaled) = tauri_monitors(app_handle) {
+                for monitor in &mut monitors {
+                    if let Some(other) = scaled.iter().find(|m| m.area == monitor.area) {
+                        monitor.scale_factor = other.scale_factor;
+                    }
+                }
+            }
+            Ok((monitors, "randr"))
+        }
+        Some(Err(e)) => Err(e),
+        None => tauri_monitors(app_handle).map(|monitors| (monitors, "tauri")),
+    };
+    let (monitors, source) = match enumerated {
+        Ok(enumerated) => enumerated,
+        Err(e) => {
+            state.display.update(app_handle, |inner| {
+                if inner.error.as_ref() != Some(&e) {
+                    eprintln!("[Tauri] 无法枚举显示器: {}", e);
+                }
+                inner.error = Some(e);
+            });
+            return;
+        }
+    };
+
+    let changed = state.display.inner.lock().unwrap().monitors != monitors;
+    if changed {
+        let names: Vec<_> = monitors.iter().map(|m| m.name.as_str()).collect();
+        println!("[Tauri] 显示器: {}", names.join(", "));
+    }
+    state.display.update(app_handle, |inner| {
+        inner.monitors = monitors;
+        inner.source = Some(source);
+        inner.error = probe.error.clone();
+    });
+
+    if changed && reconfigure {
+        let settings = state.settings.get();
+        state.input.configure(&settings.host.input);
+        state.executor.configure(app_handle, &settings);
+        if state.bridge.status().connected {
+            let synced = state
+                .bridge
+                .set_input_delegate(state.input.delegate())
+                .and_then(|_| {
+                    state
+                        .bridge
+                        .set_host_execution(state.executor.host_execution())
+                });
+            if let Err(e) = synced {
+                eprintln!("[Tauri] 无法同步注入方式: {}", e);
+            }
+        }
+    }
+}
+
+/// 通过 Tauri 枚举显示器（需要主窗口）
+fn tauri_monitors(app_handle: &AppHandle) -> Result<Vec<Monitor>, String> {
+    let window = app_handle
+        .get_window("main")
+        .ok_or_else(|| "主窗口不存在".to_string())?;
+    let primary = window.primary_monitor().map_err(|e| e.to_string())?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("无法枚举显示器: {}", e))?;
+
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            let (position, size) = (monitor.position(), monitor.size());
+            Monitor {
+                name: monitor.name().cloned().unwrap_or_default(),
+                area: DisplayArea {
+                    x: position.x,
+                    y: position.y,
+                    width: size.width,
+                    height: size.height,
+                },
+                scale_factor: monitor.scale_factor(),
+                primary: primary.as_ref().is_some_and(|primary| {
+                    primary.position() == position && primary.name() == monitor.name()
+                }),
+            }
+        })
+        .collect())
+}
+
+fn status(inner: &Inner) -> DisplayStatus {
+    DisplayStatus {
+        monitors: inner.monitors.clone(),
+        desktop: desktop(&inner.monitors),
+        target: inner.target.clone(),
+        source: inner.source,
+        error: inner.error.clone(),
+    }
+}
+
+fn log_target(inner: &Inner) {
+    let Some(target) = &inner.target else {
+        return;
+    };
+    let wanted = inner.settings.monitor.as_ref();
+    if inner.settings.mode == DisplayMode::Monitor && target.monitor.as_ref() != wanted {
+        eprintln!(
+            "[Tauri] 找不到显示器 {}，改为映射到主显示器",
+            wanted.map_or("", String::as_str)
+        );
+    }
+    let area = target.area;
+    println!(
+        "[Tauri] 鼠标映射区域: {} {}x{}+{}+{}",
+        target.monitor.as_deref().unwrap_or("整个桌面"),
+        area.width,
+        area.height,
+        area.x,
+        area.y
+    );
+}
+
+/// 所有显示器的外接矩形
+fn desktop(monitors: &[Monitor]) -> Option<DisplayArea> {
+    let left = monitors.iter().map(|m| m.area.x).min()?;
+    let top = monitors.iter().map(|m| m.area.y).min()?;
+    let right = monitors.iter().map(|m| right(&m.area)).max()?;
+    let bottom = monitors.iter().map(|m| bottom(&m.area)).max()?;
+    Some(DisplayArea {
+        x: left,
+        y: top,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+    })
+}
+
+/// 按映射方式确定区域：找不到对应的显示器时保留上一次的显示器，再退回主显示器
+fn resolve(inner: &Inner) -> Option<DisplayTarget> {
+    let monitors = &inner.monitors;
+    if inner.settings.mode == DisplayMode::Desktop {
+        return desktop(monitors).map(|area| DisplayTarget {
+            monitor: None,
+            area,
+        });
+    }
+
+    let named = |name: Option<&String>| monitors.iter().find(|m| Some(&m.name) == name);
+    let containing = |point: Option<(i32, i32)>| {
+        let (x, y) = point?;
+        monitors.iter().find(|m| {
+            (m.area.x..right(&m.area)).contains(&x) && (m.area.y..bottom(&m.area)).contains(&y)
+        })
+    };
+    let previous = inner
+        .target
+        .as_ref()
+        .and_then(|target| target.monitor.as_ref());
+    let monitor = match inner.settings.mode {
+        DisplayMode::UnderCursor => containing(inner.cursor).or_else(|| named(previous)),
+        DisplayMode::FocusedWindow => containing(inner.focused).or_else(|| named(previous)),
+        _ => named(inner.settings.monitor.as_ref()),
+    }
+    .or_else(|| monitors.iter().find(|m| m.primary))
+    .or_else(|| monitors.first())?;
+
+    Some(DisplayTarget {
+        monitor: Some(monitor.name.clone()),
+        area: monitor.area,
+    })
+}
+
+fn right(area: &DisplayArea) -> i32 {
+    area.x.saturating_add(area.width as i32)
+}
+
+fn bottom(area: &DisplayArea) -> i32 {
+    area.y.saturating_add(area.height as i32)
+}
+
+/// 平台相关的查询：Linux 上为 X11（RandR），其他平台由 Tauri 枚举显示器
+struct Probe {
+    #[cfg(target_os = "linux")]
+    x11: Option<x11::X11Display>,
+    /// 无法使用平台接口的原因（此时定时通过 Tauri 枚举）
+    error: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl Probe {
+    fn open() -> Self {
+        match x11::X11Display::open() {
+            Ok(x11) => Self {
+                x11: Some(x11),
+                error: None,
+            },
+            Err(e) => {
+                eprintln!("[Tauri] 无法通过 RandR 监听显示器，改为定时枚举: {}", e);
+                Self {
+                    x11: None,
+                    error: Some(e),
+                }
+            }
+        }
+    }
+
+    /// 平台自己枚举的显示器，不支持时为空
+    fn monitors(&self) -> Option<Result<Vec<Monitor>, String>> {
+        self.x11.as_ref().map(|x11| x11.monitors())
+    }
+
+    /// 是否收到了布局变化事件，不支持事件时为空
+    fn poll_changed(&self) -> Option<bool> {
+        let x11 = self.x11.as_ref()?;
+        Some(x11.poll_changed().unwrap_or_else(|e| {
+            eprintln!("[Tauri] 显示器事件读取失败: {}", e);
+            true
+        }))
+    }
+
+    fn cursor(&self) -> Option<(i32, i32)> {
+        self.x11.as_ref()?.cursor()
+    }
+
+    fn focused_window_center(&self) -> Option<(i32, i32)> {
+        self.x11.as_ref()?.focused_window_center()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Probe {
+    fn open() -> Self {
+        Self { error: None }
+    }
+
+    fn monitors(&self) -> Option<Result<Vec<Monitor>, String>> {
+        None
+    }
+
+    fn poll_changed(&self) -> Option<bool> {
+        None
+    }
+
+    #[cfg(target_os = "windows")]
+    fn cursor(&self) -> Option<(i32, i32)> {
+        windows::cursor()
+    }
+
+    #[cfg(target_os = "windows")]
+    fn focused_window_center(&self) -> Option<(i32, i32)> {
+        windows::focused_window_center()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn cursor(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn focused_window_center(&self) -> Option<(i32, i32)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{InputDriver, InputEvent, InputInjector, InputSettings};
+
+    fn monitor(name: &str, x: i32, width: u32, height: u32, primary: bool) -> Monitor {
+        Monitor {
+            name: name.to_string(),
+            area: DisplayArea {
+                x,
+                y: 0,
+                width,
+                height,
+            },
+            scale_factor: 1.0,
+            primary,
+        }
+    }
+
+    /// 1920x1080 的主显示器右侧接一个 1280x1024 的显示器
+    fn topology(mode: DisplayMode, monitor_name: Option<&str>) -> DisplayTopology {
+        let topology = DisplayTopology::new();
+        {
+            let mut inner = topology.inner.lock().unwrap();
+            inner.monitors = vec![
+                monitor("DP-1", 0, 1920, 1080, true),
+                monitor("HDMI-1", 1920, 1280, 1024, false),
+            ];
+            inner.settings = DisplaySettings {
+                mode,
+                monitor: monitor_name.map(str::to_string),
+            };
+            inner.target = resolve(&inner);
+        }
+        topology
+    }
+
+    fn absolute(x: f64, y: f64) -> InputCommand {
+        InputCommand::MoveAbsolute { x, y }
+    }
+
+    #[test]
+    fn maps_monitor_area_onto_desktop() {
+        let topology = topology(DisplayMode::Monitor, Some("HDMI-1"));
+        let injector = InputInjector::new();
+        injector.configure(&InputSettings {
+            driver: InputDriver::Mock,
+        });
+        for (x, y) in [(0.0, 0.0), (1.0, 1.0), (0.5, 0.5)] {
+            injector.execute(&topology.map(&absolute(x, y))).unwrap();
+        }
+
+        let expected = [
+            (1920.0 / 3199.0, 0.0),
+            (1.0, 1023.0 / 1079.0),
+            ((1920.0 + 639.5) / 3199.0, 511.5 / 1079.0),
+        ];
+        let recorded = injector.take_recorded().unwrap();
+        assert_eq!(recorded.len(), expected.len());
+        for (event, (ex, ey)) in recorded.iter().zip(expected) {
+            let InputEvent::MoveAbsolute { x, y } = *event else {
+                panic!("{event:?}");
+            };
+            assert!(
+                (x - ex).abs() < 1e-12 && (y - ey).abs() < 1e-12,
+                "{event:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_monitor_falls_back_to_primary() {
+        let topology = topology(DisplayMode::Monitor, Some("VGA-1"));
+        assert_eq!(
+            topology.map(&absolute(1.0, 1.0)),
+            absolute(1919.0 / 3199.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn desktop_and_other_commands_pass_through() {
+        let whole = topology(DisplayMode::Desktop, None);
+        assert_eq!(whole.map(&absolute(0.25, 0.75)), absolute(0.25, 0.75));
+
+        let single = topology(DisplayMode::Monitor, Some("HDMI-1"));
+        let scroll = InputCommand::Scroll { amount: 3 };
+        assert_eq!(single.map(&scroll), scroll);
+        assert_eq!(
+            DisplayTopology::new().map(&absolute(0.5, 0.5)),
+            absolute(0.5, 0.5)
+        );
+    }
+}
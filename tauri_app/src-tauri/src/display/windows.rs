@@ -0,0 +1,34 @@
+//! Windows 指针与前台窗口位置
+//!
+//! 宿主进程按显示器感知 DPI 运行，坐标为虚拟桌面上的物理像素，与 Tauri 枚举的显示器一致
+
+use windows_sys::Win32::Foundation::{POINT, RECT};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetForegroundWindow, GetWindowRect,
+};
+
+/// 指针在桌面上的位置
+pub fn cursor() -> Option<(i32, i32)> {
+    let mut point = POINT { x: 0, y: 0 };
+    // SAFETY: point 是有效的输出参数
+    let ok = unsafe { GetCursorPos(&mut point) } != 0;
+    ok.then_some((point.x, point.y))
+}
+
+/// 前台窗口中心在桌面上的位置
+pub fn focused_window_center() -> Option<(i32, i32)> {
+    // SAFETY: 没有参数，没有前台窗口时返回 0
+    let window = unsafe { GetForegroundWindow() };
+    if window == 0 {
+        return None;
+    }
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    // SAFETY: window 为有效句柄（窗口已关闭时调用失败），rect 是有效的输出参数
+    let ok = unsafe { GetWindowRect(window, &mut rect) } != 0;
+    ok.then_some(((rect.left + rect.right) / 2, (rect.top + rect.bottom) / 2))
+}
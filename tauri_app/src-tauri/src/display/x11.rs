@@ -0,0 +1,142 @@
+//! X11 显示器拓扑
+//!
+//! 通过 RandR 1.5 的 `GetMonitors` 枚举显示器（名称为输出名，如 `HDMI-1`），
+//! 订阅 RandR 事件感知热插拔与分辨率变化；同时查询指针与前台窗口的位置。
+//! 只需要 `DISPLAY`，可以在 Xvfb 下运行
+
+use phantom_protocol::DisplayArea;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::randr::{self, ConnectionExt as _, NotifyMask};
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use super::Monitor;
+
+pub struct X11Display {
+    conn: RustConnection,
+    root: Window,
+    active_window: Atom,
+}
+
+impl X11Display {
+    pub fn open() -> Result<Self, String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| format!("无法连接 X11: {}", e))?;
+        let root = conn.setup().roots[screen].root;
+
+        let present = conn
+            .extension_information(randr::X11_EXTENSION_NAME)
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !present {
+            return Err("X 服务器不支持 RandR 扩展".to_string());
+        }
+        let version = conn
+            .randr_query_version(1, 5)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("无法查询 RandR 版本: {}", e))?;
+        if (version.major_version, version.minor_version) < (1, 5) {
+            return Err(format!(
+                "X 服务器的 RandR 版本过低（{}.{}，需要 1.5）",
+                version.major_version, version.minor_version
+            ));
+        }
+
+        let mask = NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE;
+        conn.randr_select_input(root, mask)
+            .map_err(|e| e.to_string())?
+            .check()
+            .map_err(|e| format!("无法订阅 RandR 事件: {}", e))?;
+        let active_window = conn
+            .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .atom;
+
+        Ok(Self {
+            conn,
+            root,
+            active_window,
+        })
+    }
+
+    /// 当前启用的显示器（缩放比例固定为 1，由调用方按 Tauri 的结果补充）
+    pub fn monitors(&self) -> Result<Vec<Monitor>, String> {
+        let reply = self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("无法枚举显示器: {}", e))?;
+
+        let mut monitors = Vec::with_capacity(reply.monitors.len());
+        for monitor in reply.monitors {
+            let name = self
+                .conn
+                .get_atom_name(monitor.name)
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                .unwrap_or_default();
+            monitors.push(Monitor {
+                name,
+                area: DisplayArea {
+                    x: monitor.x.into(),
+                    y: monitor.y.into(),
+                    width: monitor.width.into(),
+                    height: monitor.height.into(),
+                },
+                scale_factor: 1.0,
+                primary: monitor.primary,
+            });
+        }
+        Ok(monitors)
+    }
+
+    /// 取出已到达的事件（不阻塞），返回其中是否有显示器布局变化
+    pub fn poll_changed(&self) -> Result<bool, String> {
+        let mut changed = false;
+        while let Some(event) = self.conn.poll_for_event().map_err(|e| e.to_string())? {
+            if matches!(
+                event,
+                Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_)
+            ) {
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// 指针在桌面上的位置
+    pub fn cursor(&self) -> Option<(i32, i32)> {
+        let reply = self.conn.query_pointer(self.root).ok()?.reply().ok()?;
+        Some((reply.root_x.into(), reply.root_y.into()))
+    }
+
+    /// 前台窗口中心在桌面上的位置
+    pub fn focused_window_center(&self) -> Option<(i32, i32)> {
+        let window = self
+            .conn
+            .get_property(false, self.root, self.active_window, AtomEnum::WINDOW, 0, 1)
+            .ok()?
+            .reply()
+            .ok()?
+            .value32()?
+            .next()
+            .filter(|&window| window != x11rb::NONE)?;
+
+        let geometry = self.conn.get_geometry(window).ok()?.reply().ok()?;
+        let origin = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .ok()?
+            .reply()
+            .ok()?;
+        Some((
+            i32::from(origin.dst_x) + i32::from(geometry.width) / 2,
+            i32::from(origin.dst_y) + i32::from(geometry.height) / 2,
+        ))
+    }
+}
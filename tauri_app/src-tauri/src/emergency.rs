@@ -0,0 +1,82 @@
+//! 紧急停止
+//!
+//! 手势控制失控时（例如 pinch 卡住导致鼠标一直按下）的保险：立即停用控制并
+//! 释放按住的按键，同时闪烁托盘图标。WebSocket 不可达或后端未及时确认时，
+//! 直接结束后端进程
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// 等待后端确认停用的最长时间
+const ACK_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// 结束后端期间的托盘提示
+const STOPPING_BACKEND: &str = "紧急停止：正在结束后端进程";
+
+/// `emergency-stop` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergencyStop {
+    /// 是否结束了运行中的后端进程（WebSocket 停用失败时）
+    pub killed_backend: bool,
+    /// 停用失败的原因，结束后端也失败时一并给出
+    pub error: Option<String>,
+}
+
+/// 触发紧急停止（立即返回，停用与回退在后台完成）
+pub fn trigger(app_handle: &AppHandle) {
+    println!("[Tauri] 紧急停止");
+    let state = app_handle.state::<AppState>();
+    state.tray.flash(app_handle);
+    // 由宿主执行动作时不必等后端确认，立即松开按下的鼠标
+    state.executor.set_active(app_handle, false);
+    // 后端委托注入的按键同样立即松开，不等待停用确认或结束后端
+    state.input.release_all();
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let result = match state.bridge.deactivate(ACK_TIMEOUT).await {
+            Ok(()) => {
+                println!("[Tauri] 后端已停用控制");
+                EmergencyStop {
+                    killed_backend: false,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                eprintln!("[Tauri] 无法通过 WebSocket 停用控制: {}，结束后端进程", e);
+                state.tray.update(&app_handle, |view| {
+                    view.failure = Some(STOPPING_BACKEND.to_string());
+                });
+                let stopped = state.backend.stop(&app_handle).await;
+                // 结束后端后紧急停止即已完成，只有结束失败时保留托盘提示
+                state.tray.update(&app_handle, |view| {
+                    if view.failure.as_deref() == Some(STOPPING_BACKEND) {
+                        view.failure = stopped
+                            .as_ref()
+                            .err()
+                            .map(|_| "紧急停止：无法结束后端进程".to_string());
+                    }
+                });
+                match stopped {
+                    Ok(killed_backend) => EmergencyStop {
+                        killed_backend,
+                        error: Some(e),
+                    },
+                    Err(stop_error) => {
+                        eprintln!("[Tauri] 结束后端失败: {}", stop_error);
+                        EmergencyStop {
+                            killed_backend: false,
+                            error: Some(format!("{}；结束后端失败: {}", e, stop_error)),
+                        }
+                    }
+                }
+            }
+        };
+        let _ = app_handle.emit_all("emergency-stop", result);
+    });
+}
@@ -0,0 +1,235 @@
+//! 动作审计记录
+//!
+//! 宿主执行的每个动作都记录触发它的手势事件、执行原因与实际注入的输入，
+//! 用于排查"为什么鼠标动了"一类的问题。保持期间逐帧的鼠标移动合并为一条
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use phantom_protocol::{Action, GestureEventType, InputCommand};
+use serde::Serialize;
+
+use crate::backend::unix_millis;
+
+/// 最多保留的记录数
+const MAX_ENTRIES: usize = 500;
+
+/// 触发动作的手势事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trigger {
+    /// 手势名（滑动时为 slide_<方向>）
+    pub gesture: String,
+    pub event_type: GestureEventType,
+    pub hand_id: String,
+}
+
+/// 一条审计记录（`action-audit` 事件与 `get_action_audit` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    /// 时间（Unix 毫秒）
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub trigger: Trigger,
+    /// 执行的动作；激活与停用控制时为空
+    pub action: Option<Action>,
+    /// 执行原因
+    pub reason: String,
+    /// 注入的输入
+    pub commands: Vec<InputCommand>,
+    /// 合并进这条记录的鼠标移动帧数
+    pub merged: u32,
+    /// 执行失败的原因
+    pub error: Option<String>,
+}
+
+/// 有界的审计记录
+pub struct AuditLog {
+    entries: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 追加一条记录，返回需要推送（`action-audit` 事件）的新记录；
+    /// 同一手势连续的鼠标移动合并到上一条记录中，不再推送
+    pub fn record(&self, entry: AuditEntry) -> Option<AuditEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(last) = entries.back_mut() {
+            if merge(last, &entry) {
+                return None;
+            }
+        }
+
+        if entries.len() >= MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(entry.clone());
+        Some(entry)
+    }
+
+    /// 最近的记录（按时间先后）
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl AuditEntry {
+    pub fn new(
+        trigger: &Trigger,
+        action: Option<Action>,
+        reason: impl Into<String>,
+        commands: Vec<InputCommand>,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: unix_millis(),
+            trigger: trigger.clone(),
+            action,
+            reason: reason.into(),
+            commands,
+            merged: 0,
+            error,
+        }
+    }
+}
+
+/// 把一帧鼠标移动合并到上一条记录：相对移动累加位移，绝对移动保留最后的位置
+fn merge(last: &mut AuditEntry, entry: &AuditEntry) -> bool {
+    if last.trigger != entry.trigger
+        || last.action != entry.action
+        || last.reason != entry.reason
+        || last.error.is_some()
+        || entry.error.is_some()
+    {
+        return false;
+    }
+    match (last.commands.as_mut_slice(), entry.commands.as_slice()) {
+        (
+            [InputCommand::MoveRelative { dx, dy }],
+            [InputCommand::MoveRelative {
+                dx: next_dx,
+                dy: next_dy,
+            }],
+        ) => {
+            *dx += next_dx;
+            *dy += next_dy;
+        }
+        ([InputCommand::MoveAbsolute { x, y }], [InputCommand::MoveAbsolute { x: nx, y: ny }]) => {
+            *x = *nx;
+            *y = *ny;
+        }
+        _ => return false,
+    }
+    last.timestamp = entry.timestamp;
+    last.merged += 1;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phantom_protocol::MouseButton;
+
+    fn trigger(hand_id: &str) -> Trigger {
+        Trigger {
+            gesture: "pinch".to_string(),
+            event_type: GestureEventType::Hold,
+            hand_id: hand_id.to_string(),
+        }
+    }
+
+    /// 一帧鼠标移动的记录
+    fn moved(hand_id: &str, command: InputCommand) -> AuditEntry {
+        AuditEntry::new(
+            &trigger(hand_id),
+            Some(Action::MouseMove),
+            "跟随食指",
+            vec![command],
+            None,
+        )
+    }
+
+    fn relative(dx: i32, dy: i32) -> InputCommand {
+        InputCommand::MoveRelative { dx, dy }
+    }
+
+    fn absolute(x: f64, y: f64) -> InputCommand {
+        InputCommand::MoveAbsolute { x, y }
+    }
+
+    #[test]
+    fn merges_consecutive_moves() {
+        let mut last = moved("0", relative(3, -1));
+        assert!(merge(&mut last, &moved("0", relative(2, 4))));
+        assert!(merge(&mut last, &moved("0", relative(-1, 0))));
+        assert_eq!(last.commands, [relative(4, 3)]);
+        assert_eq!(last.merged, 2);
+
+        let mut last = moved("0", absolute(0.1, 0.2));
+        assert!(merge(&mut last, &moved("0", absolute(0.5, 0.6))));
+        assert_eq!(last.commands, [absolute(0.5, 0.6)]);
+        assert_eq!(last.merged, 1);
+    }
+
+    #[test]
+    fn keeps_other_entries_apart() {
+        let failed = AuditEntry {
+            error: Some("注入失败".to_string()),
+            ..moved("0", relative(1, 1))
+        };
+        let pressed = AuditEntry::new(
+            &trigger("0"),
+            Some(Action::MouseDrag),
+            "按下鼠标",
+            vec![InputCommand::Button {
+                button: MouseButton::Left,
+                pressed: true,
+            }],
+            None,
+        );
+        let cases = [
+            (moved("0", relative(1, 1)), moved("1", relative(1, 1))),
+            (moved("0", relative(1, 1)), moved("0", absolute(0.5, 0.5))),
+            (moved("0", relative(1, 1)), failed.clone()),
+            (failed, moved("0", relative(1, 1))),
+            (pressed.clone(), pressed),
+        ];
+        for (mut last, entry) in cases {
+            let before = last.clone();
+            assert!(!merge(&mut last, &entry), "{entry:?}");
+            assert_eq!(last.commands, before.commands);
+            assert_eq!(last.merged, 0);
+        }
+    }
+
+    #[test]
+    fn record_returns_only_new_entries() {
+        let log = AuditLog::new();
+        assert!(log.record(moved("0", relative(1, 0))).is_some());
+        assert!(log.record(moved("0", relative(1, 0))).is_none());
+        assert!(log.record(moved("1", relative(1, 0))).is_some());
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].commands, [relative(2, 0)]);
+
+        for _ in 0..MAX_ENTRIES {
+            log.record(AuditEntry::new(
+                &trigger("0"),
+                None,
+                "激活",
+                Vec::new(),
+                None,
+            ));
+        }
+        assert_eq!(log.entries().len(), MAX_ENTRIES);
+        assert_eq!(log.entries()[0].reason, "激活");
+    }
+}
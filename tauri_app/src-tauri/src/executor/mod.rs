@@ -0,0 +1,1082 @@
+//! 宿主侧动作执行
+//!
+//! 启用 `host.executor` 后由宿主代替后端执行手势动作：后端只负责识别，广播
+//! `gesture_event` 与 `frame_data`；宿主维护自己的激活状态（open 激活、fist 停用），
+//! 把当前手势配置中的绑定换算为 [`InputCommand`] 交给输入注入（见 [`crate::input`]）。
+//! 绝对定位的位置为 `host.display` 选择的区域内的坐标（见 [`crate::display`]），
+//! 有校准时食指位置先经过校准变换（见 [`crate::calibration`]）。
+//! 后端不转发 hold 事件，保持期间的鼠标跟随与 hold 重复动作由每帧的 `frame_data` 驱动。
+//! 启动程序等宿主动作仍由 `mapping` 执行。每次注入都记入审计记录（见 [`audit`]）
+
+mod audit;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use phantom_protocol::{
+    Action, ActionBinding, FrameData, GestureEvent, GestureEventType, HostExecution, InputCommand,
+    MouseButton,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+pub use audit::AuditEntry;
+use audit::{AuditLog, Trigger};
+
+use crate::bridge::BackendBridge;
+use crate::calibration::CalibrationStore;
+use crate::display::DisplayTopology;
+use crate::input::InputInjector;
+use crate::mapping;
+use crate::settings::Settings;
+use crate::AppState;
+
+/// 注入后端不知道桌面尺寸时使用的默认值（与后端 `ActionConfig` 一致）
+const DEFAULT_SCREEN_SIZE: (u32, u32) = (1920, 1080);
+
+/// 相对移动的位移平滑系数（与后端 `ActionConfig.mouse_delta_smoothing` 一致）
+const DELTA_SMOOTHING: f64 = 0.5;
+
+/// 食指指尖的关键点序号
+pub(crate) const INDEX_TIP: usize = 8;
+
+/// 鼠标定位方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointerMode {
+    /// 类似触摸板，按食指的位移移动
+    #[default]
+    Relative,
+    /// 食指位置映射到 `host.display` 选择的区域（有校准时按校准映射）
+    Absolute,
+}
+
+/// 宿主执行设置（`host.executor`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutorSettings {
+    /// 是否由宿主执行手势动作（需要可用的输入注入后端）
+    pub enabled: bool,
+    pub pointer_mode: PointerMode,
+    /// 相对移动的速度增益
+    pub mouse_speed: f64,
+    /// 相对移动的死区（归一化坐标）
+    pub mouse_deadzone: f64,
+}
+
+impl Default for ExecutorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pointer_mode: PointerMode::default(),
+            mouse_speed: 2.0,
+            mouse_deadzone: 0.003,
+        }
+    }
+}
+
+/// 执行状态（`get_executor_status` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutorStatus {
+    /// 设置中是否启用
+    pub enabled: bool,
+    /// 实际是否由宿主执行
+    pub running: bool,
+    /// 宿主记录的激活状态
+    pub active: bool,
+    /// 启用但无法由宿主执行的原因
+    pub error: Option<String>,
+}
+
+/// 鼠标参数（来自 `host.executor` 与 `action`）
+struct PointerConfig {
+    mode: PointerMode,
+    speed: f64,
+    deadzone: f64,
+    /// 绝对定位的灵敏度与平滑系数
+    sensitivity: f64,
+    smoothing: f64,
+    /// 相对定位的位移平滑系数
+    delta_smoothing: f64,
+    screen: (u32, u32),
+}
+
+/// 鼠标跟随状态，手势进入、退出与停用时重置
+#[derive(Default)]
+struct PointerTracker {
+    /// 相对定位：上一帧的食指位置与平滑后的位移
+    last: Option<(f64, f64)>,
+    delta: (f64, f64),
+    /// 绝对定位：平滑后的位置
+    smoothed: Option<(f64, f64)>,
+}
+
+/// 一只手正在保持的手势
+struct Held {
+    trigger: Trigger,
+    since: Instant,
+    /// 进入时该手势的绑定（保持期间切换配置不影响已按下的鼠标）
+    bindings: Vec<ActionBinding>,
+    /// 绑定序号 -> hold 重复动作已触发的次数
+    fired: HashMap<usize, u64>,
+}
+
+struct Inner {
+    enabled: bool,
+    running: bool,
+    error: Option<String>,
+    active: bool,
+    pointer_config: PointerConfig,
+    tracker: PointerTracker,
+    /// 手 ID -> 正在保持的手势
+    held: HashMap<String, Held>,
+    /// 按下鼠标左键的手势（click / drag），退出或停用时松开
+    pressed: Option<Trigger>,
+}
+
+/// 执行器用到的宿主状态（由 `AppState` 提供，测试中直接构造）
+struct Host<'a> {
+    input: &'a InputInjector,
+    display: &'a DisplayTopology,
+    calibration: &'a CalibrationStore,
+    bridge: &'a BackendBridge,
+    /// 新增的审计记录，释放执行器的锁后推送
+    recorded: Vec<AuditEntry>,
+}
+
+impl<'a> Host<'a> {
+    fn new(state: &'a AppState) -> Self {
+        Self {
+            input: &state.input,
+            display: &state.display,
+            calibration: &state.calibration,
+            bridge: &state.bridge,
+            recorded: Vec::new(),
+        }
+    }
+}
+
+/// 宿主侧动作执行器
+pub struct ActionExecutor {
+    inner: Mutex<Inner>,
+    audit: AuditLog,
+}
+
+impl ActionExecutor {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                enabled: false,
+                running: false,
+                error: None,
+                active: false,
+                pointer_config: pointer_config(&Settings::default(), None),
+                tracker: PointerTracker::default(),
+                held: HashMap::new(),
+                pressed: None,
+            }),
+            audit: AuditLog::new(),
+        }
+    }
+
+    /// 按设置与当前注入后端决定是否由宿主执行（注入后端变化后也须调用）
+    pub fn configure(&self, app_handle: &AppHandle, settings: &Settings) {
+        self.with_host(app_handle, |host| self.apply_settings(host, settings));
+    }
+
+    /// 发送给后端的执行设置
+    pub fn host_execution(&self) -> HostExecution {
+        HostExecution {
+            enabled: self.inner.lock().unwrap().running,
+        }
+    }
+
+    pub fn status(&self) -> ExecutorStatus {
+        let inner = self.inner.lock().unwrap();
+        ExecutorStatus {
+            enabled: inner.enabled,
+            running: inner.running,
+            active: inner.active,
+            error: inner.error.clone(),
+        }
+    }
+
+    pub fn audit(&self) -> Vec<AuditEntry> {
+        self.audit.entries()
+    }
+
+    pub fn clear_audit(&self) {
+        self.audit.clear();
+    }
+
+    /// 同步后端广播的激活状态（托盘、快捷键与紧急停止也经由后端确认）；停用时松开鼠标
+    pub fn set_active(&self, app_handle: &AppHandle, active: bool) {
+        self.with_host(app_handle, |host| self.sync_active(host, active));
+    }
+
+    /// 与后端断开：松开鼠标并清空手势状态（重连后以欢迎消息中的激活状态为准）
+    pub fn reset(&self, app_handle: &AppHandle) {
+        self.with_host(app_handle, |host| self.clear(host));
+    }
+
+    /// 处理后端的手势事件
+    pub fn on_gesture_event(&self, app_handle: &AppHandle, event: &GestureEvent) {
+        let bindings = current_bindings(app_handle);
+        self.with_host(app_handle, |host| {
+            self.handle_event(host, &bindings, event);
+        });
+    }
+
+    /// 处理一帧检测结果：保持手势的鼠标跟随与 hold 重复动作
+    pub fn on_frame(&self, app_handle: &AppHandle, frame: &FrameData) {
+        self.with_host(app_handle, |host| self.handle_frame(host, frame));
+    }
+
+    /// 用 `AppState` 中的宿主状态执行，结束后推送新增的审计记录（`action-audit` 事件）
+    fn with_host(&self, app_handle: &AppHandle, f: impl FnOnce(&mut Host)) {
+        let state = app_handle.state::<AppState>();
+        let mut host = Host::new(&state);
+        f(&mut host);
+        for entry in host.recorded {
+            let _ = app_handle.emit_all("action-audit", entry);
+        }
+    }
+
+    fn apply_settings(&self, host: &mut Host, settings: &Settings) {
+        let enabled = settings.host.executor.enabled;
+        let injectable = host.input.status().backend.is_some();
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.pointer_config = pointer_config(settings, host.input.screen_size());
+        inner.enabled = enabled;
+        let error = (enabled && !injectable)
+            .then(|| "没有可用的输入注入后端，仍由后端执行动作".to_string());
+        if let Some(e) = error.as_ref().filter(|_| inner.error != error) {
+            eprintln!("[Tauri] {}", e);
+        }
+        inner.error = error;
+
+        let running = enabled && injectable;
+        if inner.running == running {
+            return;
+        }
+        inner.running = running;
+        if running {
+            println!("[Tauri] 手势动作: 由宿主执行");
+        } else {
+            self.release(host, &mut inner, "停止宿主执行时松开");
+            inner.held.clear();
+            println!("[Tauri] 手势动作: 由后端执行");
+        }
+    }
+
+    fn sync_active(&self, host: &mut Host, active: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.active == active {
+            return;
+        }
+        inner.active = active;
+        if !active {
+            self.release(host, &mut inner, "停用控制时松开");
+        }
+    }
+
+    fn clear(&self, host: &mut Host) {
+        let mut inner = self.inner.lock().unwrap();
+        self.release(host, &mut inner, "后端断开时松开");
+        inner.held.clear();
+        inner.active = false;
+    }
+
+    /// 按当前配置的绑定处理手势事件
+    fn handle_event(&self, host: &mut Host, bindings: &[ActionBinding], event: &GestureEvent) {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.running {
+            return;
+        }
+        let trigger = Trigger {
+            gesture: event.gesture.clone(),
+            event_type: event.event_type,
+            hand_id: event.hand_id.clone(),
+        };
+
+        // open 与 fist 固定用于激活/停用，无论当前是否激活；
+        // 当前配置绑定了 fist 时（如视频播放器中播放/暂停）fist 按普通手势执行
+        let deactivate =
+            event.gesture == mapping::DEACTIVATE_GESTURE && !mapping::rebinds_deactivate(bindings);
+        match (event.gesture.as_str(), event.event_type) {
+            ("open", GestureEventType::Enter) if !inner.active => {
+                return self.activate(host, &mut inner, &trigger, true);
+            }
+            (_, GestureEventType::Enter) if deactivate && inner.active => {
+                return self.activate(host, &mut inner, &trigger, false);
+            }
+            ("open", _) => return,
+            _ if deactivate => return,
+            _ => {}
+        }
+
+        match event.event_type {
+            GestureEventType::Enter => {
+                let bindings = bindings
+                    .iter()
+                    .filter(|b| b.gesture == event.gesture && b.on != GestureEventType::Slide)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if inner.active {
+                    inner.tracker = PointerTracker::default();
+                    for binding in &bindings {
+                        match binding.action {
+                            Action::MouseClick | Action::MouseDrag => {
+                                self.press(host, &mut inner, &trigger, &binding.action);
+                            }
+                            _ if binding.on == GestureEventType::Enter => {
+                                self.run(host, &trigger, binding, true, "进入手势");
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                let held = Held {
+                    trigger,
+                    since: Instant::now(),
+                    bindings,
+                    fired: HashMap::new(),
+                };
+                inner.held.insert(event.hand_id.clone(), held);
+            }
+            GestureEventType::Exit => {
+                let Some(held) = inner.held.remove(&event.hand_id) else {
+                    return;
+                };
+                if held.bindings.iter().any(|b| b.action.is_pointer()) {
+                    inner.tracker = PointerTracker::default();
+                    if inner.pressed.as_ref().map(|p| &p.hand_id) == Some(&event.hand_id) {
+                        self.release(host, &mut inner, "退出手势");
+                    }
+                }
+                if inner.active {
+                    for binding in held
+                        .bindings
+                        .iter()
+                        .filter(|b| b.on == GestureEventType::Exit)
+                    {
+                        self.run(host, &trigger, binding, true, "退出手势");
+                    }
+                }
+            }
+            GestureEventType::Slide if inner.active => {
+                // 滑动事件的手势名为 slide_<方向>，绑定中只写方向
+                let direction = event
+                    .gesture
+                    .strip_prefix("slide_")
+                    .unwrap_or(&event.gesture);
+                for binding in bindings
+                    .iter()
+                    .filter(|b| b.on == GestureEventType::Slide && b.gesture == direction)
+                {
+                    let reason = format!("向{}滑动", direction_name(direction));
+                    self.run(host, &trigger, binding, direction != "left", &reason);
+                }
+            }
+            // 后端不转发 hold 事件，保持期间由 handle_frame 处理
+            _ => {}
+        }
+    }
+
+    fn handle_frame(&self, host: &mut Host, frame: &FrameData) {
+        let mut guard = self.inner.lock().unwrap();
+        let inner = &mut *guard;
+        if !inner.running || !inner.active {
+            return;
+        }
+
+        for hand in &frame.hands {
+            let Some(held) = inner.held.get_mut(&hand.id) else {
+                continue;
+            };
+            let trigger = Trigger {
+                event_type: GestureEventType::Hold,
+                ..held.trigger.clone()
+            };
+            let due = held.due(held.since.elapsed().as_millis() as u64);
+            let pointer = held
+                .bindings
+                .iter()
+                .find(|b| matches!(b.action, Action::MouseMove | Action::MouseDrag))
+                .map(|b| b.action.clone());
+
+            let tip = hand
+                .landmarks
+                .get(INDEX_TIP)
+                .and_then(|tip| host.calibration.transform((tip[0], tip[1])));
+            if let (Some(action), Some((tip, calibrated))) = (pointer, tip) {
+                let config = &inner.pointer_config;
+                if let Some(command) = track(config, &mut inner.tracker, tip, calibrated) {
+                    self.inject(host, &trigger, Some(action), "跟随食指", vec![command]);
+                }
+            }
+            for (binding, count) in due {
+                let reason = format!("保持第 {} 次重复", count);
+                self.run(host, &trigger, &binding, true, &reason);
+            }
+        }
+    }
+
+    /// 手势激活或停用控制，并同步给后端
+    fn activate(&self, host: &mut Host, inner: &mut Inner, trigger: &Trigger, active: bool) {
+        inner.active = active;
+        inner.tracker = PointerTracker::default();
+        if !active {
+            self.release(host, inner, "停用控制时松开");
+        }
+        let reason = if active {
+            println!("[Tauri] 控制已激活（open 手势）");
+            "open 手势激活控制"
+        } else {
+            println!("[Tauri] 控制已停用（fist 手势）");
+            "fist 手势停用控制"
+        };
+        let error = host.bridge.set_active(active).err();
+        let entry = AuditEntry::new(trigger, None, reason, Vec::new(), error);
+        self.record(host, entry);
+    }
+
+    /// 执行一次性动作
+    fn run(
+        &self,
+        host: &mut Host,
+        trigger: &Trigger,
+        binding: &ActionBinding,
+        forward: bool,
+        reason: &str,
+    ) {
+        let action = Some(binding.action.clone());
+        match commands(&binding.action, forward) {
+            Ok(commands) => {
+                self.inject(host, trigger, action, reason, commands);
+            }
+            Err(e) => {
+                eprintln!("[Tauri] 动作执行失败: {}", e);
+                let entry = AuditEntry::new(trigger, action, reason, Vec::new(), Some(e));
+                self.record(host, entry);
+            }
+        }
+    }
+
+    /// click / drag 进入时按下鼠标左键
+    fn press(&self, host: &mut Host, inner: &mut Inner, trigger: &Trigger, action: &Action) {
+        let command = InputCommand::Button {
+            button: MouseButton::Left,
+            pressed: true,
+        };
+        if self.inject(
+            host,
+            trigger,
+            Some(action.clone()),
+            "按下鼠标",
+            vec![command],
+        ) {
+            inner.pressed = Some(trigger.clone());
+        }
+    }
+
+    /// 松开由 click / drag 按下的鼠标左键，并重置鼠标跟随
+    fn release(&self, host: &mut Host, inner: &mut Inner, reason: &str) {
+        inner.tracker = PointerTracker::default();
+        let Some(trigger) = inner.pressed.take() else {
+            return;
+        };
+        let command = InputCommand::Button {
+            button: MouseButton::Left,
+            pressed: false,
+        };
+        self.inject(host, &trigger, None, reason, vec![command]);
+    }
+
+    /// 依次注入并记入审计记录，返回是否全部成功
+    fn inject(
+        &self,
+        host: &mut Host,
+        trigger: &Trigger,
+        action: Option<Action>,
+        reason: &str,
+        commands: Vec<InputCommand>,
+    ) -> bool {
+        // 审计记录中为实际注入的输入（绝对移动已换算到整个桌面）
+        let commands: Vec<_> = commands
+            .iter()
+            .map(|command| host.display.map(command))
+            .collect();
+        let error = commands
+            .iter()
+            .try_for_each(|command| host.input.execute(command))
+            .err();
+        if let Some(e) = &error {
+            eprintln!("[Tauri] 输入注入失败: {}", e);
+        }
+        let ok = error.is_none();
+        self.record(
+            host,
+            AuditEntry::new(trigger, action, reason, commands, error),
+        );
+        ok
+    }
+
+    fn record(&self, host: &mut Host, entry: AuditEntry) {
+        host.recorded.extend(self.audit.record(entry));
+    }
+}
+
+impl Held {
+    /// 到期的 hold 重复动作及其次数（每个间隔触发一次，跳帧时不补发）
+    fn due(&mut self, elapsed_ms: u64) -> Vec<(ActionBinding, u64)> {
+        let mut due = Vec::new();
+        for (index, binding) in self.bindings.iter().enumerate() {
+            let Some(interval) = binding.interval_ms else {
+                continue;
+            };
+            if binding.on != GestureEventType::Hold {
+                continue;
+            }
+            let count = elapsed_ms / u64::from(interval.max(1));
+            let fired = self.fired.entry(index).or_default();
+            if count > *fired {
+                *fired = count;
+                due.push((binding.clone(), count));
+            }
+        }
+        due
+    }
+}
+
+/// 当前生效的手势配置中需要注入输入的绑定（组合键为规范形式）
+fn current_bindings(app_handle: &AppHandle) -> Vec<ActionBinding> {
+    let state = app_handle.state::<AppState>();
+    let settings = state.settings.get();
+    let active = state.focus.resolve(&settings);
+    mapping::action_map(mapping::bindings(&settings, &active.profile)).bindings
+}
+
+fn pointer_config(settings: &Settings, screen: Option<(u32, u32)>) -> PointerConfig {
+    let executor = &settings.host.executor;
+    // 关键点已经过滤波时不再叠加固定的平滑
+    let filtered = settings.host.filter.enabled;
+    PointerConfig {
+        mode: executor.pointer_mode,
+        speed: executor.mouse_speed,
+        deadzone: executor.mouse_deadzone,
+        sensitivity: settings.action.mouse_sensitivity,
+        smoothing: if filtered {
+            0.0
+        } else {
+            settings.action.mouse_smoothing
+        },
+        delta_smoothing: if filtered { 0.0 } else { DELTA_SMOOTHING },
+        screen: screen.unwrap_or(DEFAULT_SCREEN_SIZE),
+    }
+}
+
+/// 按食指位置计算本帧的鼠标移动，没有移动时为空；校准后的位置不再应用灵敏度
+fn track(
+    config: &PointerConfig,
+    tracker: &mut PointerTracker,
+    pos: (f64, f64),
+    calibrated: bool,
+) -> Option<InputCommand> {
+    match config.mode {
+        PointerMode::Relative => {
+            // 进入手势后的第一帧只记录参考点（相当于"落指"）
+            let Some(last) = tracker.last.replace(pos) else {
+                tracker.delta = (0.0, 0.0);
+                return None;
+            };
+            let alpha = config.delta_smoothing;
+            tracker.delta = (
+                alpha * tracker.delta.0 + (1.0 - alpha) * (pos.0 - last.0),
+                alpha * tracker.delta.1 + (1.0 - alpha) * (pos.1 - last.1),
+            );
+            let (dx, dy) = tracker.delta;
+            if dx.abs() < config.deadzone && dy.abs() < config.deadzone {
+                return None;
+            }
+            let (width, height) = config.screen;
+            let dx = (dx * f64::from(width) * config.speed) as i32;
+            let dy = (dy * f64::from(height) * config.speed) as i32;
+            (dx != 0 || dy != 0).then_some(InputCommand::MoveRelative { dx, dy })
+        }
+        PointerMode::Absolute => {
+            let alpha = config.smoothing;
+            let smoothed = match tracker.smoothed {
+                Some(last) => (
+                    alpha * last.0 + (1.0 - alpha) * pos.0,
+                    alpha * last.1 + (1.0 - alpha) * pos.1,
+                ),
+                None => pos,
+            };
+            tracker.smoothed = Some(smoothed);
+            let gain = if calibrated { 1.0 } else { config.sensitivity };
+            Some(InputCommand::MoveAbsolute {
+                x: (smoothed.0 * gain).clamp(0.0, 1.0),
+                y: (smoothed.1 * gain).clamp(0.0, 1.0),
+            })
+        }
+    }
+}
+
+/// 一次性动作对应的输入（与后端 `ActionExecutor` 委托给宿主时发送的输入一致）
+fn commands(action: &Action, forward: bool) -> Result<Vec<InputCommand>, String> {
+    let keys = |keys: &str| {
+        Ok(vec![InputCommand::Keys {
+            keys: keys.to_string(),
+        }])
+    };
+    match action {
+        Action::VolumeUp => keys("VolumeUp"),
+        Action::VolumeDown => keys("VolumeDown"),
+        Action::VolumeMute => keys("VolumeMute"),
+        Action::MediaPlayPause => keys("MediaPlayPause"),
+        Action::MediaNext => keys("MediaNext"),
+        Action::MediaPrev => keys("MediaPrev"),
+        Action::SwitchWindow if forward => keys("Alt+Tab"),
+        Action::SwitchWindow => keys("Alt+Shift+Tab"),
+        Action::Screenshot => keys("PrintScreen"),
+        Action::SlideNext => keys("PageDown"),
+        Action::SlidePrev => keys("PageUp"),
+        Action::KeyChord { keys: chord } => keys(chord),
+        Action::MouseScroll { amount } => Ok(vec![InputCommand::Scroll { amount: *amount }]),
+        Action::Text { text } => Ok(vec![InputCommand::Text { text: text.clone() }]),
+        // 鼠标动作在进入、保持与退出时分别处理，宿主动作由 mapping 执行
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn direction_name(direction: &str) -> &str {
+    match direction {
+        "left" => "左",
+        "right" => "右",
+        "up" => "上",
+        "down" => "下",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{InputDriver, InputEvent, InputSettings};
+    use phantom_protocol::HandData;
+    use serde_json::Map;
+
+    /// mock 注入与未连接的后端
+    struct Fixture {
+        input: InputInjector,
+        display: DisplayTopology,
+        calibration: CalibrationStore,
+        bridge: BackendBridge,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let input = InputInjector::new();
+            input.configure(&InputSettings {
+                driver: InputDriver::Mock,
+            });
+            Self {
+                input,
+                display: DisplayTopology::new(),
+                calibration: CalibrationStore::new(),
+                bridge: BackendBridge::new(),
+            }
+        }
+
+        fn host(&self) -> Host<'_> {
+            Host {
+                input: &self.input,
+                display: &self.display,
+                calibration: &self.calibration,
+                bridge: &self.bridge,
+                recorded: Vec::new(),
+            }
+        }
+
+        /// 由宿主执行的执行器（相对定位，不平滑）
+        fn executor(&self) -> ActionExecutor {
+            let mut settings = Settings::default();
+            settings.host.executor.enabled = true;
+            settings.host.filter.enabled = true;
+            let executor = ActionExecutor::new();
+            executor.apply_settings(&mut self.host(), &settings);
+            assert!(executor.status().running);
+            executor
+        }
+
+        fn event(
+            &self,
+            executor: &ActionExecutor,
+            bindings: &[ActionBinding],
+            gesture: &str,
+            event_type: GestureEventType,
+        ) {
+            let event = GestureEvent {
+                event_type,
+                gesture: gesture.to_string(),
+                hand_id: "0".to_string(),
+                timestamp: 0.0,
+                hold_duration: 0.0,
+                confidence: 1.0,
+                meta: Map::new(),
+            };
+            executor.handle_event(&mut self.host(), bindings, &event);
+        }
+
+        /// 一帧只有一只手，食指指尖位于 `tip`
+        fn frame(&self, executor: &ActionExecutor, tip: (f64, f64)) {
+            let mut landmarks = vec![[0.5, 0.5, 0.0]; 21];
+            landmarks[INDEX_TIP] = [tip.0, tip.1, 0.0];
+            let frame = FrameData {
+                frame_id: 0,
+                hands: vec![HandData {
+                    id: "0".to_string(),
+                    handedness: "Right".to_string(),
+                    landmarks,
+                    gesture: String::new(),
+                    gesture_score: 1.0,
+                    state: "held".to_string(),
+                }],
+                inference_time_ms: 0.0,
+                active: true,
+            };
+            executor.handle_frame(&mut self.host(), &frame);
+        }
+
+        fn recorded(&self) -> Vec<InputEvent> {
+            self.input.take_recorded().unwrap()
+        }
+    }
+
+    fn binding(gesture: &str, on: GestureEventType, action: Action) -> ActionBinding {
+        ActionBinding {
+            gesture: gesture.to_string(),
+            on,
+            action,
+            interval_ms: None,
+        }
+    }
+
+    fn left(pressed: bool) -> InputEvent {
+        InputEvent::Button {
+            button: MouseButton::Left,
+            pressed,
+        }
+    }
+
+    fn keys(keys: &str) -> Vec<InputCommand> {
+        vec![InputCommand::Keys {
+            keys: keys.to_string(),
+        }]
+    }
+
+    #[test]
+    fn open_activates_and_fist_deactivates() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+
+        fixture.event(&executor, &[], "open", GestureEventType::Enter);
+        assert!(executor.status().active);
+        // 已激活时再次 open 不重复激活
+        fixture.event(&executor, &[], "open", GestureEventType::Enter);
+        fixture.event(&executor, &[], "fist", GestureEventType::Enter);
+        assert!(!executor.status().active);
+
+        let reasons: Vec<_> = executor.audit().into_iter().map(|e| e.reason).collect();
+        assert_eq!(reasons, ["open 手势激活控制", "fist 手势停用控制"]);
+        // 未连接后端时记录同步失败，本地状态仍然切换
+        assert!(executor.audit().iter().all(|e| e.error.is_some()));
+    }
+
+    #[test]
+    fn rebound_fist_runs_its_binding() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [binding(
+            "fist",
+            GestureEventType::Enter,
+            Action::MediaPlayPause,
+        )];
+
+        fixture.event(&executor, &bindings, "open", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "fist", GestureEventType::Enter);
+        assert!(executor.status().active);
+        let last = executor.audit().pop().unwrap();
+        assert_eq!(last.reason, "进入手势");
+        assert_eq!(last.commands, keys("MediaPlayPause"));
+    }
+
+    #[test]
+    fn gestures_are_ignored_until_activated() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [binding(
+            "pinch",
+            GestureEventType::Enter,
+            Action::MouseClick,
+        )];
+
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Exit);
+        assert!(fixture.recorded().is_empty());
+        assert!(executor.audit().is_empty());
+    }
+
+    #[test]
+    fn click_presses_on_enter_and_releases_on_exit() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [binding(
+            "pinch",
+            GestureEventType::Enter,
+            Action::MouseClick,
+        )];
+
+        fixture.event(&executor, &bindings, "open", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Enter);
+        assert_eq!(fixture.recorded(), [left(true)]);
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Exit);
+        assert_eq!(fixture.recorded(), [left(false)]);
+        assert_eq!(executor.audit().pop().unwrap().reason, "退出手势");
+    }
+
+    #[test]
+    fn deactivating_releases_the_drag() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [binding("pinch", GestureEventType::Hold, Action::MouseDrag)];
+
+        fixture.event(&executor, &bindings, "open", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "fist", GestureEventType::Enter);
+        assert_eq!(fixture.recorded(), [left(true), left(false)]);
+        // 之后的退出不再重复松开
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Exit);
+        assert!(fixture.recorded().is_empty());
+    }
+
+    #[test]
+    fn drag_follows_the_index_tip() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [binding("pinch", GestureEventType::Hold, Action::MouseDrag)];
+
+        fixture.event(&executor, &bindings, "open", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "pinch", GestureEventType::Enter);
+        fixture.recorded();
+        fixture.frame(&executor, (0.5, 0.5));
+        fixture.frame(&executor, (0.625, 0.5));
+        fixture.frame(&executor, (0.75, 0.5));
+        let (width, _) = DEFAULT_SCREEN_SIZE;
+        let dx = (0.125 * f64::from(width) * ExecutorSettings::default().mouse_speed) as i32;
+        assert_eq!(
+            fixture.recorded(),
+            [
+                InputEvent::MoveRelative { dx, dy: 0 },
+                InputEvent::MoveRelative { dx, dy: 0 },
+            ]
+        );
+        // 连续的移动在审计记录中合并为一条
+        let last = executor.audit().pop().unwrap();
+        assert_eq!(last.reason, "跟随食指");
+        assert_eq!(last.merged, 1);
+        assert_eq!(
+            last.commands,
+            [InputCommand::MoveRelative { dx: 2 * dx, dy: 0 }]
+        );
+    }
+
+    #[test]
+    fn slides_run_direction_bindings() {
+        let fixture = Fixture::new();
+        let executor = fixture.executor();
+        let bindings = [
+            binding("left", GestureEventType::Slide, Action::SwitchWindow),
+            binding("right", GestureEventType::Slide, Action::SwitchWindow),
+        ];
+
+        fixture.event(&executor, &bindings, "open", GestureEventType::Enter);
+        fixture.event(&executor, &bindings, "slide_left", GestureEventType::Slide);
+        fixture.event(&executor, &bindings, "slide_right", GestureEventType::Slide);
+        let entries = executor.audit();
+        let slides: Vec<_> = entries[1..]
+            .iter()
+            .map(|e| (e.reason.as_str(), e.commands.clone()))
+            .collect();
+        assert_eq!(
+            slides,
+            [
+                ("向左滑动", keys("Alt+Shift+Tab")),
+                ("向右滑动", keys("Alt+Tab")),
+            ]
+        );
+    }
+
+    #[test]
+    fn hold_repeats_fire_once_per_interval() {
+        let repeat = ActionBinding {
+            interval_ms: Some(100),
+            ..binding("ok", GestureEventType::Hold, Action::VolumeUp)
+        };
+        // 没有间隔或不在 hold 上的绑定不重复
+        let once = binding("ok", GestureEventType::Hold, Action::VolumeDown);
+        let enter = ActionBinding {
+            interval_ms: Some(100),
+            ..binding("ok", GestureEventType::Enter, Action::VolumeMute)
+        };
+        let mut held = Held {
+            trigger: Trigger {
+                gesture: "ok".to_string(),
+                event_type: GestureEventType::Enter,
+                hand_id: "0".to_string(),
+            },
+            since: Instant::now(),
+            bindings: vec![once, enter, repeat.clone()],
+            fired: HashMap::new(),
+        };
+
+        let counts = |due: Vec<(ActionBinding, u64)>| {
+            assert!(due.iter().all(|(binding, _)| *binding == repeat));
+            due.into_iter().map(|(_, count)| count).collect::<Vec<_>>()
+        };
+        assert!(held.due(99).is_empty());
+        assert_eq!(counts(held.due(100)), [1]);
+        assert!(held.due(150).is_empty());
+        // 跳帧时只触发一次，次数按经过的时间计算
+        assert_eq!(counts(held.due(350)), [3]);
+        assert_eq!(held.fired[&2], 3);
+    }
+
+    fn config(mode: PointerMode) -> PointerConfig {
+        PointerConfig {
+            mode,
+            speed: 1.0,
+            deadzone: 0.01,
+            sensitivity: 1.5,
+            smoothing: 0.0,
+            delta_smoothing: 0.0,
+            screen: (1024, 512),
+        }
+    }
+
+    #[test]
+    fn relative_tracking_skips_first_frame_and_deadzone() {
+        let config = config(PointerMode::Relative);
+        let mut tracker = PointerTracker::default();
+        let mut track = |pos| track(&config, &mut tracker, pos, false);
+
+        assert_eq!(track((0.5, 0.5)), None);
+        assert_eq!(track((0.5078125, 0.4921875)), None);
+        assert_eq!(
+            track((0.6328125, 0.3671875)),
+            Some(InputCommand::MoveRelative { dx: 128, dy: -64 })
+        );
+        // 只有一个轴超出死区时两个轴都移动
+        assert_eq!(
+            track((0.8828125, 0.375)),
+            Some(InputCommand::MoveRelative { dx: 256, dy: 4 })
+        );
+    }
+
+    #[test]
+    fn relative_tracking_smooths_the_delta() {
+        let config = PointerConfig {
+            delta_smoothing: 0.5,
+            ..config(PointerMode::Relative)
+        };
+        let mut tracker = PointerTracker::default();
+        let mut track = |pos| track(&config, &mut tracker, pos, false);
+
+        track((0.0, 0.0));
+        assert_eq!(
+            track((0.25, 0.0)),
+            Some(InputCommand::MoveRelative { dx: 128, dy: 0 })
+        );
+        assert_eq!(
+            track((0.5, 0.0)),
+            Some(InputCommand::MoveRelative { dx: 192, dy: 0 })
+        );
+    }
+
+    #[test]
+    fn absolute_tracking_is_scaled_and_clamped() {
+        let config = config(PointerMode::Absolute);
+        let mut tracker = PointerTracker::default();
+        type Case = ((f64, f64), bool, (f64, f64));
+        let cases: [Case; 4] = [
+            ((0.25, 0.5), false, (0.375, 0.75)),
+            ((0.9, -0.1), false, (1.0, 0.0)),
+            // 已校准的位置不再乘以灵敏度
+            ((0.9, 0.2), true, (0.9, 0.2)),
+            ((1.2, 0.5), true, (1.0, 0.5)),
+        ];
+        for (pos, calibrated, (x, y)) in cases {
+            assert_eq!(
+                track(&config, &mut tracker, pos, calibrated),
+                Some(InputCommand::MoveAbsolute { x, y }),
+                "{pos:?}"
+            );
+        }
+
+        let config = PointerConfig {
+            smoothing: 0.5,
+            ..config
+        };
+        let mut tracker = PointerTracker::default();
+        track(&config, &mut tracker, (0.25, 0.25), true);
+        assert_eq!(
+            track(&config, &mut tracker, (0.75, 0.5), true),
+            Some(InputCommand::MoveAbsolute { x: 0.5, y: 0.375 })
+        );
+    }
+
+    #[test]
+    fn one_shot_actions_map_to_commands() {
+        let cases = [
+            (Action::VolumeUp, true, keys("VolumeUp")),
+            (Action::SwitchWindow, true, keys("Alt+Tab")),
+            (Action::SwitchWindow, false, keys("Alt+Shift+Tab")),
+            (Action::SlideNext, true, keys("PageDown")),
+            (
+                Action::KeyChord {
+                    keys: "Ctrl+C".to_string(),
+                },
+                true,
+                keys("Ctrl+C"),
+            ),
+            (
+                Action::MouseScroll { amount: -3 },
+                true,
+                vec![InputCommand::Scroll { amount: -3 }],
+            ),
+            (
+                Action::Text {
+                    text: "hi".to_string(),
+                },
+                true,
+                vec![InputCommand::Text {
+                    text: "hi".to_string(),
+                }],
+            ),
+            // 鼠标动作与宿主动作不在这里注入
+            (Action::MouseClick, true, Vec::new()),
+            (
+                Action::OpenUrl {
+                    url: "https://example.com".to_string(),
+                },
+                true,
+                Vec::new(),
+            ),
+        ];
+        for (action, forward, expected) in cases {
+            assert_eq!(commands(&action, forward).unwrap(), expected, "{action:?}");
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! 关键点滤波
+//!
+//! 后端每帧的 21 个关键点在进入校准（见 [`crate::calibration`]）、宿主的鼠标控制
+//! （见 [`crate::executor`]）与界面显示之前，先经过 `host.filter` 配置的滤波管线：
+//! 一级平滑（One Euro、卡尔曼或双指数平滑，见 [`stages`]）与可选的死区。
+//! 每只手的每个关键点各自维护滤波状态，手消失后重置。后端自己执行动作时仍使用自己的平滑。
+//!
+//! 开启 `host.filter.trace` 后记录指定关键点的原始位置与各级输出（`filter-trace` 事件与
+//! `get_filter_trace` 命令），用于对比调参
+
+mod stages;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use phantom_protocol::FrameData;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use stages::{Deadzone, DoubleExponential, Kalman, LandmarkFilter, OneEuro};
+
+use crate::backend::unix_millis;
+
+/// 最多保留的调试记录数（约 20 秒）
+const MAX_TRACE: usize = 600;
+
+/// 手消失超过该时间后重新出现时重置滤波状态
+const RESET_GAP: Duration = Duration::from_millis(500);
+
+/// 帧间隔的下限，避免同一时刻收到的两帧使速度发散
+const MIN_DT: f64 = 0.001;
+
+/// 平滑滤波器（`host.filter.smoother`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Smoother {
+    /// 不平滑（只使用死区）
+    None,
+    #[default]
+    OneEuro,
+    Kalman,
+    DoubleExponential,
+}
+
+/// One Euro 滤波参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OneEuroSettings {
+    /// 静止时的截止频率（Hz），越小越稳定
+    pub min_cutoff: f64,
+    /// 截止频率随速度增加的系数，越大快速移动时延迟越小
+    pub beta: f64,
+    /// 速度估计的截止频率（Hz）
+    pub d_cutoff: f64,
+}
+
+impl Default for OneEuroSettings {
+    fn default() -> Self {
+        Self {
+            min_cutoff: 1.0,
+            beta: 10.0,
+            d_cutoff: 1.0,
+        }
+    }
+}
+
+/// 卡尔曼滤波参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KalmanSettings {
+    /// 过程噪声（加速度的方差），越大越跟手
+    pub process_noise: f64,
+    /// 观测噪声（关键点抖动的方差），越大越平滑
+    pub measurement_noise: f64,
+}
+
+impl Default for KalmanSettings {
+    fn default() -> Self {
+        Self {
+            process_noise: 0.01,
+            measurement_noise: 1e-5,
+        }
+    }
+}
+
+/// 双指数平滑参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoubleExponentialSettings {
+    /// 位置的平滑系数，越大越跟手
+    pub alpha: f64,
+    /// 趋势的平滑系数
+    pub gamma: f64,
+}
+
+impl Default for DoubleExponentialSettings {
+    fn default() -> Self {
+        Self {
+            alpha: 0.5,
+            gamma: 0.3,
+        }
+    }
+}
+
+/// 调试记录设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceSettings {
+    pub enabled: bool,
+    /// 记录的关键点序号（默认为食指指尖）
+    pub landmark: usize,
+}
+
+impl Default for TraceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            landmark: crate::executor::INDEX_TIP,
+        }
+    }
+}
+
+/// 关键点滤波设置（`host.filter`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterSettings {
+    /// 是否启用；启用后宿主执行动作时不再叠加 `action.mouse_smoothing` 等固定平滑
+    pub enabled: bool,
+    pub smoother: Smoother,
+    pub one_euro: OneEuroSettings,
+    pub kalman: KalmanSettings,
+    pub double_exponential: DoubleExponentialSettings,
+    /// 死区半径（归一化坐标），为 0 时关闭
+    pub deadzone: f64,
+    pub trace: TraceSettings,
+}
+
+/// 一级滤波的输出
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStage {
+    pub name: &'static str,
+    pub value: [f64; 3],
+}
+
+/// 一条调试记录（`filter-trace` 事件与 `get_filter_trace` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEntry {
+    /// 时间（Unix 毫秒）
+    pub timestamp: u64,
+    pub frame_id: u64,
+    pub hand_id: String,
+    pub landmark: usize,
+    /// 后端发送的原始位置
+    pub raw: [f64; 3],
+    /// 依次经过各级滤波后的位置，最后一项为最终结果；未启用滤波时为空
+    pub stages: Vec<TraceStage>,
+}
+
+/// 一只手的滤波状态
+struct HandFilter {
+    last: Instant,
+    /// 关键点序号 -> 各级滤波
+    landmarks: Vec<Vec<Box<dyn LandmarkFilter>>>,
+}
+
+struct Inner {
+    settings: FilterSettings,
+    /// 手 ID -> 滤波状态
+    hands: HashMap<String, HandFilter>,
+    trace: VecDeque<TraceEntry>,
+}
+
+/// 关键点滤波管线
+pub struct FilterPipeline {
+    inner: Mutex<Inner>,
+}
+
+impl FilterPipeline {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                settings: FilterSettings::default(),
+                hands: HashMap::new(),
+                trace: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// 应用设置并重置滤波状态
+    pub fn configure(&self, settings: &FilterSettings) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.settings == *settings {
+            return;
+        }
+        inner.settings = settings.clone();
+        inner.hands.clear();
+        let names: Vec<_> = stages(settings).iter().map(|stage| stage.name()).collect();
+        if names.is_empty() {
+            println!("[Tauri] 关键点滤波: 关闭");
+        } else {
+            println!("[Tauri] 关键点滤波: {}", names.join(" -> "));
+        }
+    }
+
+    /// 滤波一帧的所有关键点；开启调试记录时记录指定关键点并推送 `filter-trace` 事件
+    pub fn apply(&self, app_handle: &AppHandle, mut frame: FrameData) -> FrameData {
+        let mut inner = self.inner.lock().unwrap();
+        let settings = inner.settings.clone();
+        if !settings.enabled && !settings.trace.enabled {
+            return frame;
+        }
+
+        let now = Instant::now();
+        inner.hands.retain(|id, hand| {
+            frame.hands.iter().any(|h| &h.id == id) || now - hand.last < RESET_GAP
+        });
+        let mut traced = Vec::new();
+        for hand in &mut frame.hands {
+            let state = inner
+                .hands
+                .entry(hand.id.clone())
+                .or_insert_with(|| HandFilter {
+                    last: now,
+                    landmarks: Vec::new(),
+                });
+            let dt = (now - state.last).as_secs_f64().max(MIN_DT);
+            if now - state.last >= RESET_GAP {
+                state.landmarks.clear();
+            }
+            state.last = now;
+
+            for (index, point) in hand.landmarks.iter_mut().enumerate() {
+                if state.landmarks.len() <= index {
+                    state.landmarks.push(stages(&settings));
+                }
+                let trace = settings.trace.enabled && index == settings.trace.landmark;
+                let raw = *point;
+                let mut outputs = Vec::new();
+                for stage in &mut state.landmarks[index] {
+                    *point = stage.filter(*point, dt);
+                    if trace {
+                        outputs.push(TraceStage {
+                            name: stage.name(),
+                            value: *point,
+                        });
+                    }
+                }
+                if trace {
+                    traced.push(TraceEntry {
+                        timestamp: unix_millis(),
+                        frame_id: frame.frame_id,
+                        hand_id: hand.id.clone(),
+                        landmark: index,
+                        raw,
+                        stages: outputs,
+                    });
+                }
+            }
+        }
+
+        for entry in &traced {
+            if inner.trace.len() >= MAX_TRACE {
+                inner.trace.pop_front();
+            }
+            inner.trace.push_back(entry.clone());
+        }
+        drop(inner);
+        for entry in traced {
+            let _ = app_handle.emit_all("filter-trace", entry);
+        }
+        frame
+    }
+
+    /// 最近的调试记录（按时间先后）
+    pub fn trace(&self) -> Vec<TraceEntry> {
+        self.inner.lock().unwrap().trace.iter().cloned().collect()
+    }
+
+    pub fn clear_trace(&self) {
+        self.inner.lock().unwrap().trace.clear();
+    }
+}
+
+/// 按设置组装一个关键点的各级滤波，未启用时为空
+fn stages(settings: &FilterSettings) -> Vec<Box<dyn LandmarkFilter>> {
+    let mut stages: Vec<Box<dyn LandmarkFilter>> = Vec::new();
+    if !settings.enabled {
+        return stages;
+    }
+    match settings.smoother {
+        Smoother::None => {}
+        Smoother::OneEuro => stages.push(Box::new(OneEuro::new(&settings.one_euro))),
+        Smoother::Kalman => stages.push(Box::new(Kalman::new(&settings.kalman))),
+        Smoother::DoubleExponential => stages.push(Box::new(DoubleExponential::new(
+            &settings.double_exponential,
+        ))),
+    }
+    if settings.deadzone > 0.0 {
+        stages.push(Box::new(Deadzone::new(settings.deadzone)));
+    }
+    stages
+}
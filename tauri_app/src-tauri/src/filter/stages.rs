@@ -0,0 +1,301 @@
+//! 滤波器
+//!
+//! 每个滤波器实例处理一个关键点的 [x, y, z]，`dt` 为与上一帧的间隔（秒）；
+//! 第一帧原样输出并作为初值
+
+use std::f64::consts::PI;
+
+use super::{DoubleExponentialSettings, KalmanSettings, OneEuroSettings};
+
+/// 一级滤波
+pub trait LandmarkFilter: Send {
+    /// 名称（调试记录中使用，与 `host.filter.smoother` 的取值一致）
+    fn name(&self) -> &'static str;
+
+    fn filter(&mut self, value: [f64; 3], dt: f64) -> [f64; 3];
+}
+
+/// One Euro 滤波：低速时截止频率低（去抖），速度越快截止频率越高（减少延迟）
+pub struct OneEuro {
+    min_cutoff: f64,
+    beta: f64,
+    d_cutoff: f64,
+    /// 上一帧的输出与平滑后的速度
+    state: Option<([f64; 3], [f64; 3])>,
+}
+
+impl OneEuro {
+    pub fn new(settings: &OneEuroSettings) -> Self {
+        Self {
+            min_cutoff: settings.min_cutoff,
+            beta: settings.beta,
+            d_cutoff: settings.d_cutoff,
+            state: None,
+        }
+    }
+}
+
+impl LandmarkFilter for OneEuro {
+    fn name(&self) -> &'static str {
+        "one_euro"
+    }
+
+    fn filter(&mut self, value: [f64; 3], dt: f64) -> [f64; 3] {
+        let Some((last, last_speed)) = self.state else {
+            self.state = Some((value, [0.0; 3]));
+            return value;
+        };
+        let speed: [f64; 3] = std::array::from_fn(|i| {
+            let raw = (value[i] - last[i]) / dt;
+            last_speed[i] + smoothing(self.d_cutoff, dt) * (raw - last_speed[i])
+        });
+        let output: [f64; 3] = std::array::from_fn(|i| {
+            let cutoff = self.min_cutoff + self.beta * speed[i].abs();
+            last[i] + smoothing(cutoff, dt) * (value[i] - last[i])
+        });
+        self.state = Some((output, speed));
+        output
+    }
+}
+
+/// 截止频率（Hz）对应的一阶低通系数
+fn smoothing(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+/// 每个坐标的匀速模型状态
+#[derive(Clone, Copy)]
+struct Axis {
+    position: f64,
+    velocity: f64,
+    /// 协方差矩阵 [[p00, p01], [p01, p11]]
+    p00: f64,
+    p01: f64,
+    p11: f64,
+}
+
+/// 匀速模型的卡尔曼滤波，三个坐标各自独立
+pub struct Kalman {
+    process_noise: f64,
+    measurement_noise: f64,
+    state: Option<[Axis; 3]>,
+}
+
+impl Kalman {
+    pub fn new(settings: &KalmanSettings) -> Self {
+        Self {
+            process_noise: settings.process_noise,
+            measurement_noise: settings.measurement_noise,
+            state: None,
+        }
+    }
+}
+
+impl LandmarkFilter for Kalman {
+    fn name(&self) -> &'static str {
+        "kalman"
+    }
+
+    fn filter(&mut self, value: [f64; 3], dt: f64) -> [f64; 3] {
+        let (q, r) = (self.process_noise, self.measurement_noise);
+        let Some(axes) = &mut self.state else {
+            self.state = Some(value.map(|position| Axis {
+                position,
+                velocity: 0.0,
+                p00: r,
+                p01: 0.0,
+                p11: 1.0,
+            }));
+            return value;
+        };
+
+        for (axis, &measured) in axes.iter_mut().zip(&value) {
+            // 预测：位置按速度外推，过程噪声为连续白噪声加速度
+            axis.position += axis.velocity * dt;
+            axis.p00 += dt * (2.0 * axis.p01 + dt * axis.p11) + q * dt.powi(3) / 3.0;
+            axis.p01 += dt * axis.p11 + q * dt.powi(2) / 2.0;
+            axis.p11 += q * dt;
+
+            // 更新：只观测位置
+            let s = axis.p00 + r;
+            let (k0, k1) = (axis.p00 / s, axis.p01 / s);
+            let residual = measured - axis.position;
+            axis.position += k0 * residual;
+            axis.velocity += k1 * residual;
+            axis.p11 -= k1 * axis.p01;
+            axis.p01 *= 1.0 - k0;
+            axis.p00 *= 1.0 - k0;
+        }
+        axes.map(|axis| axis.position)
+    }
+}
+
+/// 双指数平滑（Holt）：同时平滑位置与趋势，比单次 EMA 延迟小；按帧计算，不使用 `dt`
+pub struct DoubleExponential {
+    alpha: f64,
+    gamma: f64,
+    /// 平滑后的位置与趋势
+    state: Option<([f64; 3], [f64; 3])>,
+}
+
+impl DoubleExponential {
+    pub fn new(settings: &DoubleExponentialSettings) -> Self {
+        Self {
+            alpha: settings.alpha,
+            gamma: settings.gamma,
+            state: None,
+        }
+    }
+}
+
+impl LandmarkFilter for DoubleExponential {
+    fn name(&self) -> &'static str {
+        "double_exponential"
+    }
+
+    fn filter(&mut self, value: [f64; 3], _dt: f64) -> [f64; 3] {
+        let Some((level, trend)) = self.state else {
+            self.state = Some((value, [0.0; 3]));
+            return value;
+        };
+        let next: [f64; 3] = std::array::from_fn(|i| {
+            self.alpha * value[i] + (1.0 - self.alpha) * (level[i] + trend[i])
+        });
+        let trend: [f64; 3] = std::array::from_fn(|i| {
+            self.gamma * (next[i] - level[i]) + (1.0 - self.gamma) * trend[i]
+        });
+        self.state = Some((next, trend));
+        next
+    }
+}
+
+/// 死区：在画面平面内移动不超过半径时保持不动，超出后只跟随超出的部分（不跳变）
+pub struct Deadzone {
+    radius: f64,
+    last: Option<[f64; 3]>,
+}
+
+impl Deadzone {
+    pub fn new(radius: f64) -> Self {
+        Self { radius, last: None }
+    }
+}
+
+impl LandmarkFilter for Deadzone {
+    fn name(&self) -> &'static str {
+        "deadzone"
+    }
+
+    fn filter(&mut self, value: [f64; 3], _dt: f64) -> [f64; 3] {
+        let Some(last) = self.last else {
+            self.last = Some(value);
+            return value;
+        };
+        let distance = (value[0] - last[0]).hypot(value[1] - last[1]);
+        if distance <= self.radius {
+            return last;
+        }
+        let t = 1.0 - self.radius / distance;
+        let output = std::array::from_fn(|i| last[i] + (value[i] - last[i]) * t);
+        self.last = Some(output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 30 fps 的帧间隔
+    const DT: f64 = 1.0 / 30.0;
+
+    /// 使用默认参数的平滑滤波器
+    fn smoothers() -> Vec<Box<dyn LandmarkFilter>> {
+        vec![
+            Box::new(OneEuro::new(&OneEuroSettings::default())),
+            Box::new(Kalman::new(&KalmanSettings::default())),
+            Box::new(DoubleExponential::new(&DoubleExponentialSettings::default())),
+        ]
+    }
+
+    fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+        (0..3).map(|i| (a[i] - b[i]).abs()).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn first_sample_passes_through() {
+        let value = [0.42, 0.58, -0.03];
+        let mut filters = smoothers();
+        filters.push(Box::new(Deadzone::new(0.01)));
+        for filter in &mut filters {
+            assert_eq!(filter.filter(value, DT), value, "{}", filter.name());
+        }
+    }
+
+    #[test]
+    fn constant_input_stays_constant() {
+        let value = [0.3, 0.7, -0.05];
+        for mut filter in smoothers() {
+            for _ in 0..100 {
+                let output = filter.filter(value, DT);
+                assert!(distance(output, value) < 1e-12, "{}", filter.name());
+            }
+        }
+    }
+
+    #[test]
+    fn step_input_converges() {
+        let (from, to) = ([0.2, 0.3, 0.0], [0.6, 0.7, -0.1]);
+        for mut filter in smoothers() {
+            filter.filter(from, DT);
+            let first = filter.filter(to, DT);
+            assert!(distance(first, to) > 0.0, "{} 没有平滑", filter.name());
+            let mut output = first;
+            for _ in 0..300 {
+                output = filter.filter(to, DT);
+            }
+            assert!(distance(output, to) < 1e-3, "{}: {output:?}", filter.name());
+        }
+    }
+
+    #[test]
+    fn deadzone_holds_inside_radius() {
+        let mut deadzone = Deadzone::new(0.01);
+        let origin = [0.5, 0.5, 0.0];
+        deadzone.filter(origin, DT);
+        for value in [[0.505, 0.5, 0.0], [0.5, 0.509, 0.02], [0.493, 0.493, 0.0]] {
+            assert_eq!(deadzone.filter(value, DT), origin);
+        }
+    }
+
+    #[test]
+    fn deadzone_follows_outside_radius_without_jump() {
+        let radius = 0.01;
+        let mut deadzone = Deadzone::new(radius);
+        deadzone.filter([0.5, 0.5, 0.0], DT);
+
+        // 刚越过半径时只移动超出的部分
+        let output = deadzone.filter([0.5105, 0.5, 0.0], DT);
+        assert!(distance(output, [0.5005, 0.5, 0.0]) < 1e-12, "{output:?}");
+
+        // 持续移动时输出落后输入一个半径，每帧的移动不超过输入的移动
+        let planar = |a: [f64; 3], b: [f64; 3]| (a[0] - b[0]).hypot(a[1] - b[1]);
+        let mut last = output;
+        for step in 1..=20 {
+            let value = [0.5105 + 0.003 * step as f64, 0.5 + 0.002 * step as f64, 0.0];
+            let output = deadzone.filter(value, DT);
+            let lag = planar(value, output);
+            assert!((lag - radius).abs() < 1e-12, "{lag}");
+            assert!(
+                planar(output, last) <= 0.003f64.hypot(0.002) + 1e-12,
+                "{output:?}"
+            );
+            last = output;
+        }
+
+        // 往回移动不超过半径时保持不动
+        let back = [last[0] + 0.005, last[1], 0.0];
+        assert_eq!(deadzone.filter(back, DT), last);
+    }
+}
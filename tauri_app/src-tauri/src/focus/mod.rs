@@ -0,0 +1,184 @@
+//! 按前台应用切换手势配置
+//!
+//! 宿主在后台线程监听前台窗口（Linux 上通过 X11 的 `_NET_ACTIVE_WINDOW`，见 [`x11`]），
+//! 按 `host.app_profiles` 中的规则（见 [`rules`]）匹配窗口类名与标题：
+//! 命中时使用规则指定的手势配置，否则使用 `host.profile`。
+//! 生效的配置变化时推送给后端，并显示在托盘中
+
+mod rules;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::settings::Settings;
+use crate::AppState;
+use crate::{mapping, profiles};
+pub use rules::AppProfileRules;
+
+/// 前台窗口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FocusedWindow {
+    /// `WM_CLASS` 类名（如 `Vlc`）
+    pub class: String,
+    /// `WM_CLASS` 实例名（如 `vlc`）
+    pub instance: String,
+    pub title: String,
+}
+
+/// 生效的手势配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActiveProfile {
+    pub profile: String,
+    /// 命中规则的应用（窗口类名），按 `host.profile` 生效时为空
+    pub app: Option<String>,
+}
+
+/// 按应用切换的状态（`get_app_profile_status` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusStatus {
+    pub window: Option<FocusedWindow>,
+    pub active: Option<ActiveProfile>,
+    /// 无法检测前台窗口的原因（如 Wayland 会话或不支持的平台）
+    pub error: Option<String>,
+}
+
+struct Inner {
+    window: Option<FocusedWindow>,
+    /// 最近一次生效的配置
+    active: Option<ActiveProfile>,
+    error: Option<String>,
+}
+
+/// 前台窗口与生效配置
+pub struct FocusTracker {
+    inner: Mutex<Inner>,
+}
+
+impl FocusTracker {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                window: None,
+                active: None,
+                error: None,
+            }),
+        }
+    }
+
+    pub fn status(&self) -> FocusStatus {
+        let inner = self.inner.lock().unwrap();
+        FocusStatus {
+            window: inner.window.clone(),
+            active: inner.active.clone(),
+            error: inner.error.clone(),
+        }
+    }
+
+    /// 按当前前台窗口计算应生效的配置
+    pub fn resolve(&self, settings: &Settings) -> ActiveProfile {
+        let inner = self.inner.lock().unwrap();
+        let rules = &settings.host.app_profiles;
+        match inner
+            .window
+            .as_ref()
+            .and_then(|window| rules.find(window).map(|rule| (rule, app_name(window))))
+        {
+            Some((rule, app)) => ActiveProfile {
+                profile: rule.profile.clone(),
+                app: Some(app),
+            },
+            None => ActiveProfile {
+                profile: settings.host.profile.clone(),
+                app: None,
+            },
+        }
+    }
+
+    /// 重新计算生效的配置，变化时更新托盘并推送给后端（未连接时在连接后推送）
+    pub fn sync(&self, app_handle: &AppHandle) -> Result<(), String> {
+        let state = app_handle.state::<AppState>();
+        let settings = state.settings.get();
+        let resolved = self.resolve(&settings);
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.active.as_ref() == Some(&resolved) {
+                return Ok(());
+            }
+            inner.active = Some(resolved.clone());
+        }
+
+        let profile = profiles::find(&resolved.profile)
+            .ok_or_else(|| format!("未知的手势配置: {}", resolved.profile))?;
+        state
+            .tray
+            .update(app_handle, |view| view.active_profile = resolved.clone());
+        mapping::push(app_handle, &settings, profile.id)?;
+        match &resolved.app {
+            Some(app) => println!("[Tauri] 已切换手势配置: {}（{}）", profile.name, app),
+            None => println!("[Tauri] 已切换手势配置: {}", profile.name),
+        }
+        let _ = app_handle.emit_all("profile-changed", &resolved);
+        Ok(())
+    }
+
+    fn set_window(&self, app_handle: &AppHandle, window: Option<FocusedWindow>) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.window == window {
+                return;
+            }
+            inner.window = window;
+        }
+        if let Err(e) = self.sync(app_handle) {
+            eprintln!("[Tauri] 按应用切换手势配置失败: {}", e);
+        }
+    }
+
+    fn set_error(&self, error: String) {
+        self.inner.lock().unwrap().error = Some(error);
+    }
+}
+
+/// 启动前台窗口监听线程
+pub fn spawn_watcher(app_handle: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("focus-watcher".to_string())
+        .spawn(move || {
+            let result = watch(&app_handle);
+            if let Err(e) = result {
+                eprintln!("[Tauri] 无法检测前台窗口，按应用切换手势配置不可用: {}", e);
+                app_handle.state::<AppState>().focus.set_error(e);
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("[Tauri] 无法启动前台窗口监听线程: {}", e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn watch(app_handle: &AppHandle) -> Result<(), String> {
+    x11::watch(|window| {
+        app_handle
+            .state::<AppState>()
+            .focus
+            .set_window(app_handle, window)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn watch(_app_handle: &AppHandle) -> Result<(), String> {
+    Err("当前平台暂不支持".to_string())
+}
+
+/// 用于显示的应用名（优先使用实例名，通常为小写的程序名）
+fn app_name(window: &FocusedWindow) -> String {
+    if window.instance.is_empty() {
+        window.class.clone()
+    } else {
+        window.instance.clone()
+    }
+}
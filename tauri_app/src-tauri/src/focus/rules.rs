@@ -0,0 +1,231 @@
+//! 按应用切换手势配置的规则
+//!
+//! 规则按顺序匹配，第一条命中的规则生效。类名与标题模式不区分大小写，
+//! 需要匹配整个字符串，`*` 匹配任意字符，`?` 匹配单个字符
+
+use serde::{Deserialize, Serialize};
+
+use super::FocusedWindow;
+use crate::profiles;
+
+/// 模式的最大长度（字符）
+const MAX_PATTERN_LEN: usize = 256;
+
+/// 按应用切换手势配置的设置（`host.app_profiles`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppProfileRules {
+    /// 是否启用
+    pub enabled: bool,
+    pub rules: Vec<AppRule>,
+}
+
+/// 一条规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppRule {
+    /// 匹配窗口类名（`WM_CLASS` 的类名或实例名）
+    #[serde(default)]
+    pub class: Option<String>,
+    /// 匹配窗口标题
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 命中时使用的手势配置
+    pub profile: String,
+}
+
+impl AppRule {
+    fn new(class: Option<&str>, title: Option<&str>, profile: &str) -> Self {
+        Self {
+            class: class.map(str::to_string),
+            title: title.map(str::to_string),
+            profile: profile.to_string(),
+        }
+    }
+
+    /// 规则中给出的条件须全部满足
+    pub fn matches(&self, window: &FocusedWindow) -> bool {
+        let class = self.class.as_deref().is_none_or(|pattern| {
+            wildcard(pattern, &window.class) || wildcard(pattern, &window.instance)
+        });
+        let title = self
+            .title
+            .as_deref()
+            .is_none_or(|pattern| wildcard(pattern, &window.title));
+        class && title
+    }
+}
+
+impl Default for AppProfileRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: vec![
+                AppRule::new(Some("libreoffice-impress"), None, "presentation"),
+                AppRule::new(Some("wpp"), None, "presentation"),
+                AppRule::new(None, Some("*Google Slides*"), "presentation"),
+                AppRule::new(Some("vlc"), None, "media"),
+                AppRule::new(Some("mpv"), None, "media"),
+                AppRule::new(Some("totem"), None, "media"),
+                AppRule::new(Some("*celluloid*"), None, "media"),
+            ],
+        }
+    }
+}
+
+impl AppProfileRules {
+    /// 第一条命中的规则
+    pub fn find(&self, window: &FocusedWindow) -> Option<&AppRule> {
+        if !self.enabled {
+            return None;
+        }
+        self.rules.iter().find(|rule| rule.matches(window))
+    }
+
+    /// 校验规则，错误信息包含规则序号
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            let check = || -> Result<(), String> {
+                let patterns = [&rule.class, &rule.title];
+                if patterns.iter().all(|pattern| pattern.is_none()) {
+                    return Err("至少需要 class 或 title".to_string());
+                }
+                for pattern in patterns.into_iter().flatten() {
+                    if pattern.is_empty() || pattern.chars().count() > MAX_PATTERN_LEN {
+                        return Err(format!("模式长度应在 1 到 {} 之间", MAX_PATTERN_LEN));
+                    }
+                }
+                if profiles::find(&rule.profile).is_none() {
+                    return Err(format!("未知的手势配置 {}", rule.profile));
+                }
+                Ok(())
+            };
+            check().map_err(|e| format!("rules[{}]: {}", index, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// 通配符匹配（不区分大小写）
+fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // 贪心匹配，遇到不符时回到上一个 `*` 多吞一个字符
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(class: &str, instance: &str, title: &str) -> FocusedWindow {
+        FocusedWindow {
+            class: class.to_string(),
+            instance: instance.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn wildcard_matches_whole_string() {
+        let cases = [
+            ("vlc", "vlc", true),
+            ("vlc", "VLC", true),
+            ("vlc", "vlc2", false),
+            ("vl?", "vlc", true),
+            ("vl?", "vl", false),
+            ("*", "", true),
+            ("*", "anything", true),
+            ("", "", true),
+            ("", "a", false),
+            ("*celluloid*", "io.github.celluloid_player.Celluloid", true),
+            ("*Slides*", "Deck - Google Slides - Chrome", true),
+            ("*Slides", "Deck - Google Slides - Chrome", false),
+            ("a*b*c", "aXbYbZc", true),
+            ("a*b*c", "aXbYc-", false),
+            ("**a", "ba", true),
+            ("幻灯片*", "幻灯片放映", true),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(wildcard(pattern, text), expected, "{pattern} ~ {text}");
+        }
+    }
+
+    #[test]
+    fn rule_requires_all_given_patterns() {
+        let vlc = window("Vlc", "vlc", "movie.mkv - VLC media player");
+        assert!(AppRule::new(Some("vlc"), None, "media").matches(&vlc));
+        assert!(AppRule::new(Some("Vlc"), None, "media").matches(&vlc));
+        assert!(AppRule::new(None, Some("*.mkv*"), "media").matches(&vlc));
+        assert!(AppRule::new(Some("vlc"), Some("*VLC*"), "media").matches(&vlc));
+        assert!(!AppRule::new(Some("vlc"), Some("*.mp4*"), "media").matches(&vlc));
+        assert!(!AppRule::new(Some("mpv"), None, "media").matches(&vlc));
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let mut rules = AppProfileRules {
+            enabled: true,
+            rules: vec![
+                AppRule::new(None, Some("*Slides*"), "presentation"),
+                AppRule::new(Some("*chrom*"), None, "pointer"),
+            ],
+        };
+        let slides = window("Google-chrome", "google-chrome", "Deck - Google Slides");
+        let other = window("Google-chrome", "google-chrome", "Inbox");
+        assert_eq!(rules.find(&slides).unwrap().profile, "presentation");
+        assert_eq!(rules.find(&other).unwrap().profile, "pointer");
+        assert!(rules.find(&window("Code", "code", "main.rs")).is_none());
+
+        rules.enabled = false;
+        assert!(rules.find(&slides).is_none());
+    }
+
+    #[test]
+    fn validate_rejects_invalid_rules() {
+        let long = "x".repeat(MAX_PATTERN_LEN + 1);
+        let cases = [
+            (AppRule::new(None, None, "media"), "rules[0]: 至少需要"),
+            (AppRule::new(Some(""), None, "media"), "rules[0]: 模式长度"),
+            (
+                AppRule::new(None, Some(&long), "media"),
+                "rules[0]: 模式长度",
+            ),
+            (
+                AppRule::new(Some("vlc"), None, "missing"),
+                "rules[0]: 未知的手势配置",
+            ),
+        ];
+        for (rule, expected) in cases {
+            let rules = AppProfileRules {
+                enabled: true,
+                rules: vec![rule],
+            };
+            let error = rules.validate().unwrap_err();
+            assert!(error.starts_with(expected), "{error}");
+        }
+        AppProfileRules::default().validate().unwrap();
+    }
+}
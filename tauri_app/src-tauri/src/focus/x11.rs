@@ -0,0 +1,146 @@
+//! X11 前台窗口检测
+//!
+//! 监听根窗口的 `_NET_ACTIVE_WINDOW` 属性（EWMH，主流窗口管理器都支持），
+//! 同时监听当前窗口的标题变化（如浏览器切换标签页）。
+//! 只需要 `DISPLAY`，可以在 Xvfb 下运行
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt, EventMask, Window,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use super::FocusedWindow;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_WM_NAME,
+        UTF8_STRING,
+    }
+}
+
+/// 属性值读取上限（32 位单位）
+const MAX_PROPERTY_LEN: u32 = 1024;
+
+/// 持续监听前台窗口，每次变化（包括标题变化）时回调；连接断开时返回错误
+pub fn watch(mut on_change: impl FnMut(Option<FocusedWindow>)) -> Result<(), String> {
+    let (conn, screen) = x11rb::connect(None).map_err(|e| format!("无法连接 X11: {}", e))?;
+    let root = conn.setup().roots[screen].root;
+    let atoms = Atoms::new(&conn)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?;
+
+    select_property_events(&conn, root, true)?;
+    let mut watched: Option<Window> = None;
+    loop {
+        let active = active_window(&conn, root, &atoms)?;
+        if active != watched {
+            // 旧窗口可能已经关闭，取消监听失败时忽略
+            if let Some(previous) = watched {
+                let _ = select_property_events(&conn, previous, false);
+            }
+            if let Some(window) = active {
+                let _ = select_property_events(&conn, window, true);
+            }
+            watched = active;
+        }
+        on_change(active.and_then(|window| describe(&conn, window, &atoms)));
+
+        // 等待下一次相关的属性变化
+        loop {
+            let event = conn.wait_for_event().map_err(|e| e.to_string())?;
+            let Event::PropertyNotify(event) = event else {
+                continue;
+            };
+            let focus_changed = event.window == root && event.atom == atoms._NET_ACTIVE_WINDOW;
+            let title_changed = Some(event.window) == watched
+                && [
+                    atoms._NET_WM_NAME,
+                    AtomEnum::WM_NAME.into(),
+                    AtomEnum::WM_CLASS.into(),
+                ]
+                .contains(&event.atom);
+            if focus_changed || title_changed {
+                break;
+            }
+        }
+    }
+}
+
+fn select_property_events(conn: &RustConnection, window: Window, on: bool) -> Result<(), String> {
+    let mask = if on {
+        EventMask::PROPERTY_CHANGE
+    } else {
+        EventMask::NO_EVENT
+    };
+    conn.change_window_attributes(window, &ChangeWindowAttributesAux::new().event_mask(mask))
+        .map_err(|e| e.to_string())?;
+    conn.flush().map_err(|e| e.to_string())
+}
+
+fn active_window(
+    conn: &RustConnection,
+    root: Window,
+    atoms: &Atoms,
+) -> Result<Option<Window>, String> {
+    let reply = conn
+        .get_property(
+            false,
+            root,
+            atoms._NET_ACTIVE_WINDOW,
+            AtomEnum::WINDOW,
+            0,
+            1,
+        )
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| format!("无法读取 _NET_ACTIVE_WINDOW: {}", e))?;
+    Ok(reply
+        .value32()
+        .and_then(|mut values| values.next())
+        .filter(|&window| window != x11rb::NONE))
+}
+
+/// 读取窗口类名与标题；窗口已经关闭时返回空
+fn describe(conn: &RustConnection, window: Window, atoms: &Atoms) -> Option<FocusedWindow> {
+    // WM_CLASS 为 "实例名\0类名\0"
+    let class = property(
+        conn,
+        window,
+        AtomEnum::WM_CLASS.into(),
+        AtomEnum::STRING.into(),
+    )?;
+    let mut parts = class.split(|&byte| byte == 0).map(String::from_utf8_lossy);
+    let instance = parts.next().unwrap_or_default().into_owned();
+    let class = parts.next().unwrap_or_default().into_owned();
+
+    let title = property(conn, window, atoms._NET_WM_NAME, atoms.UTF8_STRING)
+        .filter(|title| !title.is_empty())
+        .or_else(|| {
+            property(
+                conn,
+                window,
+                AtomEnum::WM_NAME.into(),
+                AtomEnum::STRING.into(),
+            )
+        })
+        .unwrap_or_default();
+
+    Some(FocusedWindow {
+        class,
+        instance,
+        title: String::from_utf8_lossy(&title).into_owned(),
+    })
+}
+
+fn property(conn: &RustConnection, window: Window, name: Atom, kind: Atom) -> Option<Vec<u8>> {
+    let reply = conn
+        .get_property(false, window, name, kind, 0, MAX_PROPERTY_LEN)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(reply.value)
+}
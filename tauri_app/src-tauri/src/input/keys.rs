@@ -0,0 +1,241 @@
+//! 注入用的按键
+//!
+//! 按键名与组合键的规范形式一致（见 `mapping::keys`），另加后端委托时使用的媒体键
+
+/// 可注入的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Ctrl,
+    Alt,
+    Shift,
+    Super,
+    /// 字母 A-Z 或数字 0-9（大写）
+    Char(char),
+    /// F1-F24
+    F(u8),
+    Enter,
+    Tab,
+    Space,
+    Escape,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Left,
+    Right,
+    Up,
+    Down,
+    PrintScreen,
+    Minus,
+    Equal,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Quote,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Backquote,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MediaPlayPause,
+    MediaNext,
+    MediaPrev,
+}
+
+/// 具名按键的规范名称
+const NAMED: &[(&str, Key)] = &[
+    ("Ctrl", Key::Ctrl),
+    ("Alt", Key::Alt),
+    ("Shift", Key::Shift),
+    ("Super", Key::Super),
+    ("Enter", Key::Enter),
+    ("Tab", Key::Tab),
+    ("Space", Key::Space),
+    ("Escape", Key::Escape),
+    ("Backspace", Key::Backspace),
+    ("Delete", Key::Delete),
+    ("Insert", Key::Insert),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("PrintScreen", Key::PrintScreen),
+    ("Minus", Key::Minus),
+    ("Equal", Key::Equal),
+    ("Comma", Key::Comma),
+    ("Period", Key::Period),
+    ("Slash", Key::Slash),
+    ("Semicolon", Key::Semicolon),
+    ("Quote", Key::Quote),
+    ("BracketLeft", Key::BracketLeft),
+    ("BracketRight", Key::BracketRight),
+    ("Backslash", Key::Backslash),
+    ("Backquote", Key::Backquote),
+    ("VolumeUp", Key::VolumeUp),
+    ("VolumeDown", Key::VolumeDown),
+    ("VolumeMute", Key::VolumeMute),
+    ("MediaPlayPause", Key::MediaPlayPause),
+    ("MediaNext", Key::MediaNext),
+    ("MediaPrev", Key::MediaPrev),
+];
+
+impl Key {
+    /// 按规范名称解析（区分大小写）
+    pub fn parse(name: &str) -> Option<Key> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if c.is_ascii_uppercase() || c.is_ascii_digit() {
+                return Some(Key::Char(c));
+            }
+        }
+        if let Some(number) = name.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+            if (1..=24).contains(&number) {
+                return Some(Key::F(number));
+            }
+        }
+        NAMED
+            .iter()
+            .find(|(named, _)| *named == name)
+            .map(|(_, key)| *key)
+    }
+
+    /// 规范名称
+    pub fn name(&self) -> String {
+        match self {
+            Key::Char(c) => c.to_string(),
+            Key::F(number) => format!("F{}", number),
+            key => NAMED
+                .iter()
+                .find(|(_, named)| named == key)
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// US 键盘布局下需要按 Shift 输入的符号与对应的按键
+const SHIFTED: &[(char, Key)] = &[
+    ('!', Key::Char('1')),
+    ('@', Key::Char('2')),
+    ('#', Key::Char('3')),
+    ('$', Key::Char('4')),
+    ('%', Key::Char('5')),
+    ('^', Key::Char('6')),
+    ('&', Key::Char('7')),
+    ('*', Key::Char('8')),
+    ('(', Key::Char('9')),
+    (')', Key::Char('0')),
+    ('_', Key::Minus),
+    ('+', Key::Equal),
+    ('<', Key::Comma),
+    ('>', Key::Period),
+    ('?', Key::Slash),
+    (':', Key::Semicolon),
+    ('"', Key::Quote),
+    ('{', Key::BracketLeft),
+    ('}', Key::BracketRight),
+    ('|', Key::Backslash),
+    ('~', Key::Backquote),
+];
+
+/// US 键盘布局下输入字符所需的按键与是否按住 Shift（只支持 ASCII，用于没有 keysym 的注入方式）
+pub fn us_layout(c: char) -> Option<(Key, bool)> {
+    let key = match c {
+        'a'..='z' | '0'..='9' => Key::Char(c.to_ascii_uppercase()),
+        'A'..='Z' => return Some((Key::Char(c), true)),
+        ' ' => Key::Space,
+        '\n' => Key::Enter,
+        '\t' => Key::Tab,
+        '-' => Key::Minus,
+        '=' => Key::Equal,
+        ',' => Key::Comma,
+        '.' => Key::Period,
+        '/' => Key::Slash,
+        ';' => Key::Semicolon,
+        '\'' => Key::Quote,
+        '[' => Key::BracketLeft,
+        ']' => Key::BracketRight,
+        '\\' => Key::Backslash,
+        '`' => Key::Backquote,
+        _ => {
+            return SHIFTED
+                .iter()
+                .find(|(shifted, _)| *shifted == c)
+                .map(|(_, key)| (*key, true))
+        }
+    };
+    Some((key, false))
+}
+
+/// 解析规范形式的组合键（修饰键在前）
+pub fn parse_chord(keys: &str) -> Result<Vec<Key>, String> {
+    keys.split('+')
+        .map(|name| Key::parse(name).ok_or_else(|| format!("未知的按键: {}", name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_canonical_chords() {
+        let cases: &[(&str, &[Key])] = &[
+            ("Ctrl+Shift+T", &[Key::Ctrl, Key::Shift, Key::Char('T')]),
+            ("Super+F12", &[Key::Super, Key::F(12)]),
+            ("Alt+Tab", &[Key::Alt, Key::Tab]),
+            ("MediaPlayPause", &[Key::MediaPlayPause]),
+            ("Ctrl+7", &[Key::Ctrl, Key::Char('7')]),
+        ];
+        for (chord, expected) in cases {
+            assert_eq!(parse_chord(chord).unwrap(), *expected, "{chord}");
+            let names: Vec<String> = expected.iter().map(Key::name).collect();
+            assert_eq!(names.join("+"), *chord);
+        }
+    }
+
+    #[test]
+    fn rejects_non_canonical_names() {
+        let cases = [
+            ("ctrl+T", "未知的按键: ctrl"),
+            ("Ctrl+t", "未知的按键: t"),
+            ("F0", "未知的按键: F0"),
+            ("F25", "未知的按键: F25"),
+            ("Ctrl+", "未知的按键: "),
+            ("Esc", "未知的按键: Esc"),
+        ];
+        for (chord, expected) in cases {
+            assert_eq!(parse_chord(chord).unwrap_err(), expected, "{chord}");
+        }
+    }
+
+    #[test]
+    fn maps_ascii_to_us_layout() {
+        let cases = [
+            ('a', Some((Key::Char('A'), false))),
+            ('Z', Some((Key::Char('Z'), true))),
+            ('7', Some((Key::Char('7'), false))),
+            ('@', Some((Key::Char('2'), true))),
+            (' ', Some((Key::Space, false))),
+            ('\n', Some((Key::Enter, false))),
+            ('\t', Some((Key::Tab, false))),
+            ('-', Some((Key::Minus, false))),
+            ('_', Some((Key::Minus, true))),
+            ('é', None),
+            ('世', None),
+        ];
+        for (c, expected) in cases {
+            assert_eq!(us_layout(c), expected, "{c:?}");
+        }
+    }
+}
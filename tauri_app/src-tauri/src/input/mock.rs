@@ -0,0 +1,64 @@
+//! 记录事件的注入后端
+//!
+//! 不产生真实输入，只把事件按顺序记录下来，由 `take_recorded_input` 命令取出
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use super::{InputBackend, InputEvent, Key, MouseButton};
+
+pub struct MockBackend {
+    events: Arc<Mutex<VecDeque<InputEvent>>>,
+    capacity: usize,
+}
+
+impl MockBackend {
+    /// 超过 `capacity` 时丢弃最早的事件
+    pub fn new(events: Arc<Mutex<VecDeque<InputEvent>>>, capacity: usize) -> Self {
+        Self { events, capacity }
+    }
+
+    fn record(&self, event: InputEvent) -> Result<(), String> {
+        let mut events = self.events.lock().unwrap();
+        if events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+        Ok(())
+    }
+}
+
+impl InputBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn move_relative(&mut self, dx: i32, dy: i32) -> Result<(), String> {
+        self.record(InputEvent::MoveRelative { dx, dy })
+    }
+
+    fn move_absolute(&mut self, x: f64, y: f64) -> Result<(), String> {
+        self.record(InputEvent::MoveAbsolute { x, y })
+    }
+
+    fn button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+        self.record(InputEvent::Button { button, pressed })
+    }
+
+    fn scroll(&mut self, amount: i32) -> Result<(), String> {
+        self.record(InputEvent::Scroll { amount })
+    }
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+        self.record(InputEvent::Key {
+            key: key.name(),
+            pressed,
+        })
+    }
+
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        self.record(InputEvent::Text {
+            text: text.to_string(),
+        })
+    }
+}
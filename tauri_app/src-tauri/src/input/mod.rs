@@ -0,0 +1,479 @@
+//! 输入注入
+//!
+//! 宿主侧的鼠标与键盘注入。连接后端时通过 `set_input_delegate` 告知后端是否由宿主注入，
+//! 委托时后端不再直接调用系统接口，而是发送 `input` 消息，由这里的注入后端执行。
+//! 注入方式由 `host.input.driver` 选择：Linux 上可用 XTest（X11 及 XWayland，见 [`x11`]）
+//! 或 `/dev/uinput`（内核虚拟设备，不依赖显示服务器，见 [`uinput`]）；
+//! `mock` 只记录事件，用于在没有显示环境的 CI 中检查注入结果；
+//! `backend` 表示仍由后端自己注入（目前只有 Windows 实现）
+
+mod keys;
+mod mock;
+#[cfg(target_os = "linux")]
+mod uinput;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use phantom_protocol::{InputCommand, InputDelegate};
+use serde::{Deserialize, Serialize};
+
+pub use keys::Key;
+use mock::MockBackend;
+pub use phantom_protocol::MouseButton;
+
+/// mock 最多保留的事件数
+const MAX_RECORDED: usize = 1000;
+
+/// 注入方式（`host.input.driver`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputDriver {
+    /// Linux 上依次尝试 XTest 与 uinput，其他平台由后端注入
+    #[default]
+    Auto,
+    /// 由后端注入
+    Backend,
+    Xtest,
+    Uinput,
+    /// 只记录事件
+    Mock,
+}
+
+/// 输入注入设置（`host.input`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputSettings {
+    pub driver: InputDriver,
+}
+
+/// 注入后端
+pub trait InputBackend: Send {
+    /// 名称（xtest / uinput / mock）
+    fn name(&self) -> &'static str;
+
+    /// 桌面尺寸（像素），未知时为空
+    fn screen_size(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// 相对移动（像素）
+    fn move_relative(&mut self, dx: i32, dy: i32) -> Result<(), String>;
+
+    /// 绝对移动，坐标为整个桌面上的归一化位置（0-1）
+    fn move_absolute(&mut self, x: f64, y: f64) -> Result<(), String>;
+
+    fn button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String>;
+
+    /// 滚动，正数向上（单位为滚轮格数）
+    fn scroll(&mut self, amount: i32) -> Result<(), String>;
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String>;
+
+    /// 逐字符输入文本；含有无法输入的字符时不输入任何内容并返回错误
+    fn text(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// mock 记录的事件（`take_recorded_input` 命令返回）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputEvent {
+    MoveRelative { dx: i32, dy: i32 },
+    MoveAbsolute { x: f64, y: f64 },
+    Button { button: MouseButton, pressed: bool },
+    Scroll { amount: i32 },
+    Key { key: String, pressed: bool },
+    Text { text: String },
+}
+
+/// 注入状态（`get_input_status` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct InputStatus {
+    /// 设置中的注入方式
+    pub driver: InputDriver,
+    /// 实际使用的注入后端，由后端注入时为空
+    pub backend: Option<&'static str>,
+    /// 无法打开注入后端的原因（此时回退为由后端注入）
+    pub error: Option<String>,
+}
+
+struct Inner {
+    driver: InputDriver,
+    backend: Option<Box<dyn InputBackend>>,
+    error: Option<String>,
+    /// 委托期间按下的鼠标按键，断开连接或切换后端时松开
+    pressed: Vec<MouseButton>,
+    /// 未能松开的按键（组合键松开失败时），同样在断开连接或切换后端时再次松开
+    keys: Vec<Key>,
+}
+
+/// 宿主侧输入注入
+pub struct InputInjector {
+    inner: Mutex<Inner>,
+    recorded: Arc<Mutex<VecDeque<InputEvent>>>,
+}
+
+impl InputInjector {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                driver: InputDriver::default(),
+                backend: None,
+                error: None,
+                pressed: Vec::new(),
+                keys: Vec::new(),
+            }),
+            recorded: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// 按设置打开注入后端（替换已有后端）
+    pub fn configure(&self, settings: &InputSettings) {
+        self.release_all();
+        let opened = open(settings.driver, &self.recorded);
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.driver = settings.driver;
+        match opened {
+            Ok(backend) => {
+                match &backend {
+                    Some(backend) => println!("[Tauri] 输入注入: {}", backend.name()),
+                    None => println!("[Tauri] 输入注入: 由后端执行"),
+                }
+                inner.backend = backend;
+                inner.error = None;
+            }
+            Err(e) => {
+                eprintln!("[Tauri] 无法打开输入注入后端，改由后端执行: {}", e);
+                inner.backend = None;
+                inner.error = Some(e);
+            }
+        }
+    }
+
+    pub fn status(&self) -> InputStatus {
+        let inner = self.inner.lock().unwrap();
+        InputStatus {
+            driver: inner.driver,
+            backend: inner.backend.as_ref().map(|backend| backend.name()),
+            error: inner.error.clone(),
+        }
+    }
+
+    /// 发送给后端的委托设置
+    pub fn delegate(&self) -> InputDelegate {
+        let inner = self.inner.lock().unwrap();
+        let size = inner
+            .backend
+            .as_ref()
+            .and_then(|backend| backend.screen_size());
+        InputDelegate {
+            enabled: inner.backend.is_some(),
+            screen_width: size.map(|(width, _)| width),
+            screen_height: size.map(|(_, height)| height),
+        }
+    }
+
+    /// 注入后端报告的桌面尺寸
+    pub fn screen_size(&self) -> Option<(u32, u32)> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .backend
+            .as_ref()
+            .and_then(|backend| backend.screen_size())
+    }
+
+    /// 执行后端委托或宿主执行器产生的输入
+    pub fn execute(&self, command: &InputCommand) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        let backend = inner
+            .backend
+            .as_mut()
+            .ok_or_else(|| "没有可用的输入注入后端".to_string())?;
+
+        match command {
+            InputCommand::MoveRelative { dx, dy } => backend.move_relative(*dx, *dy),
+            InputCommand::MoveAbsolute { x, y } => {
+                backend.move_absolute(x.clamp(0.0, 1.0), y.clamp(0.0, 1.0))
+            }
+            InputCommand::Button { button, pressed } => {
+                backend.button(*button, *pressed)?;
+                inner.pressed.retain(|b| b != button);
+                if *pressed {
+                    inner.pressed.push(*button);
+                }
+                Ok(())
+            }
+            InputCommand::Scroll { amount } => backend.scroll(*amount),
+            InputCommand::Keys { keys } => {
+                chord(backend.as_mut(), &mut inner.keys, &keys::parse_chord(keys)?)
+            }
+            InputCommand::Text { text } => backend.text(text),
+        }
+    }
+
+    /// 松开委托期间按下的鼠标按键与未能松开的按键（后端断开时按键可能仍处于按下状态）
+    pub fn release_all(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let pressed = std::mem::take(&mut inner.pressed);
+        let keys = std::mem::take(&mut inner.keys);
+        if let Some(backend) = inner.backend.as_mut() {
+            for button in pressed {
+                if let Err(e) = backend.button(button, false) {
+                    eprintln!("[Tauri] 无法松开鼠标按键: {}", e);
+                }
+            }
+            for key in keys.into_iter().rev() {
+                if let Err(e) = backend.key(key, false) {
+                    eprintln!("[Tauri] 无法松开按键 {}: {}", key.name(), e);
+                }
+            }
+        }
+    }
+
+    /// 取出 mock 记录的事件
+    pub fn take_recorded(&self) -> Result<Vec<InputEvent>, String> {
+        if self.inner.lock().unwrap().driver != InputDriver::Mock {
+            return Err("只有 mock 注入方式会记录事件".to_string());
+        }
+        Ok(self.recorded.lock().unwrap().drain(..).collect())
+    }
+}
+
+/// 按下并松开组合键：依次按下，逆序松开；中途失败时也会松开已按下的键，
+/// 松开失败的键记录在 `held` 中
+fn chord(backend: &mut dyn InputBackend, held: &mut Vec<Key>, keys: &[Key]) -> Result<(), String> {
+    let mut result = Ok(());
+    let mut pressed = 0;
+    for key in keys {
+        result = backend.key(*key, true);
+        if result.is_err() {
+            break;
+        }
+        held.push(*key);
+        pressed += 1;
+    }
+    for key in keys[..pressed].iter().rev() {
+        let released = backend.key(*key, false);
+        if released.is_ok() {
+            if let Some(index) = held.iter().rposition(|k| k == key) {
+                held.remove(index);
+            }
+        }
+        result = result.and(released);
+    }
+    result
+}
+
+/// 打开注入后端；由后端注入时返回空
+fn open(
+    driver: InputDriver,
+    recorded: &Arc<Mutex<VecDeque<InputEvent>>>,
+) -> Result<Option<Box<dyn InputBackend>>, String> {
+    match driver {
+        InputDriver::Backend => Ok(None),
+        InputDriver::Mock => Ok(Some(Box::new(MockBackend::new(
+            recorded.clone(),
+            MAX_RECORDED,
+        )))),
+        InputDriver::Xtest => open_xtest().map(Some),
+        InputDriver::Uinput => open_uinput().map(Some),
+        InputDriver::Auto if cfg!(target_os = "linux") => match open_xtest() {
+            Ok(backend) => Ok(Some(backend)),
+            Err(xtest) => open_uinput()
+                .map(Some)
+                .map_err(|uinput| format!("XTest: {}；uinput: {}", xtest, uinput)),
+        },
+        InputDriver::Auto => Ok(None),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_xtest() -> Result<Box<dyn InputBackend>, String> {
+    Ok(Box::new(x11::XTestBackend::open()?))
+}
+
+#[cfg(target_os = "linux")]
+fn open_uinput() -> Result<Box<dyn InputBackend>, String> {
+    Ok(Box::new(uinput::UinputBackend::open()?))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_xtest() -> Result<Box<dyn InputBackend>, String> {
+    Err("当前平台不支持 XTest".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_uinput() -> Result<Box<dyn InputBackend>, String> {
+    Err("当前平台不支持 uinput".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock() -> InputInjector {
+        let injector = InputInjector::new();
+        injector.configure(&InputSettings {
+            driver: InputDriver::Mock,
+        });
+        injector
+    }
+
+    fn key(name: &str, pressed: bool) -> InputEvent {
+        InputEvent::Key {
+            key: name.to_string(),
+            pressed,
+        }
+    }
+
+    #[test]
+    fn chord_releases_in_reverse_order() {
+        let injector = mock();
+        injector
+            .execute(&InputCommand::Keys {
+                keys: "Ctrl+Shift+T".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            injector.take_recorded().unwrap(),
+            [
+                key("Ctrl", true),
+                key("Shift", true),
+                key("T", true),
+                key("T", false),
+                key("Shift", false),
+                key("Ctrl", false),
+            ]
+        );
+        assert!(injector.inner.lock().unwrap().keys.is_empty());
+    }
+
+    #[test]
+    fn invalid_chord_injects_nothing() {
+        let injector = mock();
+        let command = InputCommand::Keys {
+            keys: "Ctrl+Hyper".to_string(),
+        };
+        assert!(injector.execute(&command).is_err());
+        assert!(injector.take_recorded().unwrap().is_empty());
+    }
+
+    #[test]
+    fn text_is_injected_as_one_event() {
+        let injector = mock();
+        let text = "Hello, 世界\n".to_string();
+        injector
+            .execute(&InputCommand::Text { text: text.clone() })
+            .unwrap();
+        assert_eq!(
+            injector.take_recorded().unwrap(),
+            [InputEvent::Text { text }]
+        );
+    }
+
+    #[test]
+    fn release_all_releases_held_buttons() {
+        let injector = mock();
+        for (button, pressed) in [
+            (MouseButton::Left, true),
+            (MouseButton::Right, true),
+            (MouseButton::Right, false),
+            (MouseButton::Middle, true),
+        ] {
+            injector
+                .execute(&InputCommand::Button { button, pressed })
+                .unwrap();
+        }
+        injector.take_recorded().unwrap();
+
+        injector.release_all();
+        let released = |button| InputEvent::Button {
+            button,
+            pressed: false,
+        };
+        assert_eq!(
+            injector.take_recorded().unwrap(),
+            [released(MouseButton::Left), released(MouseButton::Middle)]
+        );
+        injector.release_all();
+        assert!(injector.take_recorded().unwrap().is_empty());
+    }
+
+    /// 第一次松开指定按键时失败的 mock
+    struct StuckKey {
+        mock: MockBackend,
+        stuck: Option<Key>,
+    }
+
+    impl InputBackend for StuckKey {
+        fn name(&self) -> &'static str {
+            "stuck"
+        }
+
+        fn move_relative(&mut self, dx: i32, dy: i32) -> Result<(), String> {
+            self.mock.move_relative(dx, dy)
+        }
+
+        fn move_absolute(&mut self, x: f64, y: f64) -> Result<(), String> {
+            self.mock.move_absolute(x, y)
+        }
+
+        fn button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+            self.mock.button(button, pressed)
+        }
+
+        fn scroll(&mut self, amount: i32) -> Result<(), String> {
+            self.mock.scroll(amount)
+        }
+
+        fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+            if !pressed && self.stuck == Some(key) {
+                self.stuck = None;
+                return Err("松开失败".to_string());
+            }
+            self.mock.key(key, pressed)
+        }
+
+        fn text(&mut self, text: &str) -> Result<(), String> {
+            self.mock.text(text)
+        }
+    }
+
+    #[test]
+    fn release_all_retries_stuck_keys() {
+        let injector = mock();
+        let recorded = injector.recorded.clone();
+        injector.inner.lock().unwrap().backend = Some(Box::new(StuckKey {
+            mock: MockBackend::new(recorded, MAX_RECORDED),
+            stuck: Some(Key::Ctrl),
+        }));
+
+        let command = InputCommand::Keys {
+            keys: "Ctrl+C".to_string(),
+        };
+        assert!(injector.execute(&command).is_err());
+        assert_eq!(
+            injector.take_recorded().unwrap(),
+            [key("Ctrl", true), key("C", true), key("C", false)]
+        );
+
+        injector.release_all();
+        assert_eq!(injector.take_recorded().unwrap(), [key("Ctrl", false)]);
+        assert!(injector.inner.lock().unwrap().keys.is_empty());
+    }
+
+    #[test]
+    fn move_absolute_is_clamped() {
+        let injector = mock();
+        injector
+            .execute(&InputCommand::MoveAbsolute { x: 1.5, y: -0.5 })
+            .unwrap();
+        assert_eq!(
+            injector.take_recorded().unwrap(),
+            [InputEvent::MoveAbsolute { x: 1.0, y: 0.0 }]
+        );
+    }
+}
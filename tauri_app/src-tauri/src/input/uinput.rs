@@ -0,0 +1,333 @@
+//! uinput 注入
+//!
+//! 在 `/dev/uinput` 上创建两个虚拟设备：键盘与相对定位鼠标共用一个设备，
+//! 绝对定位单独用一个类似数位板的设备（同时带相对轴与绝对轴的设备会被 libinput 误判）。
+//! 不依赖显示服务器，Wayland 下同样有效，但需要对 `/dev/uinput` 的写权限（通常由 udev 规则授予 input 组）
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+
+use super::keys::us_layout;
+use super::{InputBackend, Key, MouseButton};
+
+const UINPUT_PATH: &str = "/dev/uinput";
+
+// linux/uinput.h 中的 ioctl 请求
+const UI_DEV_CREATE: u64 = 0x5501;
+const UI_DEV_DESTROY: u64 = 0x5502;
+const UI_DEV_SETUP: u64 = 0x405c_5503;
+const UI_ABS_SETUP: u64 = 0x401c_5504;
+const UI_SET_EVBIT: u64 = 0x4004_5564;
+const UI_SET_KEYBIT: u64 = 0x4004_5565;
+const UI_SET_RELBIT: u64 = 0x4004_5566;
+const UI_SET_ABSBIT: u64 = 0x4004_5567;
+
+// linux/input-event-codes.h
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+const SYN_REPORT: u16 = 0;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_WHEEL: u16 = 0x08;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const BTN_LEFT: u16 = 0x110;
+const BTN_RIGHT: u16 = 0x111;
+const BTN_MIDDLE: u16 = 0x112;
+const BUS_VIRTUAL: u16 = 0x06;
+
+/// 绝对轴的取值上限（归一化坐标按此缩放）
+const ABS_MAX: i32 = 65535;
+
+const BUTTONS: [u16; 3] = [BTN_LEFT, BTN_RIGHT, BTN_MIDDLE];
+
+pub struct UinputBackend {
+    /// 键盘与相对定位鼠标
+    pointer: Device,
+    /// 绝对定位
+    tablet: Device,
+}
+
+impl UinputBackend {
+    pub fn open() -> Result<Self, String> {
+        let pointer = Device::create("PhantomHand Virtual Input", |device| {
+            device.enable(UI_SET_EVBIT, EV_KEY)?;
+            for code in KEYS.iter().map(|(_, code)| *code).chain(BUTTONS) {
+                device.enable(UI_SET_KEYBIT, code)?;
+            }
+            device.enable(UI_SET_EVBIT, EV_REL)?;
+            for code in [REL_X, REL_Y, REL_WHEEL] {
+                device.enable(UI_SET_RELBIT, code)?;
+            }
+            Ok(())
+        })?;
+        let tablet = Device::create("PhantomHand Virtual Pointer", |device| {
+            device.enable(UI_SET_EVBIT, EV_KEY)?;
+            for code in BUTTONS {
+                device.enable(UI_SET_KEYBIT, code)?;
+            }
+            device.enable(UI_SET_EVBIT, EV_ABS)?;
+            for code in [ABS_X, ABS_Y] {
+                device.enable(UI_SET_ABSBIT, code)?;
+                device.abs_setup(code, ABS_MAX)?;
+            }
+            Ok(())
+        })?;
+        Ok(Self { pointer, tablet })
+    }
+}
+
+impl InputBackend for UinputBackend {
+    fn name(&self) -> &'static str {
+        "uinput"
+    }
+
+    fn move_relative(&mut self, dx: i32, dy: i32) -> Result<(), String> {
+        self.pointer
+            .emit(&[(EV_REL, REL_X, dx), (EV_REL, REL_Y, dy)])
+    }
+
+    fn move_absolute(&mut self, x: f64, y: f64) -> Result<(), String> {
+        let scale = |v: f64| (v * f64::from(ABS_MAX)).round() as i32;
+        self.tablet
+            .emit(&[(EV_ABS, ABS_X, scale(x)), (EV_ABS, ABS_Y, scale(y))])
+    }
+
+    fn button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+        let code = match button {
+            MouseButton::Left => BTN_LEFT,
+            MouseButton::Right => BTN_RIGHT,
+            MouseButton::Middle => BTN_MIDDLE,
+        };
+        self.pointer.emit(&[(EV_KEY, code, pressed.into())])
+    }
+
+    fn scroll(&mut self, amount: i32) -> Result<(), String> {
+        self.pointer.emit(&[(EV_REL, REL_WHEEL, amount)])
+    }
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+        let code = KEYS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, code)| *code)
+            .ok_or_else(|| format!("uinput 不支持按键 {}", key.name()))?;
+        self.pointer.emit(&[(EV_KEY, code, pressed.into())])
+    }
+
+    /// 按 US 键盘布局输入，只支持 ASCII 字符（内核设备只有键码，字符由桌面的键盘布局决定）
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        let keys = text
+            .chars()
+            .filter(|&c| c != '\r')
+            .map(|c| us_layout(c).ok_or_else(|| format!("uinput 无法输入字符 {:?}", c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (key, shift) in keys {
+            if shift {
+                self.key(Key::Shift, true)?;
+            }
+            let typed = self.key(key, true).and_then(|_| self.key(key, false));
+            if shift {
+                self.key(Key::Shift, false)?;
+            }
+            typed?;
+        }
+        Ok(())
+    }
+}
+
+/// 一个 uinput 虚拟设备，释放时销毁
+struct Device {
+    file: File,
+}
+
+impl Device {
+    fn create(
+        name: &str,
+        configure: impl FnOnce(&Device) -> Result<(), String>,
+    ) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(UINPUT_PATH)
+            .map_err(|e| format!("无法打开 {}: {}", UINPUT_PATH, e))?;
+        let device = Device { file };
+        configure(&device)?;
+
+        // SAFETY: 全零是 uinput_setup 的合法取值
+        let mut setup: libc::uinput_setup = unsafe { std::mem::zeroed() };
+        setup.id.bustype = BUS_VIRTUAL;
+        setup.id.vendor = 0x1209;
+        setup.id.product = 0x5048;
+        for (dst, src) in setup
+            .name
+            .iter_mut()
+            .zip(name.bytes().take(libc::UINPUT_MAX_NAME_SIZE - 1))
+        {
+            *dst = src as libc::c_char;
+        }
+        device.ioctl(UI_DEV_SETUP, &setup as *const _ as libc::c_ulong)?;
+        device.ioctl(UI_DEV_CREATE, 0)?;
+        Ok(device)
+    }
+
+    fn ioctl(&self, request: u64, arg: libc::c_ulong) -> Result<(), String> {
+        // SAFETY: 请求码与参数类型按 linux/uinput.h 一一对应
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), request as _, arg) };
+        if ret < 0 {
+            return Err(format!(
+                "uinput ioctl 0x{:x} 失败: {}",
+                request,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    fn enable(&self, request: u64, code: u16) -> Result<(), String> {
+        self.ioctl(request, code.into())
+    }
+
+    fn abs_setup(&self, code: u16, maximum: i32) -> Result<(), String> {
+        // SAFETY: 全零是 uinput_abs_setup 的合法取值
+        let mut setup: libc::uinput_abs_setup = unsafe { std::mem::zeroed() };
+        setup.code = code;
+        setup.absinfo.maximum = maximum;
+        self.ioctl(UI_ABS_SETUP, &setup as *const _ as libc::c_ulong)
+    }
+
+    /// 写入一组事件并以 SYN_REPORT 结束
+    fn emit(&mut self, events: &[(u16, u16, i32)]) -> Result<(), String> {
+        let mut buffer =
+            Vec::with_capacity((events.len() + 1) * std::mem::size_of::<libc::input_event>());
+        for &(kind, code, value) in events.iter().chain([&(EV_SYN, SYN_REPORT, 0)]) {
+            // SAFETY: 全零是 input_event 的合法取值，时间戳由内核填写
+            let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+            event.type_ = kind;
+            event.code = code;
+            event.value = value;
+            // SAFETY: input_event 是没有填充字节以外内容的 POD 结构
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    &event as *const _ as *const u8,
+                    std::mem::size_of::<libc::input_event>(),
+                )
+            };
+            buffer.extend_from_slice(bytes);
+        }
+        self.file
+            .write_all(&buffer)
+            .map_err(|e| format!("无法写入 uinput 事件: {}", e))
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        let _ = self.ioctl(UI_DEV_DESTROY, 0);
+    }
+}
+
+/// 按键对应的 evdev 键码（linux/input-event-codes.h）
+const KEYS: &[(Key, u16)] = &[
+    (Key::Ctrl, 29),
+    (Key::Alt, 56),
+    (Key::Shift, 42),
+    (Key::Super, 125),
+    (Key::Char('A'), 30),
+    (Key::Char('B'), 48),
+    (Key::Char('C'), 46),
+    (Key::Char('D'), 32),
+    (Key::Char('E'), 18),
+    (Key::Char('F'), 33),
+    (Key::Char('G'), 34),
+    (Key::Char('H'), 35),
+    (Key::Char('I'), 23),
+    (Key::Char('J'), 36),
+    (Key::Char('K'), 37),
+    (Key::Char('L'), 38),
+    (Key::Char('M'), 50),
+    (Key::Char('N'), 49),
+    (Key::Char('O'), 24),
+    (Key::Char('P'), 25),
+    (Key::Char('Q'), 16),
+    (Key::Char('R'), 19),
+    (Key::Char('S'), 31),
+    (Key::Char('T'), 20),
+    (Key::Char('U'), 22),
+    (Key::Char('V'), 47),
+    (Key::Char('W'), 17),
+    (Key::Char('X'), 45),
+    (Key::Char('Y'), 21),
+    (Key::Char('Z'), 44),
+    (Key::Char('1'), 2),
+    (Key::Char('2'), 3),
+    (Key::Char('3'), 4),
+    (Key::Char('4'), 5),
+    (Key::Char('5'), 6),
+    (Key::Char('6'), 7),
+    (Key::Char('7'), 8),
+    (Key::Char('8'), 9),
+    (Key::Char('9'), 10),
+    (Key::Char('0'), 11),
+    (Key::F(1), 59),
+    (Key::F(2), 60),
+    (Key::F(3), 61),
+    (Key::F(4), 62),
+    (Key::F(5), 63),
+    (Key::F(6), 64),
+    (Key::F(7), 65),
+    (Key::F(8), 66),
+    (Key::F(9), 67),
+    (Key::F(10), 68),
+    (Key::F(11), 87),
+    (Key::F(12), 88),
+    (Key::F(13), 183),
+    (Key::F(14), 184),
+    (Key::F(15), 185),
+    (Key::F(16), 186),
+    (Key::F(17), 187),
+    (Key::F(18), 188),
+    (Key::F(19), 189),
+    (Key::F(20), 190),
+    (Key::F(21), 191),
+    (Key::F(22), 192),
+    (Key::F(23), 193),
+    (Key::F(24), 194),
+    (Key::Enter, 28),
+    (Key::Tab, 15),
+    (Key::Space, 57),
+    (Key::Escape, 1),
+    (Key::Backspace, 14),
+    (Key::Delete, 111),
+    (Key::Insert, 110),
+    (Key::Home, 102),
+    (Key::End, 107),
+    (Key::PageUp, 104),
+    (Key::PageDown, 109),
+    (Key::Left, 105),
+    (Key::Right, 106),
+    (Key::Up, 103),
+    (Key::Down, 108),
+    (Key::PrintScreen, 99),
+    (Key::Minus, 12),
+    (Key::Equal, 13),
+    (Key::Comma, 51),
+    (Key::Period, 52),
+    (Key::Slash, 53),
+    (Key::Semicolon, 39),
+    (Key::Quote, 40),
+    (Key::BracketLeft, 26),
+    (Key::BracketRight, 27),
+    (Key::Backslash, 43),
+    (Key::Backquote, 41),
+    (Key::VolumeUp, 115),
+    (Key::VolumeDown, 114),
+    (Key::VolumeMute, 113),
+    (Key::MediaPlayPause, 164),
+    (Key::MediaNext, 163),
+    (Key::MediaPrev, 165),
+];
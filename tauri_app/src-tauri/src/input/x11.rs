@@ -0,0 +1,328 @@
+//! XTest 注入
+//!
+//! 通过 XTest 扩展向 X 服务器发送伪造的输入事件，只需要 `DISPLAY`，可以在 Xvfb 下运行。
+//! 按 keysym 查找键码；当前键盘映射中没有的按键（如部分 Xvfb 缺少的媒体键）
+//! 在按下期间临时映射到一个空闲键码，松开后（或注入后端关闭时）恢复为空，
+//! 不会长期改变整个 X 会话的键盘映射
+
+use std::collections::HashMap;
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{
+    ConnectionExt, Keycode, Keysym, Window, BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT,
+    KEY_PRESS_EVENT, KEY_RELEASE_EVENT, MOTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::xtest::{self, ConnectionExt as _};
+use x11rb::rust_connection::RustConnection;
+
+use super::{InputBackend, Key, MouseButton};
+
+/// 键盘映射的一行：键码与其 keysym 列表
+type MappingRow = (Keycode, Vec<Keysym>);
+
+pub struct XTestBackend {
+    conn: RustConnection,
+    root: Window,
+    width: u16,
+    height: u16,
+    /// keysym -> 键码（键盘映射中已有的按键）
+    keycodes: HashMap<Keysym, Keycode>,
+    /// 按下期间临时映射的 keysym -> (空闲键码, 每个键码的 keysym 数)
+    remapped: HashMap<Keysym, (Keycode, u8)>,
+}
+
+impl XTestBackend {
+    pub fn open() -> Result<Self, String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| format!("无法连接 X11: {}", e))?;
+        let present = conn
+            .extension_information(xtest::X11_EXTENSION_NAME)
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !present {
+            return Err("X 服务器不支持 XTest 扩展".to_string());
+        }
+        conn.xtest_get_version(2, 2)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("无法查询 XTest 版本: {}", e))?;
+
+        let screen = &conn.setup().roots[screen];
+        let (root, width, height) = (screen.root, screen.width_in_pixels, screen.height_in_pixels);
+        Ok(Self {
+            conn,
+            root,
+            width,
+            height,
+            keycodes: HashMap::new(),
+            remapped: HashMap::new(),
+        })
+    }
+
+    fn fake(&self, kind: u8, detail: u8, x: i16, y: i16) -> Result<(), String> {
+        // 相对移动时 root 须为 None
+        let root = if kind == MOTION_NOTIFY_EVENT && detail == 0 {
+            self.root
+        } else {
+            x11rb::NONE
+        };
+        self.conn
+            .xtest_fake_input(kind, detail, x11rb::CURRENT_TIME, root, x, y, 0)
+            .map_err(|e| format!("无法发送输入事件: {}", e))?;
+        self.conn.flush().map_err(|e| e.to_string())
+    }
+
+    fn click(&self, button: u8, pressed: bool) -> Result<(), String> {
+        let kind = if pressed {
+            BUTTON_PRESS_EVENT
+        } else {
+            BUTTON_RELEASE_EVENT
+        };
+        self.fake(kind, button, 0, 0)
+    }
+
+    /// 读取当前键盘映射：每个键码的 keysym 数与 (键码, keysym 列表)
+    fn keyboard_mapping(&self) -> Result<(u8, Vec<MappingRow>), String> {
+        let setup = self.conn.setup();
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+        let mapping = self
+            .conn
+            .get_keyboard_mapping(min, max - min + 1)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("无法读取键盘映射: {}", e))?;
+        let per = mapping.keysyms_per_keycode;
+        let rows = (min..=max)
+            .zip(mapping.keysyms.chunks(per.into()))
+            .map(|(keycode, syms)| (keycode, syms.to_vec()))
+            .collect();
+        Ok((per, rows))
+    }
+
+    /// 查找 keysym 对应的键码，没有时临时映射到空闲键码；返回键码与是否为临时映射
+    fn keycode(&mut self, keysym: Keysym) -> Result<(Keycode, bool), String> {
+        if let Some(&keycode) = self.keycodes.get(&keysym) {
+            return Ok((keycode, false));
+        }
+        if let Some(&(keycode, _)) = self.remapped.get(&keysym) {
+            return Ok((keycode, true));
+        }
+
+        let (per, rows) = self.keyboard_mapping()?;
+        if let Some((keycode, _)) = rows.iter().find(|(_, syms)| syms.contains(&keysym)) {
+            self.keycodes.insert(keysym, *keycode);
+            return Ok((*keycode, false));
+        }
+        Ok((self.map_spare(&rows, per, keysym)?, true))
+    }
+
+    /// 把 keysym 临时映射到一个空闲键码，用完后须调用 [`Self::restore`]
+    fn map_spare(
+        &mut self,
+        rows: &[MappingRow],
+        per: u8,
+        keysym: Keysym,
+    ) -> Result<Keycode, String> {
+        let in_use: Vec<Keycode> = self
+            .remapped
+            .values()
+            .map(|&(keycode, _)| keycode)
+            .collect();
+        let spare = rows
+            .iter()
+            .find(|(keycode, syms)| syms.iter().all(|&sym| sym == 0) && !in_use.contains(keycode))
+            .map(|(keycode, _)| *keycode)
+            .ok_or_else(|| format!("键盘映射中没有 0x{:x}，且没有空闲键码", keysym))?;
+        self.remap(spare, per, keysym)?;
+        self.remapped.insert(keysym, (spare, per));
+        Ok(spare)
+    }
+
+    /// 按下并松开一个键码
+    fn tap(&self, keycode: Keycode) -> Result<(), String> {
+        self.fake(KEY_PRESS_EVENT, keycode, 0, 0)?;
+        self.fake(KEY_RELEASE_EVENT, keycode, 0, 0)
+    }
+
+    /// 把键码的所有 keysym 设为 `keysym`（为 0 时即恢复为空）
+    fn remap(&self, keycode: Keycode, per: u8, keysym: Keysym) -> Result<(), String> {
+        let syms = vec![keysym; per.into()];
+        self.conn
+            .change_keyboard_mapping(1, keycode, per, &syms)
+            .map_err(|e| e.to_string())?
+            .check()
+            .map_err(|e| format!("无法修改键盘映射: {}", e))
+    }
+
+    /// 恢复临时映射的键码
+    fn restore(&mut self, keysym: Keysym) -> Result<(), String> {
+        let Some((keycode, per)) = self.remapped.remove(&keysym) else {
+            return Ok(());
+        };
+        // 等待 X 服务器处理完松开事件，避免按新映射解释
+        self.conn
+            .get_input_focus()
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        self.remap(keycode, per, 0)
+    }
+}
+
+impl Drop for XTestBackend {
+    fn drop(&mut self) {
+        let keysyms: Vec<Keysym> = self.remapped.keys().copied().collect();
+        for keysym in keysyms {
+            if let Err(e) = self.restore(keysym) {
+                eprintln!("[Tauri] 无法恢复键盘映射: {}", e);
+            }
+        }
+    }
+}
+
+impl InputBackend for XTestBackend {
+    fn name(&self) -> &'static str {
+        "xtest"
+    }
+
+    fn screen_size(&self) -> Option<(u32, u32)> {
+        Some((self.width.into(), self.height.into()))
+    }
+
+    fn move_relative(&mut self, dx: i32, dy: i32) -> Result<(), String> {
+        let clamp = |v: i32| v.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        self.fake(MOTION_NOTIFY_EVENT, 1, clamp(dx), clamp(dy))
+    }
+
+    fn move_absolute(&mut self, x: f64, y: f64) -> Result<(), String> {
+        let scale = |v: f64, size: u16| (v * f64::from(size.saturating_sub(1))).round() as i16;
+        self.fake(
+            MOTION_NOTIFY_EVENT,
+            0,
+            scale(x, self.width),
+            scale(y, self.height),
+        )
+    }
+
+    fn button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+        let button = match button {
+            MouseButton::Left => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Right => 3,
+        };
+        self.click(button, pressed)
+    }
+
+    fn scroll(&mut self, amount: i32) -> Result<(), String> {
+        // 滚轮为按钮 4（向上）与 5（向下），每格按下并松开一次
+        let button = if amount > 0 { 4 } else { 5 };
+        for _ in 0..amount.unsigned_abs() {
+            self.click(button, true)?;
+            self.click(button, false)?;
+        }
+        Ok(())
+    }
+
+    fn key(&mut self, key: Key, pressed: bool) -> Result<(), String> {
+        let keysym = keysym(key);
+        let (keycode, temporary) = self.keycode(keysym)?;
+        if pressed {
+            return self.fake(KEY_PRESS_EVENT, keycode, 0, 0);
+        }
+        let result = self.fake(KEY_RELEASE_EVENT, keycode, 0, 0);
+        if temporary {
+            result.and(self.restore(keysym))
+        } else {
+            result
+        }
+    }
+
+    /// 键盘映射中第一列（不按修饰键）或第二列（按住 Shift）有该字符时直接输入，
+    /// 否则临时映射到空闲键码后输入并恢复
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        let keysyms = text
+            .chars()
+            .filter(|&c| c != '\r')
+            .map(|c| char_keysym(c).ok_or_else(|| format!("无法输入字符 {:?}", c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (per, rows) = self.keyboard_mapping()?;
+        for keysym in keysyms {
+            let level = rows.iter().find_map(|(keycode, syms)| {
+                let level = syms.iter().take(2).position(|&sym| sym == keysym)?;
+                Some((*keycode, level))
+            });
+            match level {
+                Some((keycode, 0)) => self.tap(keycode)?,
+                Some((keycode, _)) => {
+                    self.key(Key::Shift, true)?;
+                    let typed = self.tap(keycode);
+                    self.key(Key::Shift, false)?;
+                    typed?;
+                }
+                None => {
+                    let keycode = self.map_spare(&rows, per, keysym)?;
+                    let typed = self.tap(keycode);
+                    self.restore(keysym)?;
+                    typed?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 字符对应的 keysym：Latin-1 与 keysym 相同，其余为 0x01000000 加码点；不能输入的控制字符返回空
+fn char_keysym(c: char) -> Option<Keysym> {
+    match c {
+        '\n' => Some(0xff0d),
+        '\t' => Some(0xff09),
+        c if c.is_control() => None,
+        ' '..='~' | '\u{a0}'..='\u{ff}' => Some(c as Keysym),
+        c => Some(0x0100_0000 + c as Keysym),
+    }
+}
+
+/// 按键对应的 keysym（X11/keysymdef.h 与 XF86keysym.h）
+fn keysym(key: Key) -> Keysym {
+    match key {
+        Key::Ctrl => 0xffe3,
+        Key::Alt => 0xffe9,
+        Key::Shift => 0xffe1,
+        Key::Super => 0xffeb,
+        // 字母使用小写 keysym，数字与 ASCII 相同
+        Key::Char(c) => c.to_ascii_lowercase() as Keysym,
+        Key::F(number) => 0xffbe + Keysym::from(number) - 1,
+        Key::Enter => 0xff0d,
+        Key::Tab => 0xff09,
+        Key::Space => 0x0020,
+        Key::Escape => 0xff1b,
+        Key::Backspace => 0xff08,
+        Key::Delete => 0xffff,
+        Key::Insert => 0xff63,
+        Key::Home => 0xff50,
+        Key::End => 0xff57,
+        Key::PageUp => 0xff55,
+        Key::PageDown => 0xff56,
+        Key::Left => 0xff51,
+        Key::Up => 0xff52,
+        Key::Right => 0xff53,
+        Key::Down => 0xff54,
+        Key::PrintScreen => 0xff61,
+        Key::Minus => 0x002d,
+        Key::Equal => 0x003d,
+        Key::Comma => 0x002c,
+        Key::Period => 0x002e,
+        Key::Slash => 0x002f,
+        Key::Semicolon => 0x003b,
+        Key::Quote => 0x0027,
+        Key::BracketLeft => 0x005b,
+        Key::BracketRight => 0x005d,
+        Key::Backslash => 0x005c,
+        Key::Backquote => 0x0060,
+        Key::VolumeUp => 0x1008ff13,
+        Key::VolumeDown => 0x1008ff11,
+        Key::VolumeMute => 0x1008ff12,
+        Key::MediaPlayPause => 0x1008ff14,
+        Key::MediaNext => 0x1008ff17,
+        Key::MediaPrev => 0x1008ff16,
+    }
+}
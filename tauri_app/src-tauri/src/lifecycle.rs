@@ -0,0 +1,61 @@
+//! 应用生命周期
+//!
+//! 托盘“退出”与关闭主窗口统一走有序关闭流程：
+//! 先让后端释放摄像头和按住的按键，再退出应用
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::AppState;
+
+/// 等待后端自行退出的最长时间
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// 防止重复进入关闭流程
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// 关闭主窗口时的行为
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    /// 退出应用
+    #[default]
+    Quit,
+    /// 隐藏到托盘
+    HideToTray,
+}
+
+/// 请求退出应用（在后台执行关闭流程）
+pub fn request_exit(app_handle: &AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        println!("[Tauri] 正在退出...");
+        let state = app_handle.state::<AppState>();
+        if let Err(e) = state.backend.shutdown(&app_handle, SHUTDOWN_GRACE).await {
+            eprintln!("[Tauri] 关闭后端失败: {}", e);
+        }
+        app_handle.exit(0);
+    });
+}
+
+/// 主窗口请求关闭：按设置隐藏到托盘或退出应用
+pub fn on_close_requested(window: &Window) {
+    let app_handle = window.app_handle();
+    let action = app_handle
+        .state::<AppState>()
+        .settings
+        .read(|settings| settings.host.close_action);
+    match action {
+        CloseAction::HideToTray => {
+            let _ = window.hide();
+        }
+        CloseAction::Quit => request_exit(&app_handle),
+    }
+}
@@ -0,0 +1,176 @@
+//! 滚动日志文件
+//!
+//! 写入 `backend.log`，超过大小上限时依次重命名为 `backend.1.log`、`backend.2.log` ...
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 单个日志文件的大小上限
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// 保留的历史文件数
+const MAX_BACKUPS: usize = 3;
+
+/// 日志文件名前缀
+const FILE_STEM: &str = "backend";
+
+pub struct RotatingFile {
+    dir: PathBuf,
+    /// 滚动期间或写入失败后为空（Windows 下无法重命名已打开的文件），下次写入时重新打开
+    file: Option<File>,
+    written: u64,
+    max_bytes: u64,
+    max_backups: usize,
+}
+
+impl RotatingFile {
+    /// 在指定目录打开（或创建）日志文件
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        Self::with_limits(dir, MAX_FILE_BYTES, MAX_BACKUPS)
+    }
+
+    fn with_limits(dir: &Path, max_bytes: u64, max_backups: usize) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = open_current(dir)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file: Some(file),
+            written,
+            max_bytes,
+            max_backups,
+        })
+    }
+
+    /// 当前日志文件路径
+    pub fn path(&self) -> PathBuf {
+        file_path(&self.dir, 0)
+    }
+
+    /// 写入一行，必要时先滚动
+    ///
+    /// 失败时关闭文件并保留未完成的滚动，下次写入时重试
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let result = self.try_write_line(line);
+        if result.is_err() {
+            self.file = None;
+        }
+        result
+    }
+
+    fn try_write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.written + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(open_current(&self.dir)?),
+        };
+        file.write_all(line.as_bytes())?;
+        file.write_all(b"\n")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+
+        // 从最旧的开始后移，超出保留数的被覆盖
+        for index in (1..self.max_backups).rev() {
+            let from = file_path(&self.dir, index);
+            if from.exists() {
+                fs::rename(&from, file_path(&self.dir, index + 1))?;
+            }
+        }
+        fs::rename(file_path(&self.dir, 0), file_path(&self.dir, 1))?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_current(dir: &Path) -> std::io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path(dir, 0))
+}
+
+fn file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{}.log", FILE_STEM))
+    } else {
+        dir.join(format!("{}.{}.log", FILE_STEM, index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 目录中按序号排列的日志文件内容
+    fn contents(dir: &Path) -> Vec<String> {
+        let mut files = Vec::new();
+        for index in 0.. {
+            match fs::read_to_string(file_path(dir, index)) {
+                Ok(text) => files.push(text),
+                Err(_) => break,
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn rotates_and_prunes_old_files() {
+        let dir = tempfile::tempdir().unwrap();
+        // 每个文件最多容纳两行
+        let mut file = RotatingFile::with_limits(dir.path(), 20, 3).unwrap();
+        for index in 0..10 {
+            file.write_line(&format!("line {}", index)).unwrap();
+        }
+
+        assert_eq!(
+            contents(dir.path()),
+            [
+                "line 8\nline 9\n",
+                "line 6\nline 7\n",
+                "line 4\nline 5\n",
+                "line 2\nline 3\n",
+            ]
+        );
+        assert!(!file_path(dir.path(), 4).exists());
+    }
+
+    #[test]
+    fn reopening_continues_current_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::with_limits(dir.path(), 20, 3).unwrap();
+        file.write_line("line 0").unwrap();
+        drop(file);
+
+        // 重新打开时计入已有的大小
+        let mut file = RotatingFile::with_limits(dir.path(), 20, 3).unwrap();
+        file.write_line("line 1").unwrap();
+        file.write_line("line 2").unwrap();
+        assert_eq!(contents(dir.path()), ["line 2\n", "line 0\nline 1\n"]);
+    }
+
+    #[test]
+    fn failed_rotation_is_retried_on_next_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::with_limits(dir.path(), 16, 1).unwrap();
+        file.write_line("first line").unwrap();
+
+        // 历史文件的位置被目录占用，重命名失败
+        let blocker = file_path(dir.path(), 1);
+        fs::create_dir(&blocker).unwrap();
+        fs::write(blocker.join("keep"), "").unwrap();
+        assert!(file.write_line("second line").is_err());
+
+        fs::remove_dir_all(&blocker).unwrap();
+        file.write_line("third line").unwrap();
+        assert_eq!(fs::read_to_string(&blocker).unwrap(), "first line\n");
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), "third line\n");
+    }
+}
@@ -0,0 +1,205 @@
+//! 后端日志
+//!
+//! 解析后端 stdout/stderr 输出，保存在内存环形缓冲区中，
+//! 同时写入应用日志目录下的滚动文件，并通过 `backend-log` 事件实时推送给前端。
+//! 发布版本没有控制台，排查用户机器上的问题只能依赖这里的记录
+
+mod file;
+mod parser;
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::backend::unix_millis;
+use file::RotatingFile;
+pub use parser::{LogCategory, LogLevel, LogSource};
+
+/// 内存中保留的日志条数
+const BUFFER_CAPACITY: usize = 2000;
+
+/// `get_logs` 默认返回的条数
+pub const DEFAULT_LIMIT: usize = 200;
+
+/// 一条日志
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// 递增序号，前端据此增量拉取
+    pub seq: u64,
+    /// 记录时间（Unix 毫秒）
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub category: LogCategory,
+    pub source: LogSource,
+    pub message: String,
+}
+
+/// 日志查询条件（各字段均可省略）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// 最低级别
+    pub level: Option<LogLevel>,
+    pub category: Option<LogCategory>,
+    /// 消息包含的文本（不区分大小写）
+    pub contains: Option<String>,
+    /// 只返回序号大于该值的日志
+    pub since_seq: Option<u64>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.level.is_none_or(|level| entry.level >= level)
+            && self
+                .category
+                .is_none_or(|category| entry.category == category)
+            && self.since_seq.is_none_or(|seq| entry.seq > seq)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|text| entry.message.to_lowercase().contains(&text.to_lowercase()))
+    }
+}
+
+struct Inner {
+    entries: VecDeque<LogEntry>,
+    next_seq: u64,
+    file: Option<RotatingFile>,
+    /// 上一次写入日志文件是否失败（只在失败与恢复时各提示一次）
+    file_failed: bool,
+}
+
+/// 后端日志存储
+pub struct LogStore {
+    inner: Mutex<Inner>,
+}
+
+impl LogStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: VecDeque::with_capacity(BUFFER_CAPACITY),
+                next_seq: 1,
+                file: None,
+                file_failed: false,
+            }),
+        }
+    }
+
+    /// 打开日志文件；失败时仅保留内存记录
+    pub fn init(&self, dir: &Path) {
+        match RotatingFile::open(dir) {
+            Ok(file) => {
+                println!("[Tauri] 后端日志文件: {}", file.path().display());
+                self.inner.lock().unwrap().file = Some(file);
+            }
+            Err(e) => eprintln!("[Tauri] 无法打开日志文件 {}: {}", dir.display(), e),
+        }
+    }
+
+    /// 当前日志文件路径
+    pub fn file_path(&self) -> Option<PathBuf> {
+        self.inner.lock().unwrap().file.as_ref().map(|f| f.path())
+    }
+
+    /// 记录一行后端输出
+    pub fn push(&self, app_handle: &AppHandle, source: LogSource, line: &str) {
+        let parsed = parser::parse_line(source, line);
+        let timestamp = unix_millis();
+
+        let entry = {
+            let mut inner = self.inner.lock().unwrap();
+            let entry = LogEntry {
+                seq: inner.next_seq,
+                timestamp,
+                level: parsed.level,
+                category: parsed.category,
+                source,
+                message: parsed.message,
+            };
+            inner.next_seq += 1;
+
+            if let Some(file) = inner.file.as_mut() {
+                let text = format!(
+                    "{} {:<5} [{}] {}",
+                    format_timestamp(timestamp),
+                    entry.level.as_str(),
+                    entry.category.as_str(),
+                    entry.message
+                );
+                match file.write_line(&text) {
+                    Ok(()) if inner.file_failed => {
+                        println!("[Tauri] 日志文件已恢复写入");
+                        inner.file_failed = false;
+                    }
+                    Err(e) if !inner.file_failed => {
+                        eprintln!("[Tauri] 写入日志文件失败，下次写入时重试: {}", e);
+                        inner.file_failed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            if inner.entries.len() == BUFFER_CAPACITY {
+                inner.entries.pop_front();
+            }
+            inner.entries.push_back(entry.clone());
+            entry
+        };
+
+        // 开发时仍输出到控制台
+        match source {
+            LogSource::Stdout => println!("[Backend] {}", line),
+            LogSource::Stderr => eprintln!("[Backend Error] {}", line),
+        }
+
+        let _ = app_handle.emit_all("backend-log", &entry);
+    }
+
+    /// 按条件查询最近的日志（按时间顺序返回最后 `limit` 条）
+    pub fn query(&self, filter: &LogFilter, limit: usize) -> Vec<LogEntry> {
+        let inner = self.inner.lock().unwrap();
+        let mut entries: Vec<LogEntry> = inner
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| filter.matches(entry))
+            .take(limit)
+            .cloned()
+            .collect();
+        entries.reverse();
+        entries
+    }
+}
+
+/// 格式化为 `YYYY-MM-DD HH:MM:SS.mmm`（UTC）
+fn format_timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // 公历日期换算（Howard Hinnant 的 civil_from_days 算法）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        millis % 1000
+    )
+}
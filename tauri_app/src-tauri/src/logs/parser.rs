@@ -0,0 +1,291 @@
+//! 后端输出解析
+//!
+//! Python 后端以 `[SERVER]`、`[STATS]`、`[ACTION]`、`[WARN]`、`[ERROR]` 等前缀区分日志，
+//! stderr 上还有 MediaPipe/absl 的 glog 格式输出（如 `I0000 00:00:...`）
+
+use serde::{Deserialize, Serialize};
+
+/// 日志级别（按严重程度递增）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+/// 日志分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogCategory {
+    /// 服务生命周期（`[SERVER]`）
+    Server,
+    /// 每秒统计（`[STATS]`）
+    Stats,
+    /// 动作执行（`[ACTION]`）
+    Action,
+    /// 视频流（`[MJPEG]`）
+    Mjpeg,
+    /// 其他输出
+    Other,
+}
+
+impl LogCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogCategory::Server => "server",
+            LogCategory::Stats => "stats",
+            LogCategory::Action => "action",
+            LogCategory::Mjpeg => "mjpeg",
+            LogCategory::Other => "other",
+        }
+    }
+}
+
+/// 输出来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSource {
+    Stdout,
+    Stderr,
+}
+
+/// 解析结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedLine {
+    pub level: LogLevel,
+    pub category: LogCategory,
+    /// 去掉前缀后的内容
+    pub message: String,
+}
+
+/// 解析一行后端输出
+pub fn parse_line(source: LogSource, line: &str) -> ParsedLine {
+    let line = line.trim();
+
+    if let Some((tag, rest)) = split_tag(line) {
+        let known = match tag {
+            "SERVER" => Some((LogLevel::Info, LogCategory::Server)),
+            "STATS" => Some((LogLevel::Debug, LogCategory::Stats)),
+            "ACTION" => Some((LogLevel::Info, LogCategory::Action)),
+            "MJPEG" => Some((LogLevel::Info, LogCategory::Mjpeg)),
+            "WARN" => Some((LogLevel::Warn, LogCategory::Other)),
+            "ERROR" => Some((LogLevel::Error, LogCategory::Other)),
+            // 未知前缀保留原文
+            _ => None,
+        };
+        if let Some((level, category)) = known {
+            return ParsedLine {
+                level,
+                category,
+                message: rest.to_string(),
+            };
+        }
+    }
+
+    ParsedLine {
+        level: default_level(source, line),
+        category: LogCategory::Other,
+        message: line.to_string(),
+    }
+}
+
+/// 拆出行首的 `[TAG]`
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let end = rest.find(']')?;
+    let tag = &rest[..end];
+    if tag.is_empty() || !tag.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+        return None;
+    }
+    Some((tag, rest[end + 1..].trim_start()))
+}
+
+/// 无前缀时的级别：stdout 视为 info；stderr 识别 glog 级别，否则视为 error（异常堆栈）
+fn default_level(source: LogSource, line: &str) -> LogLevel {
+    if source == LogSource::Stdout {
+        return LogLevel::Info;
+    }
+
+    let mut chars = line.chars();
+    let glog_level = match chars.next() {
+        Some('I') => Some(LogLevel::Info),
+        Some('W') => Some(LogLevel::Warn),
+        Some('E') | Some('F') => Some(LogLevel::Error),
+        _ => None,
+    };
+    let is_glog = chars.take(4).all(|c| c.is_ascii_digit()) && line.len() > 5;
+    match glog_level {
+        Some(level) if is_glog => level,
+        _ if line.contains("Warning") || line.contains("WARNING") => LogLevel::Warn,
+        _ => LogLevel::Error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Case = (LogSource, &'static str, LogLevel, LogCategory, &'static str);
+
+    #[test]
+    fn parses_prefixed_lines() {
+        let cases: &[Case] = &[
+            (
+                LogSource::Stdout,
+                "[SERVER] 启动于 ws://127.0.0.1:8765",
+                LogLevel::Info,
+                LogCategory::Server,
+                "启动于 ws://127.0.0.1:8765",
+            ),
+            (
+                LogSource::Stdout,
+                "[STATS] 帧数: 30, FPS: 29.8, 客户端: 1",
+                LogLevel::Debug,
+                LogCategory::Stats,
+                "帧数: 30, FPS: 29.8, 客户端: 1",
+            ),
+            (
+                LogSource::Stdout,
+                "  [ACTION] 左键单击  ",
+                LogLevel::Info,
+                LogCategory::Action,
+                "左键单击",
+            ),
+            (
+                LogSource::Stdout,
+                "[MJPEG]客户端已连接",
+                LogLevel::Info,
+                LogCategory::Mjpeg,
+                "客户端已连接",
+            ),
+            (
+                LogSource::Stdout,
+                "[WARN] 摄像头帧率过低",
+                LogLevel::Warn,
+                LogCategory::Other,
+                "摄像头帧率过低",
+            ),
+            // 前缀决定级别，与输出来源无关
+            (
+                LogSource::Stderr,
+                "[ERROR] 无法打开摄像头",
+                LogLevel::Error,
+                LogCategory::Other,
+                "无法打开摄像头",
+            ),
+            (
+                LogSource::Stderr,
+                "[SERVER] 正在关闭",
+                LogLevel::Info,
+                LogCategory::Server,
+                "正在关闭",
+            ),
+        ];
+        for &(source, line, level, category, message) in cases {
+            let expected = ParsedLine {
+                level,
+                category,
+                message: message.to_string(),
+            };
+            assert_eq!(parse_line(source, line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn keeps_unknown_or_invalid_prefixes() {
+        let cases: &[Case] = &[
+            (
+                LogSource::Stdout,
+                "[GESTURE] open",
+                LogLevel::Info,
+                LogCategory::Other,
+                "[GESTURE] open",
+            ),
+            (
+                LogSource::Stdout,
+                "[server] 小写前缀",
+                LogLevel::Info,
+                LogCategory::Other,
+                "[server] 小写前缀",
+            ),
+            (
+                LogSource::Stdout,
+                "[] 空前缀",
+                LogLevel::Info,
+                LogCategory::Other,
+                "[] 空前缀",
+            ),
+            (
+                LogSource::Stdout,
+                "[SERVER 缺少右括号",
+                LogLevel::Info,
+                LogCategory::Other,
+                "[SERVER 缺少右括号",
+            ),
+        ];
+        for &(source, line, level, category, message) in cases {
+            let parsed = parse_line(source, line);
+            assert_eq!((parsed.level, parsed.category), (level, category), "{line}");
+            assert_eq!(parsed.message, message);
+        }
+    }
+
+    #[test]
+    fn detects_level_of_bare_lines() {
+        let cases = [
+            (LogSource::Stdout, "普通输出", LogLevel::Info),
+            (LogSource::Stdout, "E0000 看起来像 glog", LogLevel::Info),
+            // MediaPipe / absl 的 glog 输出
+            (
+                LogSource::Stderr,
+                "I0000 00:00:1712345678.123456   1234 gl_context.cc:357] GL version: 3.2",
+                LogLevel::Info,
+            ),
+            (
+                LogSource::Stderr,
+                "W0000 00:00:1712345678.123456   1234 inference_feedback_manager.cc:114] ...",
+                LogLevel::Warn,
+            ),
+            (LogSource::Stderr, "E1231 23:59:59.000000 x.cc:1] 失败", LogLevel::Error),
+            (LogSource::Stderr, "F0101 00:00:00.000000 x.cc:1] 致命", LogLevel::Error),
+            // 不是 glog：首字母后不是数字，或行太短
+            (LogSource::Stderr, "Info: 不是 glog", LogLevel::Error),
+            (LogSource::Stderr, "I0000", LogLevel::Error),
+            // Python 警告与异常堆栈
+            (
+                LogSource::Stderr,
+                "/app/core/hand.py:12: UserWarning: deprecated",
+                LogLevel::Warn,
+            ),
+            (
+                LogSource::Stderr,
+                "WARNING: All log messages before absl::InitializeLog() is called are written to STDERR",
+                LogLevel::Warn,
+            ),
+            (
+                LogSource::Stderr,
+                "Traceback (most recent call last):",
+                LogLevel::Error,
+            ),
+        ];
+        for (source, line, level) in cases {
+            let parsed = parse_line(source, line);
+            assert_eq!(parsed.level, level, "{line}");
+            assert_eq!(parsed.category, LogCategory::Other);
+            assert_eq!(parsed.message, line);
+        }
+    }
+}
@@ -7,103 +7,692 @@
     windows_subsystem = "windows"
 )]
 
-use tauri::{
-    api::process::{Command, CommandEvent},
-    Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, CustomMenuItem,
+mod backend;
+mod bridge;
+mod calibration;
+mod display;
+mod emergency;
+mod executor;
+mod filter;
+mod focus;
+mod input;
+mod lifecycle;
+mod logs;
+mod mapping;
+mod metrics;
+mod profiles;
+mod settings;
+mod shortcuts;
+mod single_instance;
+mod tray;
+
+use tauri::{Manager, RunEvent, SystemTray, SystemTrayEvent, WindowEvent};
+
+use backend::{BackendEndpoints, BackendStatus, BackendSupervisor, RestartPolicy};
+use bridge::{BackendBridge, BridgeStatus};
+use calibration::{CalibrationStatus, CalibrationStore};
+use display::{DisplayStatus, DisplayTopology};
+use executor::{ActionExecutor, AuditEntry, ExecutorStatus};
+use filter::{FilterPipeline, TraceEntry};
+use focus::{FocusStatus, FocusTracker};
+use input::{InputEvent, InputInjector, InputStatus};
+use lifecycle::CloseAction;
+use logs::{LogEntry, LogFilter, LogStore};
+use metrics::{Metrics, MetricsCollector};
+use phantom_protocol::ActionBinding;
+use profiles::GestureProfile;
+use settings::{
+    Change, Format, ImportPreview, PresetInfo, Settings, SettingsApplied, SettingsStore,
 };
-use std::sync::Mutex;
+use shortcuts::{ShortcutAction, ShortcutInfo, Shortcuts};
+use single_instance::Instance;
+use tray::TrayController;
 
-/// 全局状态：存储后端进程句柄
+/// 全局状态
 struct AppState {
-    backend_running: Mutex<bool>,
-}
-
-/// 启动 Python 后端 sidecar
-fn start_backend(app_handle: &tauri::AppHandle) -> Result<(), String> {
-    let (mut rx, _child) = Command::new_sidecar("PhantomHandBackend")
-        .map_err(|e| format!("无法创建 sidecar: {}", e))?
-        .args(["--port", "8765"])
-        .spawn()
-        .map_err(|e| format!("无法启动后端: {}", e))?;
-
-    // 在后台线程监听后端输出
-    let app_handle_clone = app_handle.clone();
-    tauri::async_runtime::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommandEvent::Stdout(line) => {
-                    println!("[Backend] {}", line);
-                }
-                CommandEvent::Stderr(line) => {
-                    eprintln!("[Backend Error] {}", line);
-                }
-                CommandEvent::Error(err) => {
-                    eprintln!("[Backend Fatal] {}", err);
-                    // 可以通知前端后端崩溃
-                    let _ = app_handle_clone.emit_all("backend-error", err);
-                }
-                CommandEvent::Terminated(payload) => {
-                    println!("[Backend] 进程退出: {:?}", payload);
-                    let _ = app_handle_clone.emit_all("backend-stopped", ());
-                }
-                _ => {}
+    /// 后端进程监督器
+    backend: BackendSupervisor,
+    /// 后端 WebSocket 客户端
+    bridge: BackendBridge,
+    /// 后端日志
+    logs: LogStore,
+    /// 运行指标
+    metrics: MetricsCollector,
+    /// 托盘菜单
+    tray: TrayController,
+    /// 持久化设置
+    settings: SettingsStore,
+    /// 全局快捷键
+    shortcuts: Shortcuts,
+    /// 前台窗口与按应用生效的手势配置
+    focus: FocusTracker,
+    /// 宿主侧输入注入
+    input: InputInjector,
+    /// 宿主侧动作执行
+    executor: ActionExecutor,
+    /// 显示器拓扑与鼠标映射区域
+    display: DisplayTopology,
+    /// 鼠标映射校准
+    calibration: CalibrationStore,
+    /// 关键点滤波
+    filter: FilterPipeline,
+}
+
+/// 显示并聚焦主窗口
+fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// 切换手势配置并推送给后端（未连接时在连接后推送）
+fn set_profile(app: &tauri::AppHandle, id: &str) -> Result<(), String> {
+    let profile = profiles::find(id).ok_or_else(|| format!("未知的手势配置: {}", id))?;
+    let state = app.state::<AppState>();
+    let before = state.settings.get();
+    let after = state
+        .settings
+        .modify(|settings| settings.host.profile = profile.id.to_string())?;
+    apply_settings(app, &before, &after)
+}
+
+/// 把设置变化同步到宿主各模块，并把识别参数推送给运行中的后端
+fn apply_settings(
+    app: &tauri::AppHandle,
+    before: &Settings,
+    after: &Settings,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    state
+        .backend
+        .set_restart_policy(after.host.restart_policy.clone())?;
+    state.shortcuts.apply(app, &after.host.shortcuts);
+
+    if before.host.profile != after.host.profile {
+        state
+            .tray
+            .update(app, |view| view.profile = after.host.profile.clone());
+    }
+    // 手势配置或应用规则变化时重新确定生效的配置
+    state.focus.sync(app)?;
+    let active = state.focus.resolve(after);
+    if mapping::bindings(before, &active.profile) != mapping::bindings(after, &active.profile) {
+        mapping::push(app, after, &active.profile)?;
+        println!("[Tauri] 已更新动作绑定: {}", active.profile);
+    }
+    if before.host.input != after.host.input {
+        state.input.configure(&after.host.input);
+        if state.bridge.status().connected {
+            state.bridge.set_input_delegate(state.input.delegate())?;
+        }
+    }
+    if before.host.display != after.host.display {
+        state.display.configure(app, &after.host.display);
+    }
+    // 校准按摄像头保存（映射区域变化时由 display 刷新）
+    if before.camera != after.camera {
+        state.calibration.refresh(app);
+    }
+    if before.host.filter != after.host.filter {
+        state.filter.configure(&after.host.filter);
+    }
+    // 注入后端变化也会影响能否由宿主执行
+    if before.host.input != after.host.input
+        || before.host.executor != after.host.executor
+        || before.host.filter != after.host.filter
+        || before.action != after.action
+    {
+        state.executor.configure(app, after);
+        if state.bridge.status().connected {
+            state
+                .bridge
+                .set_host_execution(state.executor.host_execution())?;
+        }
+    }
+
+    let changes = settings::backend_changes(before, after);
+    if !changes.live.is_empty() || !changes.restart_required.is_empty() {
+        let restart_required = changes.restart_required.clone();
+        let pushed = if changes.live.is_empty() {
+            Ok(None)
+        } else {
+            state
+                .bridge
+                .update_config(changes.live, changes.restart_required)
+                .map(Some)
+        };
+        // 已推送时由后端的确认触发 settings-applied；未连接时在连接后同步
+        match pushed {
+            Ok(Some(request_id)) => println!("[Tauri] 已推送配置更新 #{}", request_id),
+            result => {
+                let applied = SettingsApplied {
+                    request_id: None,
+                    applied: Vec::new(),
+                    restart_required,
+                    deferred: result.is_err(),
+                    error: None,
+                };
+                let _ = app.emit_all("settings-applied", applied);
             }
         }
-    });
+    }
 
-    println!("[Tauri] 后端已启动");
+    let _ = app.emit_all("settings-changed", after);
     Ok(())
 }
 
-/// 创建系统托盘菜单
-fn create_tray_menu() -> SystemTrayMenu {
-    let show = CustomMenuItem::new("show".to_string(), "显示窗口");
-    let hide = CustomMenuItem::new("hide".to_string(), "隐藏窗口");
-    let quit = CustomMenuItem::new("quit".to_string(), "退出");
+/// Tauri 命令：获取后端状态
+#[tauri::command]
+fn get_backend_status(state: tauri::State<AppState>) -> BackendStatus {
+    state.backend.status()
+}
+
+/// Tauri 命令：获取后端 WebSocket 与视频流地址
+#[tauri::command]
+fn get_backend_endpoints(state: tauri::State<AppState>) -> Result<BackendEndpoints, String> {
+    state
+        .backend
+        .endpoints()
+        .ok_or_else(|| "后端尚未启动".to_string())
+}
 
-    SystemTrayMenu::new()
-        .add_item(show)
-        .add_item(hide)
-        .add_native_item(tauri::SystemTrayMenuItem::Separator)
-        .add_item(quit)
+/// Tauri 命令：获取宿主与后端的 WebSocket 连接状态
+#[tauri::command]
+fn get_bridge_status(state: tauri::State<AppState>) -> BridgeStatus {
+    state.bridge.status()
 }
 
-/// Tauri 命令：获取后端状态
+/// Tauri 命令：设置手势控制激活状态
+#[tauri::command]
+fn set_control_active(active: bool, state: tauri::State<AppState>) -> Result<(), String> {
+    state.bridge.set_active(active)
+}
+
+/// Tauri 命令：暂停或恢复摄像头
+#[tauri::command]
+fn set_camera_paused(paused: bool, state: tauri::State<AppState>) -> Result<(), String> {
+    state.bridge.set_camera_paused(paused)
+}
+
+/// Tauri 命令：获取内置手势配置
+#[tauri::command]
+fn get_gesture_profiles() -> &'static [GestureProfile] {
+    profiles::BUILTIN_PROFILES
+}
+
+/// Tauri 命令：获取当前手势配置
+#[tauri::command]
+fn get_active_profile(state: tauri::State<AppState>) -> String {
+    state
+        .settings
+        .read(|settings| settings.host.profile.clone())
+}
+
+/// Tauri 命令：获取前台窗口与实际生效的手势配置
+#[tauri::command]
+fn get_app_profile_status(state: tauri::State<AppState>) -> FocusStatus {
+    state.focus.status()
+}
+
+/// Tauri 命令：获取输入注入状态
+#[tauri::command]
+fn get_input_status(state: tauri::State<AppState>) -> InputStatus {
+    state.input.status()
+}
+
+/// Tauri 命令：取出 mock 注入方式记录的输入事件
+#[tauri::command]
+fn take_recorded_input(state: tauri::State<AppState>) -> Result<Vec<InputEvent>, String> {
+    state.input.take_recorded()
+}
+
+/// Tauri 命令：获取宿主执行状态
+#[tauri::command]
+fn get_executor_status(state: tauri::State<AppState>) -> ExecutorStatus {
+    state.executor.status()
+}
+
+/// Tauri 命令：获取宿主执行动作的审计记录
 #[tauri::command]
-fn get_backend_status(state: tauri::State<AppState>) -> bool {
-    *state.backend_running.lock().unwrap()
+fn get_action_audit(state: tauri::State<AppState>) -> Vec<AuditEntry> {
+    state.executor.audit()
 }
 
-/// Tauri 命令：重启后端
+/// Tauri 命令：清空审计记录
 #[tauri::command]
-async fn restart_backend(app_handle: tauri::AppHandle) -> Result<String, String> {
-    // 注意：这里简化处理，实际可能需要先停止旧进程
-    start_backend(&app_handle)?;
+fn clear_action_audit(state: tauri::State<AppState>) {
+    state.executor.clear_audit()
+}
+
+/// Tauri 命令：获取显示器与鼠标映射区域
+#[tauri::command]
+fn get_display_status(state: tauri::State<AppState>) -> DisplayStatus {
+    state.display.status()
+}
+
+/// Tauri 命令：获取关键点滤波的调试记录
+#[tauri::command]
+fn get_filter_trace(state: tauri::State<AppState>) -> Vec<TraceEntry> {
+    state.filter.trace()
+}
+
+/// Tauri 命令：清空关键点滤波的调试记录
+#[tauri::command]
+fn clear_filter_trace(state: tauri::State<AppState>) {
+    state.filter.clear_trace()
+}
+
+/// Tauri 命令：获取鼠标映射校准状态
+#[tauri::command]
+fn get_calibration_status(state: tauri::State<AppState>) -> CalibrationStatus {
+    state.calibration.status()
+}
+
+/// Tauri 命令：开始校准当前摄像头与映射区域，进度通过 `calibration-changed` 事件通知
+#[tauri::command]
+fn start_calibration(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<CalibrationStatus, String> {
+    state.calibration.start(&app_handle)?;
+    Ok(state.calibration.status())
+}
+
+/// Tauri 命令：立即记录当前校准目标
+#[tauri::command]
+fn capture_calibration_point(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<CalibrationStatus, String> {
+    state.calibration.capture(&app_handle)?;
+    Ok(state.calibration.status())
+}
+
+/// Tauri 命令：取消进行中的校准
+#[tauri::command]
+fn cancel_calibration(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> CalibrationStatus {
+    state.calibration.cancel(&app_handle);
+    state.calibration.status()
+}
+
+/// Tauri 命令：删除当前摄像头与映射区域的校准
+#[tauri::command]
+fn clear_calibration(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<CalibrationStatus, String> {
+    state.calibration.clear(&app_handle)?;
+    Ok(state.calibration.status())
+}
+
+/// Tauri 命令：切换手势配置
+#[tauri::command]
+fn set_active_profile(id: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    set_profile(&app_handle, &id)
+}
+
+/// Tauri 命令：修改手势配置的动作绑定，返回规范化后的绑定
+#[tauri::command]
+fn set_profile_bindings(
+    profile: String,
+    mut bindings: Vec<ActionBinding>,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ActionBinding>, String> {
+    let Some(found) = profiles::find(&profile) else {
+        return Err(format!("未知的手势配置: {}", profile));
+    };
+    mapping::normalize(&mut bindings)?;
+    mapping::validate(found, &bindings).map_err(|e| format!("{}{}", profile, e))?;
+    let before = state.settings.get();
+    let after = state.settings.modify(|settings| {
+        settings.host.mappings.insert(profile.clone(), bindings);
+    })?;
+    apply_settings(&app_handle, &before, &after)?;
+    Ok(mapping::bindings(&after, &profile).to_vec())
+}
+
+/// Tauri 命令：恢复手势配置的默认绑定
+#[tauri::command]
+fn reset_profile_bindings(
+    profile: String,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ActionBinding>, String> {
+    let defaults = profiles::find(&profile)
+        .ok_or_else(|| format!("未知的手势配置: {}", profile))?
+        .default_bindings();
+    let before = state.settings.get();
+    let after = state.settings.modify(|settings| {
+        settings.host.mappings.insert(profile.clone(), defaults);
+    })?;
+    apply_settings(&app_handle, &before, &after)?;
+    Ok(mapping::bindings(&after, &profile).to_vec())
+}
+
+/// Tauri 命令：启动后端
+#[tauri::command]
+fn start_backend(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    state.backend.start(&app_handle)?;
+    Ok("后端已启动".to_string())
+}
+
+/// Tauri 命令：停止后端
+#[tauri::command]
+async fn stop_backend(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    state.backend.stop(&app_handle).await?;
+    Ok("后端已停止".to_string())
+}
+
+/// Tauri 命令：重启后端（先停止旧进程）
+#[tauri::command]
+async fn restart_backend(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    state.backend.restart(&app_handle).await?;
     Ok("后端已重启".to_string())
 }
 
+/// Tauri 命令：获取自动重启策略
+#[tauri::command]
+fn get_restart_policy(state: tauri::State<AppState>) -> RestartPolicy {
+    state.backend.restart_policy()
+}
+
+/// Tauri 命令：更新自动重启策略
+#[tauri::command]
+fn set_restart_policy(policy: RestartPolicy, state: tauri::State<AppState>) -> Result<(), String> {
+    state.backend.set_restart_policy(policy.clone())?;
+    state
+        .settings
+        .modify(|settings| settings.host.restart_policy = policy)
+        .map(|_| ())
+}
+
+/// Tauri 命令：获取关闭主窗口时的行为
+#[tauri::command]
+fn get_close_action(state: tauri::State<AppState>) -> CloseAction {
+    state.settings.read(|settings| settings.host.close_action)
+}
+
+/// Tauri 命令：设置关闭主窗口时的行为
+#[tauri::command]
+fn set_close_action(action: CloseAction, state: tauri::State<AppState>) -> Result<(), String> {
+    state
+        .settings
+        .modify(|settings| settings.host.close_action = action)
+        .map(|_| ())
+}
+
+/// Tauri 命令：获取全部设置
+#[tauri::command]
+fn get_settings(state: tauri::State<AppState>) -> Settings {
+    state.settings.get()
+}
+
+/// Tauri 命令：更新设置（只需包含要修改的字段），返回更新后的设置
+#[tauri::command]
+fn update_settings(
+    patch: serde_json::Value,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Settings, String> {
+    let before = state.settings.get();
+    let after = state.settings.update(patch)?;
+    apply_settings(&app_handle, &before, &after)?;
+    Ok(after)
+}
+
+/// Tauri 命令：恢复默认设置
+#[tauri::command]
+fn reset_settings(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Settings, String> {
+    let before = state.settings.get();
+    let after = state.settings.reset()?;
+    apply_settings(&app_handle, &before, &after)?;
+    Ok(after)
+}
+
+/// Tauri 命令：导出完整设置（`toml` 或 `json`）
+#[tauri::command]
+fn export_settings(format: Format, state: tauri::State<AppState>) -> Result<String, String> {
+    state.settings.export(format)
+}
+
+/// Tauri 命令：预览导入设置带来的变化与其中由宿主执行的动作
+#[tauri::command]
+fn preview_settings_import(
+    text: String,
+    format: Format,
+    state: tauri::State<AppState>,
+) -> Result<ImportPreview, String> {
+    state.settings.preview_import(&text, format)
+}
+
+/// Tauri 命令：导入设置，返回更新后的设置
+#[tauri::command]
+fn import_settings(
+    text: String,
+    format: Format,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Settings, String> {
+    let before = state.settings.get();
+    let after = state.settings.import(&text, format)?;
+    apply_settings(&app_handle, &before, &after)?;
+    println!("[Tauri] 已导入设置");
+    Ok(after)
+}
+
+/// Tauri 命令：获取调校预设列表
+#[tauri::command]
+fn get_presets(state: tauri::State<AppState>) -> Vec<PresetInfo> {
+    state.settings.presets()
+}
+
+/// Tauri 命令：预览应用预设带来的变化
+#[tauri::command]
+fn preview_preset(id: String, state: tauri::State<AppState>) -> Result<Vec<Change>, String> {
+    state.settings.preview_preset(&id)
+}
+
+/// Tauri 命令：应用预设，返回更新后的设置
+#[tauri::command]
+fn apply_preset(
+    id: String,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Settings, String> {
+    let before = state.settings.get();
+    let after = state.settings.apply_preset(&id)?;
+    apply_settings(&app_handle, &before, &after)?;
+    println!("[Tauri] 已应用预设: {}", id);
+    Ok(after)
+}
+
+/// Tauri 命令：把当前参数保存为预设
+#[tauri::command]
+fn save_preset(name: String, state: tauri::State<AppState>) -> Result<Vec<PresetInfo>, String> {
+    state.settings.save_preset(&name)?;
+    Ok(state.settings.presets())
+}
+
+/// Tauri 命令：删除用户预设
+#[tauri::command]
+fn delete_preset(name: String, state: tauri::State<AppState>) -> Result<Vec<PresetInfo>, String> {
+    state.settings.delete_preset(&name)?;
+    Ok(state.settings.presets())
+}
+
+/// Tauri 命令：导出预设用于分享
+#[tauri::command]
+fn export_preset(
+    id: String,
+    format: Format,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    state.settings.export_preset(&id, format)
+}
+
+/// Tauri 命令：导入分享的预设并以 `name` 保存
+#[tauri::command]
+fn import_preset(
+    name: String,
+    text: String,
+    format: Format,
+    state: tauri::State<AppState>,
+) -> Result<Vec<PresetInfo>, String> {
+    state.settings.import_preset(&name, &text, format)?;
+    Ok(state.settings.presets())
+}
+
+/// Tauri 命令：查询后端日志
+#[tauri::command]
+fn get_logs(
+    filter: Option<LogFilter>,
+    limit: Option<usize>,
+    state: tauri::State<AppState>,
+) -> Vec<LogEntry> {
+    state.logs.query(
+        &filter.unwrap_or_default(),
+        limit.unwrap_or(logs::DEFAULT_LIMIT),
+    )
+}
+
+/// Tauri 命令：获取后端日志文件路径
+#[tauri::command]
+fn get_log_file_path(state: tauri::State<AppState>) -> Result<String, String> {
+    state
+        .logs
+        .file_path()
+        .map(|path| path.display().to_string())
+        .ok_or_else(|| "日志文件未打开".to_string())
+}
+
+/// Tauri 命令：获取最近的运行指标
+#[tauri::command]
+fn get_metrics(state: tauri::State<AppState>) -> Metrics {
+    state.metrics.latest()
+}
+
+/// Tauri 命令：紧急停止（停用控制并释放按键，失败时结束后端）
+#[tauri::command]
+fn emergency_stop(app_handle: tauri::AppHandle) {
+    emergency::trigger(&app_handle);
+}
+
+/// Tauri 命令：获取全局快捷键绑定及注册状态
+#[tauri::command]
+fn get_shortcuts(state: tauri::State<AppState>) -> Vec<ShortcutInfo> {
+    state.shortcuts.list()
+}
+
+/// Tauri 命令：修改全局快捷键（如 `CmdOrCtrl+Shift+F9`，为空时禁用）
+#[tauri::command]
+fn set_shortcut(
+    action: ShortcutAction,
+    accelerator: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ShortcutInfo>, String> {
+    let previous = state.shortcuts.bindings().get(&action).cloned().flatten();
+    state
+        .shortcuts
+        .set(&app_handle, action, accelerator.as_deref())?;
+    let bindings = state.shortcuts.bindings();
+    if let Err(e) = state
+        .settings
+        .modify(|settings| settings.host.shortcuts = bindings)
+    {
+        // 保存失败时恢复原组合，使生效的快捷键与设置文件一致
+        if let Err(restore) = state
+            .shortcuts
+            .set(&app_handle, action, previous.as_deref())
+        {
+            eprintln!("[Tauri] 无法恢复原快捷键: {}", restore);
+        }
+        return Err(e);
+    }
+    Ok(state.shortcuts.list())
+}
+
+/// Tauri 命令：恢复默认快捷键
+#[tauri::command]
+fn reset_shortcuts(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<AppState>,
+) -> Result<Vec<ShortcutInfo>, String> {
+    let settings = state
+        .settings
+        .modify(|settings| settings.host.shortcuts = shortcuts::default_bindings())?;
+    state.shortcuts.apply(&app_handle, &settings.host.shortcuts);
+    Ok(state.shortcuts.list())
+}
+
+/// Tauri 命令：有序退出应用
+#[tauri::command]
+fn quit_app(app_handle: tauri::AppHandle) {
+    lifecycle::request_exit(&app_handle);
+}
+
 fn main() {
-    let system_tray = SystemTray::new().with_menu(create_tray_menu());
+    let context = tauri::generate_context!();
+
+    // 单实例：已有实例运行时转发参数后退出，避免两个后端争抢摄像头和端口
+    let instance_dir = single_instance::instance_dir(&context.config().tauri.bundle.identifier);
+    let primary = match single_instance::acquire(&instance_dir) {
+        Ok(Instance::Primary(primary)) => Some(primary),
+        Ok(Instance::Forwarded) => {
+            println!("[Tauri] 已有实例在运行，已转发启动参数");
+            return;
+        }
+        Err(e) => {
+            eprintln!("[Tauri] 单实例检测失败: {}", e);
+            None
+        }
+    };
+
+    let system_tray = SystemTray::new().with_menu(tray::create_menu());
 
     tauri::Builder::default()
         .manage(AppState {
-            backend_running: Mutex::new(false),
+            backend: BackendSupervisor::new(),
+            bridge: BackendBridge::new(),
+            logs: LogStore::new(),
+            metrics: MetricsCollector::new(),
+            tray: TrayController::new(),
+            settings: SettingsStore::new(),
+            shortcuts: Shortcuts::new(),
+            focus: FocusTracker::new(),
+            input: InputInjector::new(),
+            executor: ActionExecutor::new(),
+            display: DisplayTopology::new(),
+            calibration: CalibrationStore::new(),
+            filter: FilterPipeline::new(),
         })
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
             SystemTrayEvent::LeftClick { .. } => {
                 // 左键点击显示窗口
-                if let Some(window) = app.get_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                show_main_window(app);
             }
             SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
                 "show" => {
-                    if let Some(window) = app.get_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+                    show_main_window(app);
                 }
                 "hide" => {
                     if let Some(window) = app.get_window("main") {
@@ -111,28 +700,149 @@ fn main() {
                     }
                 }
                 "quit" => {
-                    std::process::exit(0);
+                    lifecycle::request_exit(app);
                 }
-                _ => {}
+                id => tray::on_menu_item_click(app, id),
             },
             _ => {}
         })
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { api, .. } = event.event() {
+                if event.window().label() == "main" {
+                    api.prevent_close();
+                    lifecycle::on_close_requested(event.window());
+                }
+            }
+        })
         .setup(|app| {
+            // 接收后续启动转发的参数
+            if let Some(primary) = primary {
+                primary.listen(app.handle());
+            }
+
+            let state = app.state::<AppState>();
+
+            // 打开日志文件（须在启动后端之前）
+            match app.path_resolver().app_log_dir() {
+                Some(dir) => state.logs.init(&dir),
+                None => eprintln!("[Tauri] 无法获取日志目录"),
+            }
+
+            // 读取设置（须在启动后端之前）
+            match app.path_resolver().app_config_dir() {
+                Some(dir) => {
+                    state.settings.init(&dir);
+                    state.calibration.init(&dir);
+                }
+                None => eprintln!("[Tauri] 无法获取配置目录"),
+            }
+            let settings = state.settings.get();
+            if let Err(e) = state
+                .backend
+                .set_restart_policy(settings.host.restart_policy.clone())
+            {
+                eprintln!("[Tauri] {}", e);
+            }
+            let profile = settings.host.profile.clone();
+            state
+                .tray
+                .update(&app.handle(), |view| view.profile = profile);
+            if let Err(e) = state.focus.sync(&app.handle()) {
+                eprintln!("[Tauri] {}", e);
+            }
+            state.filter.configure(&settings.host.filter);
+            state.input.configure(&settings.host.input);
+            state.executor.configure(&app.handle(), &settings);
+
+            // 托盘图标随状态切换
+            state.tray.refresh(&app.handle());
+
+            // 全局快捷键（注册失败时仍可通过托盘操作）
+            state
+                .shortcuts
+                .init(&app.handle(), &settings.host.shortcuts);
+
             // 启动后端
-            if let Err(e) = start_backend(&app.handle()) {
+            if let Err(e) = state.backend.start(&app.handle()) {
                 eprintln!("[Tauri] 启动后端失败: {}", e);
                 // 可以选择继续运行（仅前端）或退出
-            } else {
-                let state = app.state::<AppState>();
-                *state.backend_running.lock().unwrap() = true;
             }
 
+            // 每秒推送运行指标
+            metrics::spawn_sampler(app.handle());
+
+            // 按前台应用切换手势配置
+            focus::spawn_watcher(app.handle());
+
+            // 显示器热插拔与鼠标映射区域
+            state
+                .display
+                .configure(&app.handle(), &settings.host.display);
+            display::spawn_watcher(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_backend_status,
-            restart_backend
+            get_backend_endpoints,
+            get_bridge_status,
+            set_control_active,
+            set_camera_paused,
+            get_gesture_profiles,
+            get_active_profile,
+            set_active_profile,
+            get_app_profile_status,
+            get_input_status,
+            take_recorded_input,
+            get_executor_status,
+            get_action_audit,
+            clear_action_audit,
+            get_display_status,
+            get_filter_trace,
+            clear_filter_trace,
+            get_calibration_status,
+            start_calibration,
+            capture_calibration_point,
+            cancel_calibration,
+            clear_calibration,
+            set_profile_bindings,
+            reset_profile_bindings,
+            start_backend,
+            stop_backend,
+            restart_backend,
+            get_restart_policy,
+            set_restart_policy,
+            get_close_action,
+            set_close_action,
+            get_settings,
+            update_settings,
+            reset_settings,
+            export_settings,
+            preview_settings_import,
+            import_settings,
+            get_presets,
+            preview_preset,
+            apply_preset,
+            save_preset,
+            delete_preset,
+            export_preset,
+            import_preset,
+            get_logs,
+            get_log_file_path,
+            get_metrics,
+            emergency_stop,
+            get_shortcuts,
+            set_shortcut,
+            reset_shortcuts,
+            quit_app
         ])
-        .run(tauri::generate_context!())
-        .expect("运行 Tauri 应用时出错");
+        .build(context)
+        .expect("运行 Tauri 应用时出错")
+        .run(|app_handle, event| {
+            // 其他途径触发的退出（如最后一个窗口被销毁）同样走有序关闭流程
+            if let RunEvent::ExitRequested { api, .. } = event {
+                api.prevent_exit();
+                lifecycle::request_exit(app_handle);
+            }
+        });
 }
@@ -0,0 +1,132 @@
+//! 由宿主执行的动作
+//!
+//! 启动程序、打开链接、执行命令与发送事件不需要注入输入，由宿主在收到后端的
+//! `gesture_event` 后执行。后端不转发 hold 事件，因此这类动作只能绑定在 enter、exit 与 slide 上
+
+use std::process::{Child, Command};
+
+use phantom_protocol::{Action, GestureEvent, GestureEventType};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+
+/// `gesture-action` 事件内容（`event` 动作）
+#[derive(Debug, Clone, Serialize)]
+pub struct GestureAction {
+    pub name: String,
+    pub gesture: String,
+    pub event_type: GestureEventType,
+    pub hand_id: String,
+}
+
+/// 执行当前手势配置中与事件匹配的宿主动作（仅在控制激活时）
+pub fn on_gesture_event(app_handle: &AppHandle, event: &GestureEvent) {
+    let state = app_handle.state::<AppState>();
+    if state.bridge.status().active != Some(true) {
+        return;
+    }
+
+    // 滑动事件的手势名为 slide_<方向>，绑定中只写方向
+    let gesture = match event.event_type {
+        GestureEventType::Slide => event
+            .gesture
+            .strip_prefix("slide_")
+            .unwrap_or(&event.gesture),
+        _ => &event.gesture,
+    };
+    let settings = state.settings.get();
+    let active = state.focus.resolve(&settings);
+    let actions = super::bindings(&settings, &active.profile)
+        .iter()
+        .filter(|b| b.gesture == gesture && b.on == event.event_type && b.action.is_host())
+        .map(|b| b.action.clone());
+
+    for action in actions {
+        if let Err(e) = execute(app_handle, &action, event) {
+            eprintln!("[Tauri] 动作执行失败: {}", e);
+        }
+    }
+}
+
+fn execute(app_handle: &AppHandle, action: &Action, event: &GestureEvent) -> Result<(), String> {
+    let child = match action {
+        Action::Launch { program, args } => {
+            println!("[Tauri] 启动程序: {}", program);
+            Command::new(program)
+                .args(args)
+                .spawn()
+                .map_err(|e| format!("无法启动 {}: {}", program, e))?
+        }
+        Action::OpenUrl { url } => {
+            println!("[Tauri] 打开链接: {}", url);
+            open_command(url)
+                .spawn()
+                .map_err(|e| format!("无法打开 {}: {}", url, e))?
+        }
+        Action::Shell { command } => {
+            println!("[Tauri] 执行命令: {}", command);
+            shell_command(command)
+                .spawn()
+                .map_err(|e| format!("无法执行命令: {}", e))?
+        }
+        Action::Event { name } => {
+            let payload = GestureAction {
+                name: name.clone(),
+                gesture: event.gesture.clone(),
+                event_type: event.event_type,
+                hand_id: event.hand_id.clone(),
+            };
+            return app_handle
+                .emit_all("gesture-action", payload)
+                .map_err(|e| e.to_string());
+        }
+        _ => return Ok(()),
+    };
+    reap(child);
+    Ok(())
+}
+
+/// 在后台等待子进程结束，避免留下僵尸进程
+fn reap(mut child: Child) {
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => eprintln!("[Tauri] 动作进程退出: {}", status),
+        Ok(_) => {}
+        Err(e) => eprintln!("[Tauri] 无法等待动作进程: {}", e),
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn open_command(url: &str) -> Command {
+    let mut command = Command::new("rundll32");
+    command.args(["url.dll,FileProtocolHandler", url]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn open_command(url: &str) -> Command {
+    let mut command = Command::new("open");
+    command.arg(url);
+    command
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn open_command(url: &str) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(url);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(line: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", line]);
+    command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_command(line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", line]);
+    command
+}
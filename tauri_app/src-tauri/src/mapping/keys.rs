@@ -0,0 +1,134 @@
+//! 组合键的解析与规范化
+//!
+//! 规范形式为修饰键（Ctrl、Alt、Shift、Super 依次排列）加一个主键，如 `Ctrl+Shift+T`。
+//! 后端按规范名称查找虚拟键码，新增按键时需同步修改 `core/action.py` 中的 `KEY_CODES`
+
+/// 修饰键的规范名称与别名
+const MODIFIERS: &[(&str, &[&str])] = &[
+    ("Ctrl", &["ctrl", "control"]),
+    ("Alt", &["alt", "option"]),
+    ("Shift", &["shift"]),
+    ("Super", &["super", "meta", "win", "cmd", "command"]),
+];
+
+/// 具名按键的规范名称与别名
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+    ("Enter", &["enter", "return"]),
+    ("Tab", &["tab"]),
+    ("Space", &["space"]),
+    ("Escape", &["escape", "esc"]),
+    ("Backspace", &["backspace"]),
+    ("Delete", &["delete", "del"]),
+    ("Insert", &["insert", "ins"]),
+    ("Home", &["home"]),
+    ("End", &["end"]),
+    ("PageUp", &["pageup", "pgup"]),
+    ("PageDown", &["pagedown", "pgdn"]),
+    ("Left", &["left"]),
+    ("Right", &["right"]),
+    ("Up", &["up"]),
+    ("Down", &["down"]),
+    ("PrintScreen", &["printscreen", "prtsc"]),
+    ("Minus", &["minus", "-"]),
+    ("Equal", &["equal", "="]),
+    ("Comma", &["comma", ","]),
+    ("Period", &["period", "."]),
+    ("Slash", &["slash", "/"]),
+    ("Semicolon", &["semicolon", ";"]),
+    ("Quote", &["quote", "'"]),
+    ("BracketLeft", &["bracketleft", "["]),
+    ("BracketRight", &["bracketright", "]"]),
+    ("Backslash", &["backslash", "\\"]),
+    ("Backquote", &["backquote", "`"]),
+];
+
+/// 规范化组合键，按键名不区分大小写
+pub fn normalize(keys: &str) -> Result<String, String> {
+    let mut modifiers = Vec::new();
+    let mut key: Option<String> = None;
+
+    for part in keys.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err(format!("无效的组合键: {}", keys));
+        }
+        let lower = part.to_lowercase();
+        match lookup(MODIFIERS, &lower) {
+            Some(index) if !modifiers.contains(&index) => modifiers.push(index),
+            Some(_) => return Err(format!("组合键中有重复的修饰键: {}", keys)),
+            None if key.is_some() => return Err(format!("组合键只能包含一个主键: {}", keys)),
+            None => key = Some(main_key(&lower).ok_or_else(|| format!("未知的按键: {}", part))?),
+        }
+    }
+
+    let key = key.ok_or_else(|| format!("组合键缺少主键: {}", keys))?;
+    modifiers.sort_unstable();
+    let mut parts: Vec<String> = modifiers
+        .into_iter()
+        .map(|index| MODIFIERS[index].0.to_string())
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+fn lookup(table: &[(&str, &[&str])], lower: &str) -> Option<usize> {
+    table
+        .iter()
+        .position(|(_, aliases)| aliases.contains(&lower))
+}
+
+/// 主键的规范名称：字母、数字、F1-F24 或具名按键
+fn main_key(lower: &str) -> Option<String> {
+    let mut chars = lower.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+    }
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=24).contains(&number) {
+            return Some(format!("F{}", number));
+        }
+    }
+    lookup(NAMED_KEYS, lower).map(|index| NAMED_KEYS[index].0.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_aliases_and_modifier_order() {
+        let cases = [
+            ("ctrl+shift+t", "Ctrl+Shift+T"),
+            ("Shift + Control + t", "Ctrl+Shift+T"),
+            ("cmd+option+esc", "Alt+Super+Escape"),
+            ("win+Return", "Super+Enter"),
+            ("f12", "F12"),
+            ("Ctrl+-", "Ctrl+Minus"),
+            ("ctrl+\\", "Ctrl+Backslash"),
+            ("PgDn", "PageDown"),
+            ("7", "7"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_chords() {
+        let cases = [
+            ("", "无效的组合键"),
+            ("Ctrl+", "无效的组合键"),
+            ("Ctrl++T", "无效的组合键"),
+            ("Ctrl", "组合键缺少主键"),
+            ("Ctrl+ctrl+T", "组合键中有重复的修饰键"),
+            ("A+B", "组合键只能包含一个主键"),
+            ("F25", "未知的按键"),
+            ("Ctrl+Hyper", "未知的按键"),
+        ];
+        for (input, expected) in cases {
+            let error = normalize(input).unwrap_err();
+            assert!(error.starts_with(expected), "{input}: {error}");
+        }
+    }
+}
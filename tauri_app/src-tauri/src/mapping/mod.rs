@@ -0,0 +1,324 @@
+//! 手势动作绑定
+//!
+//! 每个手势配置的绑定保存在设置的 `host.mappings` 中，默认值来自内置配置（见 [`profiles`]）。
+//! 一条绑定指定手势、触发时机（enter / hold / exit / slide）与动作（[`Action`]）：
+//! 注入输入的动作通过 `set_action_map` 交给后端执行，其余由宿主执行（见 [`host`])
+//!
+//! [`profiles`]: crate::profiles
+
+mod host;
+mod keys;
+
+use std::collections::BTreeMap;
+
+use phantom_protocol::{Action, ActionBinding, ActionMap, GestureEventType};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::profiles::{self, GestureProfile, BUILTIN_PROFILES};
+use crate::settings::Settings;
+use crate::AppState;
+pub use host::on_gesture_event;
+
+/// 可以绑定动作的手势
+const BINDABLE_GESTURES: &[&str] = &["point", "pinch", "thumbs_up", "victory", "ok"];
+
+/// 固定用于激活/停用控制的手势（手势配置可以通过 `rebinds` 放开其中的停用手势）
+const RESERVED_GESTURES: &[&str] = &["open", "fist"];
+
+/// 停用控制的手势
+pub const DEACTIVATE_GESTURE: &str = "fist";
+
+/// 滑动方向
+const SLIDE_DIRECTIONS: &[&str] = &["left", "right", "up", "down"];
+
+/// 每个配置最多的绑定数
+const MAX_BINDINGS: usize = 64;
+
+/// hold 重复间隔范围（毫秒）
+const INTERVAL_RANGE: (u32, u32) = (100, 10_000);
+
+/// 文本与命令的最大长度（字符）
+const MAX_TEXT_LEN: usize = 4096;
+
+/// 链接允许的协议
+const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// 手势配置 -> 绑定
+pub type ProfileMappings = BTreeMap<String, Vec<ActionBinding>>;
+
+/// 所有内置配置的默认绑定
+pub fn default_mappings() -> ProfileMappings {
+    BUILTIN_PROFILES
+        .iter()
+        .map(|profile| (profile.id.to_string(), profile.default_bindings()))
+        .collect()
+}
+
+/// 手势配置当前的绑定
+pub fn bindings<'a>(settings: &'a Settings, profile: &str) -> &'a [ActionBinding] {
+    settings
+        .host
+        .mappings
+        .get(profile)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// 发送给后端的映射（只包含由后端执行的动作，组合键统一为规范形式）
+pub fn action_map(bindings: &[ActionBinding]) -> ActionMap {
+    let bindings = bindings
+        .iter()
+        .filter(|binding| !binding.action.is_host())
+        .cloned()
+        .map(|mut binding| {
+            if let Action::KeyChord { keys } = &mut binding.action {
+                if let Ok(normalized) = keys::normalize(keys) {
+                    *keys = normalized;
+                }
+            }
+            binding
+        })
+        .collect();
+    ActionMap { bindings }
+}
+
+/// 把手势配置的绑定推送给后端（未连接时在连接后推送）
+pub fn push(app_handle: &AppHandle, settings: &Settings, profile: &str) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    if !state.bridge.status().connected {
+        return Ok(());
+    }
+    state
+        .bridge
+        .set_action_map(action_map(bindings(settings, profile)))
+}
+
+/// 把绑定中的组合键统一为规范形式
+pub fn normalize(bindings: &mut [ActionBinding]) -> Result<(), String> {
+    for (index, binding) in bindings.iter_mut().enumerate() {
+        if let Action::KeyChord { keys } = &mut binding.action {
+            *keys = keys::normalize(keys).map_err(|e| format!("[{}].action.keys: {}", index, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 绑定是否占用了停用手势（此时该手势执行绑定的动作而不停用控制）
+pub fn rebinds_deactivate(bindings: &[ActionBinding]) -> bool {
+    bindings
+        .iter()
+        .any(|b| b.gesture == DEACTIVATE_GESTURE && b.on != GestureEventType::Slide)
+}
+
+/// 校验所有配置的绑定，错误信息包含配置与绑定序号
+pub fn validate_mappings(mappings: &ProfileMappings) -> Result<(), String> {
+    for (id, bindings) in mappings {
+        let Some(profile) = profiles::find(id) else {
+            return Err(format!("{}: 未知的手势配置", id));
+        };
+        validate(profile, bindings).map_err(|e| format!("{}{}", id, e))?;
+    }
+    Ok(())
+}
+
+/// 校验一个配置的绑定
+pub fn validate(profile: &GestureProfile, bindings: &[ActionBinding]) -> Result<(), String> {
+    if bindings.len() > MAX_BINDINGS {
+        return Err(format!(": 绑定不能超过 {} 条", MAX_BINDINGS));
+    }
+
+    let mut pointer_gestures: Vec<&str> = Vec::new();
+    for (index, binding) in bindings.iter().enumerate() {
+        let field_error =
+            |field: &str, message: String| format!("[{}]{}: {}", index, field, message);
+        let gesture = binding.gesture.as_str();
+
+        if binding.on == GestureEventType::Slide {
+            if !SLIDE_DIRECTIONS.contains(&gesture) {
+                return Err(field_error(
+                    ".gesture",
+                    format!("滑动方向应为 {}", SLIDE_DIRECTIONS.join(" / ")),
+                ));
+            }
+        } else if RESERVED_GESTURES.contains(&gesture) {
+            if !profile.rebinds.contains(&gesture) {
+                return Err(field_error(
+                    ".gesture",
+                    format!("{} 固定用于激活/停用控制，不能绑定动作", gesture),
+                ));
+            }
+            // 后端只收到注入输入的绑定，宿主动作会让两边对是否停用控制的判断不一致
+            if binding.action.is_host() {
+                return Err(field_error(
+                    ".action",
+                    format!("{} 只能绑定注入输入的动作", gesture),
+                ));
+            }
+        } else if !BINDABLE_GESTURES.contains(&gesture) {
+            return Err(field_error(
+                ".gesture",
+                format!(
+                    "未知的手势 {}（可选 {}）",
+                    gesture,
+                    BINDABLE_GESTURES.join(" / ")
+                ),
+            ));
+        }
+
+        let action = &binding.action;
+        if action.is_pointer() {
+            if binding.on != GestureEventType::Hold {
+                return Err(field_error(
+                    ".on",
+                    "鼠标移动、点击与拖动只能绑定在 hold 上".to_string(),
+                ));
+            }
+            if pointer_gestures.contains(&gesture) {
+                return Err(field_error(
+                    ".action",
+                    format!("{} 已绑定了鼠标移动、点击或拖动", gesture),
+                ));
+            }
+            pointer_gestures.push(gesture);
+        } else if action.is_host() && binding.on == GestureEventType::Hold {
+            return Err(field_error(
+                ".on",
+                "由宿主执行的动作不能绑定在 hold 上".to_string(),
+            ));
+        }
+
+        match (binding.on, binding.interval_ms) {
+            (GestureEventType::Hold, None) if !action.is_pointer() => {
+                return Err(field_error(
+                    ".interval_ms",
+                    "hold 上的一次性动作需要重复间隔".to_string(),
+                ));
+            }
+            (GestureEventType::Hold, Some(interval)) if !action.is_pointer() => {
+                let (min, max) = INTERVAL_RANGE;
+                if !(min..=max).contains(&interval) {
+                    return Err(field_error(
+                        ".interval_ms",
+                        format!("应在 {} 到 {} 之间（当前为 {}）", min, max, interval),
+                    ));
+                }
+            }
+            (_, Some(_)) => {
+                return Err(field_error(
+                    ".interval_ms",
+                    "只用于 hold 上的一次性动作".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        validate_action(action)
+            .map_err(|(field, message)| field_error(&format!(".action{}", field), message))?;
+    }
+    Ok(())
+}
+
+/// 校验动作参数，错误时返回字段与原因
+fn validate_action(action: &Action) -> Result<(), (&'static str, String)> {
+    let not_empty = |field: &'static str, value: &str| {
+        if value.trim().is_empty() {
+            Err((field, "不能为空".to_string()))
+        } else if value.chars().count() > MAX_TEXT_LEN {
+            Err((field, format!("不能超过 {} 个字符", MAX_TEXT_LEN)))
+        } else {
+            Ok(())
+        }
+    };
+
+    match action {
+        Action::MouseScroll { amount } if *amount == 0 || amount.abs() > 20 => {
+            return Err((
+                ".amount",
+                format!("应在 -20 到 20 之间且不为 0（当前为 {}）", amount),
+            ));
+        }
+        Action::KeyChord { keys } => {
+            keys::normalize(keys).map_err(|e| (".keys", e))?;
+        }
+        Action::Text { text } if text.is_empty() || text.chars().count() > MAX_TEXT_LEN => {
+            return Err((".text", format!("长度应在 1 到 {} 之间", MAX_TEXT_LEN)));
+        }
+        Action::Launch { program, .. } => not_empty(".program", program)?,
+        Action::OpenUrl { url } => {
+            let parsed = Url::parse(url).map_err(|e| (".url", format!("无效的链接: {}", e)))?;
+            if !URL_SCHEMES.contains(&parsed.scheme()) {
+                return Err((".url", format!("只支持 {} 链接", URL_SCHEMES.join(" / "))));
+            }
+        }
+        Action::Shell { command } => not_empty(".command", command)?,
+        Action::Event { name } => {
+            let valid = !name.is_empty()
+                && name.len() <= 64
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.'));
+            if !valid {
+                return Err((
+                    ".name",
+                    "只能包含字母、数字与 _ - : .，最长 64 个字符".to_string(),
+                ));
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(gesture: &str, on: GestureEventType, action: Action) -> ActionBinding {
+        ActionBinding {
+            gesture: gesture.to_string(),
+            on,
+            action,
+            interval_ms: None,
+        }
+    }
+
+    #[test]
+    fn default_mappings_are_valid() {
+        validate_mappings(&default_mappings()).unwrap();
+    }
+
+    #[test]
+    fn only_media_profile_rebinds_fist() {
+        let mappings = default_mappings();
+        assert!(rebinds_deactivate(&mappings["media"]));
+        for profile in ["default", "presentation", "pointer"] {
+            assert!(!rebinds_deactivate(&mappings[profile]), "{profile}");
+        }
+    }
+
+    #[test]
+    fn reserved_gestures_need_profile_exception() {
+        let default = profiles::find(profiles::DEFAULT_PROFILE).unwrap();
+        let media = profiles::find("media").unwrap();
+        let play = binding("fist", GestureEventType::Enter, Action::MediaPlayPause);
+        let open = binding("open", GestureEventType::Enter, Action::MediaPlayPause);
+        let url = binding(
+            "fist",
+            GestureEventType::Enter,
+            Action::OpenUrl {
+                url: "https://example.com".to_string(),
+            },
+        );
+
+        validate(media, std::slice::from_ref(&play)).unwrap();
+        let cases = [
+            (default, play, "[0].gesture"),
+            (media, open, "[0].gesture"),
+            (media, url, "[0].action"),
+        ];
+        for (profile, binding, expected) in cases {
+            let error = validate(profile, &[binding]).unwrap_err();
+            assert!(error.starts_with(expected), "{}: {error}", profile.id);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! 运行指标
+//!
+//! 汇总后端 `[STATS]` 输出、逐帧推理耗时与进程资源占用，每秒通过 `metrics` 事件推送给前端，
+//! 便于调参时对照帧率、推理延迟和资源占用
+
+mod process;
+mod stats;
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::backend::{unix_millis, BackendState};
+use crate::AppState;
+use process::{ProcessSampler, ProcessStats};
+use stats::{parse_stats_line, StatsLine};
+
+/// 采样与推送间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 推理延迟统计窗口
+const LATENCY_WINDOW: Duration = Duration::from_secs(60);
+
+/// 超过该时间没有新的 `[STATS]` 输出时，视为没有在处理帧
+const STATS_STALE_AFTER: Duration = Duration::from_secs(3);
+
+/// 推理延迟分布（毫秒）
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    /// 窗口内样本数
+    pub samples: usize,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// 指标快照
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Metrics {
+    /// 采样时间（Unix 毫秒）
+    pub timestamp: u64,
+    /// 最近一个统计周期的帧率
+    pub fps: f64,
+    /// 后端启动以来的平均帧率
+    pub avg_fps: f64,
+    /// 后端启动以来处理的帧数
+    pub frame_count: u64,
+    /// 后端启动以来丢弃的帧数
+    pub dropped_frames: u64,
+    /// WebSocket 客户端数
+    pub clients: u32,
+    /// 最近一段时间的推理延迟
+    pub inference: Option<LatencyStats>,
+    /// 后端进程资源占用（仅 Linux）
+    pub process: Option<ProcessStats>,
+}
+
+struct Inner {
+    last_stats: Option<(StatsLine, Instant)>,
+    fps: f64,
+    latencies: VecDeque<(Instant, f64)>,
+    sampler: ProcessSampler,
+    latest: Metrics,
+}
+
+/// 指标收集器
+pub struct MetricsCollector {
+    inner: Mutex<Inner>,
+}
+
+impl MetricsCollector {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                last_stats: None,
+                fps: 0.0,
+                latencies: VecDeque::new(),
+                sampler: ProcessSampler::new(),
+                latest: Metrics::default(),
+            }),
+        }
+    }
+
+    /// 处理一行后端 stdout，不是 `[STATS]` 行时忽略
+    pub fn on_stdout(&self, line: &str) {
+        let Some(stats) = parse_stats_line(line) else {
+            return;
+        };
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+
+        // 帧数回退说明后端已重启，重新计算
+        inner.fps = match &inner.last_stats {
+            Some((last, at)) if stats.frame_count >= last.frame_count => {
+                let elapsed = now.duration_since(*at).as_secs_f64();
+                if elapsed > 0.0 {
+                    (stats.frame_count - last.frame_count) as f64 / elapsed
+                } else {
+                    inner.fps
+                }
+            }
+            _ => stats.avg_fps,
+        };
+
+        inner.last_stats = Some((stats, now));
+    }
+
+    /// 记录一帧的推理耗时（来自 `frame_data`）
+    pub fn record_inference(&self, ms: f64) {
+        self.inner
+            .lock()
+            .unwrap()
+            .latencies
+            .push_back((Instant::now(), ms));
+    }
+
+    /// 最近一次快照
+    pub fn latest(&self) -> Metrics {
+        self.inner.lock().unwrap().latest.clone()
+    }
+
+    /// 生成快照；`pid` 为运行中的后端进程
+    fn sample(&self, pid: Option<u32>) -> Metrics {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+
+        while let Some((at, _)) = inner.latencies.front() {
+            if now.duration_since(*at) <= LATENCY_WINDOW {
+                break;
+            }
+            inner.latencies.pop_front();
+        }
+
+        let mut metrics = Metrics {
+            timestamp: unix_millis(),
+            inference: latency_stats(&inner.latencies),
+            process: pid.and_then(|pid| inner.sampler.sample(pid)),
+            ..Metrics::default()
+        };
+        if let Some((stats, at)) = &inner.last_stats {
+            let fresh = pid.is_some() && now.duration_since(*at) <= STATS_STALE_AFTER;
+            metrics.fps = if fresh { inner.fps } else { 0.0 };
+            metrics.avg_fps = stats.avg_fps;
+            metrics.frame_count = stats.frame_count;
+            metrics.dropped_frames = stats.dropped_frames.unwrap_or(0);
+            metrics.clients = if fresh { stats.clients } else { 0 };
+        }
+
+        inner.latest = metrics.clone();
+        metrics
+    }
+}
+
+/// 启动后台采样任务
+pub fn spawn_sampler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+            let status = state.backend.status();
+            let pid = match status.state {
+                BackendState::Starting | BackendState::Running | BackendState::Ready => status.pid,
+                BackendState::Crashed | BackendState::Stopped => None,
+            };
+            let metrics = state.metrics.sample(pid);
+            let _ = app_handle.emit_all("metrics", metrics);
+        }
+    });
+}
+
+fn latency_stats(latencies: &VecDeque<(Instant, f64)>) -> Option<LatencyStats> {
+    if latencies.is_empty() {
+        return None;
+    }
+
+    let mut values: Vec<f64> = latencies.iter().map(|(_, ms)| *ms).collect();
+    values.sort_by(f64::total_cmp);
+    // 最近秩法
+    let percentile = |p: f64| {
+        let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+        values[rank.clamp(1, values.len()) - 1]
+    };
+
+    Some(LatencyStats {
+        samples: values.len(),
+        p50: percentile(50.0),
+        p95: percentile(95.0),
+        p99: percentile(99.0),
+        max: values[values.len() - 1],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以相同时间戳组成的延迟窗口
+    fn window(values: impl IntoIterator<Item = f64>) -> VecDeque<(Instant, f64)> {
+        let now = Instant::now();
+        values.into_iter().map(|ms| (now, ms)).collect()
+    }
+
+    #[test]
+    fn empty_window_has_no_latency() {
+        assert!(latency_stats(&VecDeque::new()).is_none());
+    }
+
+    #[test]
+    fn single_sample_is_every_percentile() {
+        let stats = latency_stats(&window([12.5])).unwrap();
+        assert_eq!(stats.samples, 1);
+        assert_eq!(
+            (stats.p50, stats.p95, stats.p99, stats.max),
+            (12.5, 12.5, 12.5, 12.5)
+        );
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        // 1..=100 打乱顺序
+        let values = (1..=100).map(|i| f64::from((i * 37) % 100 + 1));
+        let stats = latency_stats(&window(values)).unwrap();
+        assert_eq!(stats.samples, 100);
+        assert_eq!(
+            (stats.p50, stats.p95, stats.p99, stats.max),
+            (50.0, 95.0, 99.0, 100.0)
+        );
+
+        let stats = latency_stats(&window([4.0, 1.0, 3.0, 2.0])).unwrap();
+        assert_eq!((stats.p50, stats.p95, stats.max), (2.0, 4.0, 4.0));
+    }
+
+    #[test]
+    fn sample_drops_latencies_outside_window() {
+        let collector = MetricsCollector::new();
+        collector.record_inference(8.0);
+        {
+            let mut inner = collector.inner.lock().unwrap();
+            let expired = Instant::now() - LATENCY_WINDOW - Duration::from_secs(1);
+            inner.latencies.push_front((expired, 500.0));
+        }
+        let inference = collector.sample(None).inference.unwrap();
+        assert_eq!((inference.samples, inference.max), (1, 8.0));
+    }
+
+    #[test]
+    fn stats_lines_update_snapshot() {
+        let collector = MetricsCollector::new();
+        collector.on_stdout("[STATS] 帧数: 300, FPS: 29.5, 客户端: 1, 丢帧: 3, 推理: 9.0ms");
+        collector.on_stdout("[ACTION] 左键单击");
+
+        let metrics = collector.sample(Some(std::process::id()));
+        assert_eq!(metrics.frame_count, 300);
+        assert_eq!(metrics.dropped_frames, 3);
+        assert_eq!(
+            (metrics.fps, metrics.avg_fps, metrics.clients),
+            (29.5, 29.5, 1)
+        );
+
+        // 后端未运行时不报告实时帧率与客户端数
+        let metrics = collector.sample(None);
+        assert_eq!((metrics.fps, metrics.clients), (0.0, 0));
+        assert_eq!(metrics.frame_count, 300);
+    }
+}
@@ -0,0 +1,110 @@
+//! 后端进程资源占用
+//!
+//! 从 /proc 读取 CPU 时间与常驻内存。PyInstaller 单文件模式下 sidecar 是引导进程，
+//! 真正的 Python 进程是它的子进程，因此统计整个进程树
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// 进程树资源占用
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStats {
+    /// sidecar 进程 PID
+    pub pid: u32,
+    /// 进程树中的进程数
+    pub processes: usize,
+    /// CPU 占用（百分比，多核可超过 100）
+    pub cpu_percent: f64,
+    /// 常驻内存（字节）
+    pub rss_bytes: u64,
+}
+
+/// 一次读取的原始数据
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct TreeUsage {
+    processes: usize,
+    /// 累计 CPU 时间（秒）
+    cpu_seconds: f64,
+    rss_bytes: u64,
+}
+
+/// 按两次采样之差计算 CPU 占用
+pub struct ProcessSampler {
+    last: Option<(u32, f64, Instant)>,
+}
+
+impl ProcessSampler {
+    pub fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// 采样指定进程树；首次采样（或 PID 变化后）CPU 占用为 0
+    pub fn sample(&mut self, pid: u32) -> Option<ProcessStats> {
+        let Some(usage) = read_tree(pid) else {
+            self.last = None;
+            return None;
+        };
+        let now = Instant::now();
+
+        let cpu_percent = match self.last {
+            Some((last_pid, last_cpu, last_at)) if last_pid == pid => {
+                let elapsed = now.duration_since(last_at).as_secs_f64();
+                if elapsed > 0.0 {
+                    ((usage.cpu_seconds - last_cpu) / elapsed * 100.0).max(0.0)
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        self.last = Some((pid, usage.cpu_seconds, now));
+
+        Some(ProcessStats {
+            pid,
+            processes: usage.processes,
+            cpu_percent,
+            rss_bytes: usage.rss_bytes,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_tree(root: u32) -> Option<TreeUsage> {
+    use crate::backend::procfs;
+
+    // USER_HZ 在 Linux 各架构上均为 100
+    const CLOCK_TICKS: f64 = 100.0;
+
+    fn read_rss(pid: u32) -> u64 {
+        std::fs::read_to_string(format!("/proc/{}/status", pid))
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("VmRSS:"))
+                    .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+            })
+            .map(|kb: u64| kb * 1024)
+            .unwrap_or(0)
+    }
+
+    let tree = procfs::process_tree(root);
+    if tree.is_empty() {
+        return None;
+    }
+    Some(TreeUsage {
+        processes: tree.len(),
+        cpu_seconds: tree
+            .iter()
+            .map(|(_, stat)| stat.cpu_ticks as f64 / CLOCK_TICKS)
+            .sum(),
+        rss_bytes: tree.iter().map(|&(pid, _)| read_rss(pid)).sum(),
+    })
+}
+
+/// 其他平台暂不支持
+#[cfg(not(target_os = "linux"))]
+fn read_tree(_root: u32) -> Option<TreeUsage> {
+    None
+}
@@ -0,0 +1,116 @@
+//! `[STATS]` 行解析
+//!
+//! 后端每秒输出一行：`[STATS] 帧数: N, FPS: x, 客户端: k, 丢帧: d, 推理: t.tms`，
+//! 旧版本后端没有丢帧字段；推理耗时改由 `frame_data` 逐帧统计
+
+/// 一行统计输出
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsLine {
+    /// 后端启动以来处理的帧数
+    pub frame_count: u64,
+    /// 后端启动以来的平均帧率
+    pub avg_fps: f64,
+    /// WebSocket 客户端数
+    pub clients: u32,
+    /// 后端启动以来丢弃的帧数
+    pub dropped_frames: Option<u64>,
+}
+
+/// 解析 `[STATS]` 行，不是统计行或缺少必需字段时返回 `None`
+pub fn parse_stats_line(line: &str) -> Option<StatsLine> {
+    let body = line.trim().strip_prefix("[STATS]")?;
+
+    let mut frame_count = None;
+    let mut avg_fps = None;
+    let mut clients = None;
+    let mut dropped_frames = None;
+
+    for field in body.split(',') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "帧数" => frame_count = value.parse().ok(),
+            "FPS" => avg_fps = value.parse().ok(),
+            "客户端" => clients = value.parse().ok(),
+            "丢帧" => dropped_frames = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(StatsLine {
+        frame_count: frame_count?,
+        avg_fps: avg_fps?,
+        clients: clients?,
+        dropped_frames,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backend_stats_lines() {
+        let cases = [
+            // 当前后端的格式（server.py）
+            (
+                "[STATS] 帧数: 1800, FPS: 29.9, 客户端: 2, 丢帧: 14, 推理: 12.3ms",
+                StatsLine {
+                    frame_count: 1800,
+                    avg_fps: 29.9,
+                    clients: 2,
+                    dropped_frames: Some(14),
+                },
+            ),
+            // 旧版本后端没有丢帧字段
+            (
+                "[STATS] 帧数: 30, FPS: 30.0, 客户端: 1",
+                StatsLine {
+                    frame_count: 30,
+                    avg_fps: 30.0,
+                    clients: 1,
+                    dropped_frames: None,
+                },
+            ),
+            // 字段顺序与多余空白不影响解析
+            (
+                "  [STATS]客户端:0 ,FPS:0.0, 帧数:0, 未知: x  ",
+                StatsLine {
+                    frame_count: 0,
+                    avg_fps: 0.0,
+                    clients: 0,
+                    dropped_frames: None,
+                },
+            ),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_stats_line(line), Some(expected), "{line}");
+        }
+    }
+
+    #[test]
+    fn ignores_malformed_lines() {
+        let cases = [
+            "",
+            "[SERVER] 帧数: 1, FPS: 1.0, 客户端: 1",
+            "帧数: 1, FPS: 1.0, 客户端: 1",
+            "[STATS]",
+            "[STATS] 帧数: 1, FPS: 1.0",
+            "[STATS] 帧数: -1, FPS: 1.0, 客户端: 1",
+            "[STATS] 帧数: 1, FPS: fast, 客户端: 1",
+            "[STATS] 帧数 1, FPS 1.0, 客户端 1",
+        ];
+        for line in cases {
+            assert_eq!(parse_stats_line(line), None, "{line}");
+        }
+    }
+
+    #[test]
+    fn invalid_dropped_frames_are_optional() {
+        let stats = parse_stats_line("[STATS] 帧数: 5, FPS: 5.0, 客户端: 1, 丢帧: ?").unwrap();
+        assert_eq!(stats.dropped_frames, None);
+        assert_eq!(stats.frame_count, 5);
+    }
+}
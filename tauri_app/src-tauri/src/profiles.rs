@@ -0,0 +1,117 @@
+//! 手势配置
+//!
+//! 一个配置即一套手势与滑动方向到动作的绑定，切换后推送给后端。
+//! 这里只定义默认绑定，用户修改后的绑定保存在设置中（见 [`crate::mapping`]）
+
+use phantom_protocol::{Action, ActionBinding, GestureEventType};
+use serde::Serialize;
+
+/// 默认配置
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 内置手势配置
+#[derive(Debug, Serialize)]
+pub struct GestureProfile {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// 手势 -> 动作（鼠标动作绑定在 hold 上，其余在 enter 上）
+    #[serde(skip)]
+    gestures: &'static [(&'static str, Action)],
+    /// 滑动方向 -> 动作
+    #[serde(skip)]
+    slides: &'static [(&'static str, Action)],
+    /// 可以在本配置中绑定动作的激活/停用手势（如视频播放器中 fist 播放/暂停），
+    /// 绑定后该手势不再停用控制，只能通过托盘或快捷键停用
+    #[serde(skip)]
+    pub rebinds: &'static [&'static str],
+}
+
+impl GestureProfile {
+    /// 默认绑定
+    pub fn default_bindings(&self) -> Vec<ActionBinding> {
+        let gestures = self.gestures.iter().map(|(gesture, action)| {
+            let on = if action.is_pointer() {
+                GestureEventType::Hold
+            } else {
+                GestureEventType::Enter
+            };
+            (gesture, on, action)
+        });
+        let slides = self
+            .slides
+            .iter()
+            .map(|(direction, action)| (direction, GestureEventType::Slide, action));
+        gestures
+            .chain(slides)
+            .map(|(gesture, on, action)| ActionBinding {
+                gesture: gesture.to_string(),
+                on,
+                action: action.clone(),
+                interval_ms: None,
+            })
+            .collect()
+    }
+}
+
+/// 所有内置配置（托盘子菜单按此顺序显示）
+pub const BUILTIN_PROFILES: &[GestureProfile] = &[
+    GestureProfile {
+        id: DEFAULT_PROFILE,
+        name: "默认",
+        gestures: &[
+            ("thumbs_up", Action::MouseClick),
+            ("point", Action::MouseMove),
+            ("victory", Action::Screenshot),
+            ("ok", Action::VolumeMute),
+        ],
+        slides: &[
+            ("left", Action::SwitchWindow),
+            ("right", Action::SwitchWindow),
+            ("up", Action::VolumeUp),
+            ("down", Action::VolumeDown),
+        ],
+        rebinds: &[],
+    },
+    GestureProfile {
+        id: "media",
+        name: "媒体播放",
+        gestures: &[
+            ("point", Action::MouseMove),
+            ("thumbs_up", Action::MouseClick),
+            ("fist", Action::MediaPlayPause),
+            ("ok", Action::VolumeMute),
+        ],
+        slides: &[
+            ("left", Action::MediaPrev),
+            ("right", Action::MediaNext),
+            ("up", Action::VolumeUp),
+            ("down", Action::VolumeDown),
+        ],
+        rebinds: &["fist"],
+    },
+    GestureProfile {
+        id: "presentation",
+        name: "演示文稿",
+        gestures: &[
+            ("point", Action::MouseMove),
+            ("thumbs_up", Action::MouseClick),
+        ],
+        slides: &[("left", Action::SlidePrev), ("right", Action::SlideNext)],
+        rebinds: &[],
+    },
+    GestureProfile {
+        id: "pointer",
+        name: "仅鼠标",
+        gestures: &[
+            ("point", Action::MouseMove),
+            ("thumbs_up", Action::MouseClick),
+        ],
+        slides: &[],
+        rebinds: &[],
+    },
+];
+
+/// 按 ID 查找配置
+pub fn find(id: &str) -> Option<&'static GestureProfile> {
+    BUILTIN_PROFILES.iter().find(|profile| profile.id == id)
+}
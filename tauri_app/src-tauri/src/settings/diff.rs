@@ -0,0 +1,159 @@
+//! 设置差异
+//!
+//! 用于把修改推送给运行中的后端，以及在导入前预览变化
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::merge::FREE_MAPS;
+
+/// 一处变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// 字段路径（如 `gesture.pinch_distance_ratio`）
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// 比较两个文档，按字段路径列出变化（自由映射整体比较）
+pub fn diff(before: &Value, after: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_at(before, after, &mut Vec::new(), &mut changes);
+    changes
+}
+
+fn diff_at<'a>(
+    before: &Value,
+    after: &'a Value,
+    path: &mut Vec<&'a str>,
+    changes: &mut Vec<Change>,
+) {
+    let joined = path.join(".");
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) if !FREE_MAPS.contains(&joined.as_str()) => {
+            for (key, value) in new {
+                path.push(key);
+                diff_at(old.get(key).unwrap_or(&Value::Null), value, path, changes);
+                path.pop();
+            }
+        }
+        (old, new) if old != new => changes.push(Change {
+            path: joined,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// 把变化还原为部分文档
+pub fn to_patch<'a>(changes: impl IntoIterator<Item = &'a Change>) -> Map<String, Value> {
+    let mut patch = Map::new();
+    for change in changes {
+        let mut target = &mut patch;
+        let mut segments = change.path.split('.').peekable();
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                target.insert(segment.to_string(), change.new.clone());
+                break;
+            }
+            let slot = target
+                .entry(segment.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let Value::Object(next) = slot else {
+                break;
+            };
+            target = next;
+        }
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::merge::merge;
+    use serde_json::json;
+
+    fn change(path: &str, old: Value, new: Value) -> Change {
+        Change {
+            path: path.to_string(),
+            old,
+            new,
+        }
+    }
+
+    #[test]
+    fn lists_changed_leaves_by_path() {
+        let before = json!({
+            "gesture": { "pinch_distance_ratio": 0.25, "fist_tip_wrist_ratio": 0.6 },
+            "camera": { "fps": 30, "mirror": true },
+            "host": { "shortcuts": ["a", "b"] },
+        });
+        let after = json!({
+            "gesture": { "pinch_distance_ratio": 0.3, "fist_tip_wrist_ratio": 0.6 },
+            "camera": { "fps": 30, "mirror": false },
+            "host": { "shortcuts": ["a", "c"], "profile": "media" },
+        });
+        assert_eq!(
+            diff(&before, &after),
+            [
+                change("gesture.pinch_distance_ratio", json!(0.25), json!(0.3)),
+                change("camera.mirror", json!(true), json!(false)),
+                // 数组整体比较，新增的字段旧值为 null
+                change("host.shortcuts", json!(["a", "b"]), json!(["a", "c"])),
+                change("host.profile", Value::Null, json!("media")),
+            ]
+        );
+        assert!(diff(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn free_maps_are_compared_whole() {
+        let before = json!({ "state_machine": { "gesture_priority": { "open": 6, "fist": 5 } } });
+        let after = json!({ "state_machine": { "gesture_priority": { "open": 6, "ok": 3 } } });
+        assert_eq!(
+            diff(&before, &after),
+            [change(
+                "state_machine.gesture_priority",
+                json!({ "open": 6, "fist": 5 }),
+                json!({ "open": 6, "ok": 3 }),
+            )]
+        );
+    }
+
+    #[test]
+    fn patch_rebuilds_nested_paths() {
+        let changes = [
+            change("gesture.pinch_distance_ratio", json!(0.25), json!(0.3)),
+            change("state_machine.t_enter", json!(120), json!(150)),
+            change("gesture.slide_min_distance", json!(0.1), json!(0.2)),
+            change(
+                "state_machine.gesture_priority",
+                json!({ "open": 6 }),
+                json!({ "ok": 3 }),
+            ),
+            change("action", Value::Null, json!({ "mouse_sensitivity": 2.0 })),
+        ];
+        assert_eq!(
+            Value::Object(to_patch(&changes)),
+            json!({
+                "gesture": { "pinch_distance_ratio": 0.3, "slide_min_distance": 0.2 },
+                "state_machine": { "t_enter": 150, "gesture_priority": { "ok": 3 } },
+                "action": { "mouse_sensitivity": 2.0 },
+            })
+        );
+        assert!(to_patch(&[]).is_empty());
+    }
+
+    #[test]
+    fn patch_applies_diff_to_before() {
+        let before = json!({ "a": { "b": 1, "c": { "d": true } }, "e": "x" });
+        let after = json!({ "a": { "b": 2, "c": { "d": false } }, "e": "x" });
+        let mut patched = before.clone();
+        let patch = Value::Object(to_patch(&diff(&before, &after)));
+        merge(&mut patched, patch, true).unwrap();
+        assert_eq!(patched, after);
+    }
+}
@@ -0,0 +1,182 @@
+//! 部分文档合并
+//!
+//! `update_settings` 与读取设置文件都以“在完整文档上覆盖部分字段”的方式进行，
+//! 合并时按字段路径检查未知字段与类型，错误信息可直接定位到出错的字段
+
+use serde_json::Value;
+
+/// 整体替换而不逐项合并的映射（键由用户决定）
+pub(super) const FREE_MAPS: &[&str] = &["state_machine.gesture_priority"];
+
+/// 把 `patch` 合并进 `target`
+///
+/// `strict` 为真时遇到未知字段或类型不符立即返回错误；
+/// 否则跳过这些字段，并返回被跳过字段的说明
+pub fn merge(target: &mut Value, patch: Value, strict: bool) -> Result<Vec<String>, String> {
+    let mut skipped = Vec::new();
+    merge_at(target, patch, &mut Vec::new(), strict, &mut skipped)?;
+    Ok(skipped)
+}
+
+fn merge_at(
+    target: &mut Value,
+    patch: Value,
+    path: &mut Vec<String>,
+    strict: bool,
+    skipped: &mut Vec<String>,
+) -> Result<(), String> {
+    let joined = path.join(".");
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) if !FREE_MAPS.contains(&joined.as_str()) => {
+            for (key, value) in patch {
+                let slot = target.get_mut(&key);
+                path.push(key);
+                match slot {
+                    Some(slot) => merge_at(slot, value, path, strict, skipped)?,
+                    None => reject(format!("未知的设置项: {}", path.join(".")), strict, skipped)?,
+                }
+                path.pop();
+            }
+            Ok(())
+        }
+        (target, patch) => match type_error(target, &patch) {
+            Some(expected) => reject(
+                format!("{} 应为{}", display_path(&joined), expected),
+                strict,
+                skipped,
+            ),
+            None => {
+                *target = patch;
+                Ok(())
+            }
+        },
+    }
+}
+
+/// 严格模式下返回错误，否则记录后跳过
+fn reject(message: String, strict: bool, skipped: &mut Vec<String>) -> Result<(), String> {
+    if strict {
+        return Err(message);
+    }
+    skipped.push(message);
+    Ok(())
+}
+
+/// 检查新值与原值类型是否兼容，不兼容时返回期望的类型说明
+fn type_error(current: &Value, new: &Value) -> Option<&'static str> {
+    // 可为空的字段（如禁用的快捷键）交给反序列化检查
+    if current.is_null() || new.is_null() {
+        return None;
+    }
+    match current {
+        Value::Bool(_) if !new.is_boolean() => Some("布尔值"),
+        Value::String(_) if !new.is_string() => Some("字符串"),
+        Value::Number(n) if n.is_u64() && !new.is_u64() => Some("非负整数"),
+        Value::Number(_) if !new.is_number() => Some("数字"),
+        Value::Array(_) if !new.is_array() => Some("数组"),
+        Value::Object(_) if !new.is_object() => Some("对象"),
+        _ => None,
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "设置"
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn base() -> Value {
+        json!({
+            "gesture": { "pinch": 0.25, "enabled": true },
+            "server": { "port": 8765, "name": "local" },
+            "shortcut": null,
+            "list": [1, 2, 3],
+            "state_machine": { "gesture_priority": { "open": 6, "fist": 5 } },
+        })
+    }
+
+    #[test]
+    fn strict_merge_rejects_unknown_and_mistyped_fields() {
+        let cases = [
+            (json!({ "unknown": 1 }), "未知的设置项: unknown"),
+            (
+                json!({ "gesture": { "typo": 1 } }),
+                "未知的设置项: gesture.typo",
+            ),
+            (
+                json!({ "gesture": { "pinch": "0.3" } }),
+                "gesture.pinch 应为数字",
+            ),
+            (
+                json!({ "gesture": { "enabled": 1 } }),
+                "gesture.enabled 应为布尔值",
+            ),
+            (
+                json!({ "server": { "port": -1 } }),
+                "server.port 应为非负整数",
+            ),
+            (
+                json!({ "server": { "port": 1.5 } }),
+                "server.port 应为非负整数",
+            ),
+            (json!({ "server": 1 }), "server 应为对象"),
+            (json!({ "list": {} }), "list 应为数组"),
+            (json!([]), "设置 应为对象"),
+        ];
+        for (patch, expected) in cases {
+            let mut target = base();
+            let error = merge(&mut target, patch.clone(), true).unwrap_err();
+            assert_eq!(error, expected, "{patch}");
+        }
+    }
+
+    #[test]
+    fn strict_merge_applies_known_fields() {
+        let mut target = base();
+        let patch = json!({
+            "gesture": { "pinch": 0.3 },
+            "shortcut": "Ctrl+Alt+S",
+            "list": [4],
+        });
+        assert!(merge(&mut target, patch, true).unwrap().is_empty());
+        assert_eq!(target["gesture"], json!({ "pinch": 0.3, "enabled": true }));
+        assert_eq!(target["shortcut"], json!("Ctrl+Alt+S"));
+        // 数组整体替换
+        assert_eq!(target["list"], json!([4]));
+    }
+
+    #[test]
+    fn free_maps_are_replaced_wholesale() {
+        let mut target = base();
+        let patch = json!({ "state_machine": { "gesture_priority": { "ok": 3 } } });
+        merge(&mut target, patch, true).unwrap();
+        assert_eq!(
+            target["state_machine"]["gesture_priority"],
+            json!({ "ok": 3 })
+        );
+    }
+
+    #[test]
+    fn lenient_merge_skips_invalid_fields() {
+        let mut target = base();
+        let patch = json!({
+            "unknown": 1,
+            "gesture": { "pinch": "x", "enabled": false },
+            "server": { "port": 9000 },
+        });
+        let skipped = merge(&mut target, patch, false).unwrap();
+        assert_eq!(skipped, ["未知的设置项: unknown", "gesture.pinch 应为数字"]);
+        assert_eq!(
+            target["gesture"],
+            json!({ "pinch": 0.25, "enabled": false })
+        );
+        assert_eq!(target["server"]["port"], json!(9000));
+    }
+}
@@ -0,0 +1,108 @@
+//! 设置文档迁移
+//!
+//! 每次修改已有字段的含义或结构时递增 [`CURRENT_VERSION`]，并在 [`MIGRATIONS`]
+//! 末尾追加一个把上一版本文档转换为新版本的函数。只新增字段时不需要迁移，
+//! 缺失的字段会使用默认值
+
+use serde_json::Value;
+
+use super::schema::CURRENT_VERSION;
+
+/// 迁移函数：`MIGRATIONS[i]` 把版本 `i + 1` 的文档转换为版本 `i + 2`
+type Migration = fn(&mut Value);
+
+const MIGRATIONS: &[Migration] = &[];
+
+// 每个旧版本都必须有对应的迁移
+const _: () = assert!(MIGRATIONS.len() == CURRENT_VERSION as usize - 1);
+
+/// 把任意旧版本的文档迁移到当前版本；较新版本写入的文档返回错误
+pub fn migrate(document: &mut Value) -> Result<(), String> {
+    run(document, MIGRATIONS, CURRENT_VERSION)
+}
+
+/// 依次执行从文档版本开始的迁移，`migrations` 的长度须为 `current - 1`
+fn run(document: &mut Value, migrations: &[Migration], current: u32) -> Result<(), String> {
+    let version = match document.get("version") {
+        Some(version) => version
+            .as_u64()
+            .filter(|&version| version >= 1)
+            .ok_or_else(|| format!("无效的设置版本: {}", version))?,
+        // 没有版本号的文档视为第一版
+        None => 1,
+    };
+    if version > current as u64 {
+        return Err(format!(
+            "设置文件来自较新的版本（v{}，当前支持 v{}）",
+            version, current
+        ));
+    }
+
+    for (index, migration) in migrations.iter().enumerate().skip(version as usize - 1) {
+        migration(document);
+        println!("[Tauri] 设置已从 v{} 迁移到 v{}", index + 1, index + 2);
+    }
+    if let Some(object) = document.as_object_mut() {
+        object.insert("version".to_string(), current.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// v1 -> v2：`a` 改名为 `b`
+    fn rename(document: &mut Value) {
+        let object = document.as_object_mut().unwrap();
+        let value = object.remove("a").unwrap();
+        object.insert("b".to_string(), value);
+    }
+
+    /// v2 -> v3：`b` 翻倍
+    fn double(document: &mut Value) {
+        let b = document["b"].as_u64().unwrap();
+        document["b"] = json!(b * 2);
+    }
+
+    const CHAIN: &[Migration] = &[rename, double];
+
+    #[test]
+    fn runs_chain_from_document_version() {
+        let cases = [
+            (json!({ "a": 1 }), json!({ "version": 3, "b": 2 })),
+            (
+                json!({ "version": 1, "a": 1 }),
+                json!({ "version": 3, "b": 2 }),
+            ),
+            (
+                json!({ "version": 2, "b": 1 }),
+                json!({ "version": 3, "b": 2 }),
+            ),
+            (
+                json!({ "version": 3, "b": 1 }),
+                json!({ "version": 3, "b": 1 }),
+            ),
+        ];
+        for (mut document, expected) in cases {
+            run(&mut document, CHAIN, 3).unwrap();
+            assert_eq!(document, expected);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_or_newer_versions() {
+        for version in [json!(0), json!(-1), json!("2"), json!(1.5), json!(4)] {
+            let mut document = json!({ "version": version, "b": 1 });
+            assert!(run(&mut document, CHAIN, 3).is_err(), "version = {version}");
+        }
+    }
+
+    #[test]
+    fn current_chain_stamps_version() {
+        let mut document = json!({});
+        migrate(&mut document).unwrap();
+        assert_eq!(document["version"], json!(CURRENT_VERSION));
+    }
+}
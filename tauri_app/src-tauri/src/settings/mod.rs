@@ -0,0 +1,764 @@
+//! 持久化设置
+//!
+//! 设置文档保存在应用配置目录的 `settings.json` 中，启动时读取并迁移到当前版本。
+//! 后端相关部分在每次启动后端时写入 `backend_config.json`，通过 `--config` 传给 sidecar；
+//! 运行中修改时，识别与动作参数通过 `config_update` 立即推送给后端，
+//! 摄像头与服务参数在下次启动后端时生效。
+//!
+//! 设置可导出为 TOML 或 JSON 并在其他机器导入；导入的文档与调校预设（见 [`presets`]）
+//! 都先预览差异，应用时按字段路径严格校验
+
+mod diff;
+mod merge;
+mod migrate;
+mod presets;
+mod schema;
+mod transfer;
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use phantom_protocol::{Action, ActionBinding};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub use diff::Change;
+pub use presets::PresetInfo;
+use presets::{Tuning, BUILTIN_PRESETS};
+pub use schema::Settings;
+use schema::{CameraConfig, GestureThresholds, StateMachineConfig};
+pub use transfer::Format;
+
+/// 可在运行时推送给后端的部分
+const LIVE_SECTIONS: &[&str] = &["gesture", "state_machine", "action"];
+
+/// 修改后需要重启后端的部分
+const RESTART_SECTIONS: &[&str] = &["server", "camera"];
+
+/// 设置文件名
+const FILE_NAME: &str = "settings.json";
+
+/// 用户预设文件名
+const PRESETS_FILE_NAME: &str = "presets.json";
+
+/// 传给后端的配置文件名
+const BACKEND_FILE_NAME: &str = "backend_config.json";
+
+/// 传给后端的配置（结构与 Python `Config` 一致，端口由启动参数指定）
+#[derive(Serialize)]
+struct BackendConfig<'a> {
+    gesture: &'a GestureThresholds,
+    state_machine: &'a StateMachineConfig,
+    action: BackendAction,
+    server: BackendServer,
+    camera: &'a CameraConfig,
+}
+
+#[derive(Serialize)]
+struct BackendAction {
+    mouse_sensitivity: f64,
+    mouse_smoothing: f64,
+}
+
+#[derive(Serialize)]
+struct BackendServer {
+    heartbeat_interval: u32,
+    connection_timeout: u32,
+}
+
+/// 后端相关设置的变化
+#[derive(Debug, Default)]
+pub struct BackendChanges {
+    /// 可立即推送的部分配置（`config_update` 的内容）
+    pub live: Map<String, Value>,
+    /// 需要重启后端才能生效的字段路径
+    pub restart_required: Vec<String>,
+}
+
+/// 导入设置前的预览
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub changes: Vec<Change>,
+    /// 导入后新增的启动程序、执行命令或打开链接的绑定，导入前需要用户确认
+    pub host_actions: Vec<ImportedAction>,
+}
+
+/// 导入文档中会在本机执行的动作绑定
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportedAction {
+    /// 绑定位置，如 `host.mappings.default.3`
+    pub path: String,
+    pub binding: ActionBinding,
+}
+
+/// `settings-applied` 事件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsApplied {
+    /// `config_update` 请求 ID，未推送给后端时为空
+    pub request_id: Option<u64>,
+    /// 后端确认已生效的字段路径
+    pub applied: Vec<String>,
+    /// 需要重启后端才能生效的字段路径
+    pub restart_required: Vec<String>,
+    /// 后端未连接，修改将在连接后推送
+    pub deferred: bool,
+    /// 后端拒绝更新的原因
+    pub error: Option<String>,
+}
+
+struct Inner {
+    settings: Settings,
+    /// 用户预设（名称 -> 参数）
+    presets: BTreeMap<String, Tuning>,
+    /// 配置目录，未初始化时为空（只保存在内存中）
+    dir: Option<PathBuf>,
+}
+
+/// 设置存储
+pub struct SettingsStore {
+    inner: Mutex<Inner>,
+}
+
+impl SettingsStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                settings: Settings::default(),
+                presets: BTreeMap::new(),
+                dir: None,
+            }),
+        }
+    }
+
+    /// 读取设置文件（启动时调用）；文件无法使用时备份后改用默认设置
+    pub fn init(&self, dir: &Path) {
+        let path = dir.join(FILE_NAME);
+        let settings = match fs::read_to_string(&path) {
+            Ok(text) => match load(&text) {
+                Ok(settings) => {
+                    println!("[Tauri] 已读取设置: {}", path.display());
+                    settings
+                }
+                Err(e) => {
+                    eprintln!("[Tauri] 设置文件无效，已改用默认设置: {}", e);
+                    let backup = path.with_extension("invalid.json");
+                    if let Err(e) = fs::rename(&path, &backup) {
+                        eprintln!("[Tauri] 无法备份设置文件: {}", e);
+                    }
+                    Settings::default()
+                }
+            },
+            Err(_) => Settings::default(),
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.settings = settings;
+        inner.presets = load_presets(&dir.join(PRESETS_FILE_NAME));
+        inner.dir = Some(dir.to_path_buf());
+        if let Err(e) = save(&inner) {
+            eprintln!("[Tauri] {}", e);
+        }
+    }
+
+    /// 当前设置
+    pub fn get(&self) -> Settings {
+        self.inner.lock().unwrap().settings.clone()
+    }
+
+    /// 读取部分设置
+    pub fn read<T>(&self, f: impl FnOnce(&Settings) -> T) -> T {
+        f(&self.inner.lock().unwrap().settings)
+    }
+
+    /// 按部分文档更新（只能包含已有字段），校验通过后保存，返回更新后的设置
+    pub fn update(&self, patch: Value) -> Result<Settings, String> {
+        if patch.get("version").is_some() {
+            return Err("version 不能修改".to_string());
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let mut document = serde_json::to_value(&inner.settings).map_err(|e| e.to_string())?;
+        merge::merge(&mut document, patch, true)?;
+        let settings = from_document(document)?;
+        replace(&mut inner, settings)
+    }
+
+    /// 在宿主内部修改设置（如托盘切换手势配置）
+    pub fn modify(&self, f: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut settings = inner.settings.clone();
+        f(&mut settings);
+        replace(&mut inner, settings)
+    }
+
+    /// 恢复默认设置
+    pub fn reset(&self) -> Result<Settings, String> {
+        let mut inner = self.inner.lock().unwrap();
+        replace(&mut inner, Settings::default())
+    }
+
+    /// 导出完整设置
+    pub fn export(&self, format: Format) -> Result<String, String> {
+        transfer::export(&self.inner.lock().unwrap().settings, format)
+    }
+
+    /// 预览导入文档带来的变化（不保存），并标出其中会在本机执行的动作
+    pub fn preview_import(&self, text: &str, format: Format) -> Result<ImportPreview, String> {
+        let inner = self.inner.lock().unwrap();
+        let imported = import_document(&inner.settings, transfer::parse(text, format)?)?;
+        Ok(ImportPreview {
+            changes: changes(&inner.settings, &imported)?,
+            host_actions: imported_host_actions(&inner.settings, &imported),
+        })
+    }
+
+    /// 导入设置文档（可以只包含部分字段），返回更新后的设置
+    pub fn import(&self, text: &str, format: Format) -> Result<Settings, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let imported = import_document(&inner.settings, transfer::parse(text, format)?)?;
+        for action in imported_host_actions(&inner.settings, &imported) {
+            println!("[Tauri] 导入了由宿主执行的动作 {}", action.path);
+        }
+        replace(&mut inner, imported)
+    }
+
+    /// 全部预设（内置在前）
+    pub fn presets(&self) -> Vec<PresetInfo> {
+        let inner = self.inner.lock().unwrap();
+        let builtin = BUILTIN_PRESETS.iter().map(|preset| PresetInfo {
+            id: preset.id.to_string(),
+            name: preset.name.to_string(),
+            builtin: true,
+        });
+        let custom = inner.presets.keys().map(|name| PresetInfo {
+            id: name.clone(),
+            name: name.clone(),
+            builtin: false,
+        });
+        builtin.chain(custom).collect()
+    }
+
+    /// 预览应用预设带来的变化
+    pub fn preview_preset(&self, id: &str) -> Result<Vec<Change>, String> {
+        let inner = self.inner.lock().unwrap();
+        let mut settings = inner.settings.clone();
+        find_preset(&inner, id)?.apply_to(&mut settings);
+        changes(&inner.settings, &settings)
+    }
+
+    /// 应用预设，返回更新后的设置
+    pub fn apply_preset(&self, id: &str) -> Result<Settings, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut settings = inner.settings.clone();
+        find_preset(&inner, id)?.apply_to(&mut settings);
+        replace(&mut inner, settings)
+    }
+
+    /// 把当前参数保存为用户预设（同名时覆盖）
+    pub fn save_preset(&self, name: &str) -> Result<(), String> {
+        presets::validate_name(name)?;
+        let mut inner = self.inner.lock().unwrap();
+        let tuning = Tuning::of(&inner.settings);
+        let previous = inner.presets.insert(name.to_string(), tuning);
+        save_presets(&inner).inspect_err(|_| restore_preset(&mut inner, name, previous))
+    }
+
+    /// 删除用户预设
+    pub fn delete_preset(&self, name: &str) -> Result<(), String> {
+        presets::validate_name(name)?;
+        let mut inner = self.inner.lock().unwrap();
+        let previous = inner.presets.remove(name);
+        if previous.is_none() {
+            return Err(format!("未知的预设: {}", name));
+        }
+        save_presets(&inner).inspect_err(|_| restore_preset(&mut inner, name, previous))
+    }
+
+    /// 导出预设，用于分享
+    pub fn export_preset(&self, id: &str, format: Format) -> Result<String, String> {
+        let inner = self.inner.lock().unwrap();
+        let tuning = find_preset(&inner, id)?;
+        let mut document = serde_json::to_value(&tuning).map_err(|e| e.to_string())?;
+        if let Some(object) = document.as_object_mut() {
+            object.insert("version".to_string(), schema::CURRENT_VERSION.into());
+        }
+        transfer::export(&document, format)
+    }
+
+    /// 导入分享的预设并以 `name` 保存（只能包含预设对应的部分，缺少的字段取默认值）
+    pub fn import_preset(&self, name: &str, text: &str, format: Format) -> Result<(), String> {
+        presets::validate_name(name)?;
+        let document = transfer::parse(text, format)?;
+        if let Some(object) = document.as_object() {
+            let extra = object
+                .keys()
+                .find(|key| *key != "version" && !presets::SECTIONS.contains(&key.as_str()));
+            if let Some(key) = extra {
+                return Err(format!(
+                    "预设只能包含 {}（发现 {}）",
+                    presets::SECTIONS.join("、"),
+                    key
+                ));
+            }
+        }
+        let tuning = Tuning::of(&import_document(&Settings::default(), document)?);
+
+        let mut inner = self.inner.lock().unwrap();
+        let previous = inner.presets.insert(name.to_string(), tuning);
+        save_presets(&inner).inspect_err(|_| restore_preset(&mut inner, name, previous))
+    }
+
+    /// 写出后端配置文件，返回其路径
+    pub fn write_backend_config(&self) -> Result<PathBuf, String> {
+        let inner = self.inner.lock().unwrap();
+        let dir = inner
+            .dir
+            .as_ref()
+            .ok_or_else(|| "设置尚未初始化".to_string())?;
+        let settings = &inner.settings;
+        let config = BackendConfig {
+            gesture: &settings.gesture,
+            state_machine: &settings.state_machine,
+            action: BackendAction {
+                mouse_sensitivity: settings.action.mouse_sensitivity,
+                mouse_smoothing: settings.action.mouse_smoothing,
+            },
+            server: BackendServer {
+                heartbeat_interval: settings.server.heartbeat_interval,
+                connection_timeout: settings.server.connection_timeout,
+            },
+            camera: &settings.camera,
+        };
+
+        let path = dir.join(BACKEND_FILE_NAME);
+        let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        write_atomic(&path, text.as_bytes())
+            .map_err(|e| format!("无法写入后端配置 {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+/// 比较两份设置中后端相关的部分
+pub fn backend_changes(before: &Settings, after: &Settings) -> BackendChanges {
+    let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return BackendChanges::default();
+    };
+    let changes = diff::diff(&before, &after);
+    let in_sections = |sections: &[&str], change: &diff::Change| {
+        let section = change.path.split('.').next().unwrap_or_default();
+        sections.contains(&section)
+    };
+
+    BackendChanges {
+        live: diff::to_patch(changes.iter().filter(|c| in_sections(LIVE_SECTIONS, c))),
+        restart_required: changes
+            .iter()
+            .filter(|c| in_sections(RESTART_SECTIONS, c))
+            .map(|c| c.path.clone())
+            .collect(),
+    }
+}
+
+/// 可在运行时推送的全部配置（连接后端时同步一次，覆盖后端启动后的修改）
+pub fn live_config(settings: &Settings) -> Map<String, Value> {
+    let Ok(Value::Object(mut document)) = serde_json::to_value(settings) else {
+        return Map::new();
+    };
+    document.retain(|section, _| LIVE_SECTIONS.contains(&section.as_str()));
+    document
+}
+
+/// 解析设置文件：迁移到当前版本后覆盖到默认设置上，跳过未知或类型不符的字段
+fn load(text: &str) -> Result<Settings, String> {
+    let mut document: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    migrate::migrate(&mut document)?;
+
+    let mut merged = serde_json::to_value(Settings::default()).map_err(|e| e.to_string())?;
+    for skipped in merge::merge(&mut merged, document, false)? {
+        eprintln!("[Tauri] 忽略设置项: {}", skipped);
+    }
+    let settings: Settings = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// 把外部文档迁移到当前版本后严格合并到 `base` 上，并校验结果
+fn import_document(base: &Settings, mut document: Value) -> Result<Settings, String> {
+    migrate::migrate(&mut document)?;
+    if let Some(object) = document.as_object_mut() {
+        object.remove("version");
+    }
+    let mut merged = serde_json::to_value(base).map_err(|e| e.to_string())?;
+    merge::merge(&mut merged, document, true)?;
+    let settings = from_document(merged)?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// 反序列化完整文档，错误信息包含字段路径
+fn from_document(document: Value) -> Result<Settings, String> {
+    serde_path_to_error::deserialize(document).map_err(|e| format!("{}: {}", e.path(), e.inner()))
+}
+
+/// 导入后新增的启动程序、执行命令或打开链接的绑定（当前设置中已有的相同绑定不算）
+fn imported_host_actions(before: &Settings, after: &Settings) -> Vec<ImportedAction> {
+    let mut found = Vec::new();
+    for (profile, bindings) in &after.host.mappings {
+        let existing = before.host.mappings.get(profile);
+        for (index, binding) in bindings.iter().enumerate() {
+            let runs = matches!(
+                binding.action,
+                Action::Launch { .. } | Action::Shell { .. } | Action::OpenUrl { .. }
+            );
+            if runs && !existing.is_some_and(|list| list.contains(binding)) {
+                found.push(ImportedAction {
+                    path: format!("host.mappings.{}.{}", profile, index),
+                    binding: binding.clone(),
+                });
+            }
+        }
+    }
+    found
+}
+
+/// 按字段路径列出两份设置的差异
+fn changes(before: &Settings, after: &Settings) -> Result<Vec<Change>, String> {
+    let before = serde_json::to_value(before).map_err(|e| e.to_string())?;
+    let after = serde_json::to_value(after).map_err(|e| e.to_string())?;
+    Ok(diff::diff(&before, &after))
+}
+
+fn find_preset(inner: &Inner, id: &str) -> Result<Tuning, String> {
+    match presets::find_builtin(id) {
+        Some(preset) => Ok(preset.tuning()),
+        None => inner
+            .presets
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("未知的预设: {}", id)),
+    }
+}
+
+/// 保存失败时撤销对用户预设的修改
+fn restore_preset(inner: &mut Inner, name: &str, previous: Option<Tuning>) {
+    match previous {
+        Some(tuning) => inner.presets.insert(name.to_string(), tuning),
+        None => inner.presets.remove(name),
+    };
+}
+
+/// 读取用户预设，跳过无效的条目
+fn load_presets(path: &Path) -> BTreeMap<String, Tuning> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    let documents: BTreeMap<String, Value> = match serde_json::from_str(&text) {
+        Ok(documents) => documents,
+        Err(e) => {
+            eprintln!("[Tauri] 预设文件无效: {}", e);
+            return BTreeMap::new();
+        }
+    };
+
+    let mut loaded = BTreeMap::new();
+    for (name, document) in documents {
+        match import_document(&Settings::default(), document) {
+            Ok(settings) => {
+                loaded.insert(name, Tuning::of(&settings));
+            }
+            Err(e) => eprintln!("[Tauri] 忽略无效的预设 {}: {}", name, e),
+        }
+    }
+    loaded
+}
+
+fn save_presets(inner: &Inner) -> Result<(), String> {
+    let Some(dir) = &inner.dir else {
+        return Ok(());
+    };
+    let path = dir.join(PRESETS_FILE_NAME);
+    let text = serde_json::to_string_pretty(&inner.presets).map_err(|e| e.to_string())?;
+    write_atomic(&path, text.as_bytes())
+        .map_err(|e| format!("无法保存预设 {}: {}", path.display(), e))
+}
+
+/// 校验并保存新设置
+fn replace(inner: &mut Inner, settings: Settings) -> Result<Settings, String> {
+    settings.validate()?;
+    if settings != inner.settings {
+        let previous = std::mem::replace(&mut inner.settings, settings);
+        if let Err(e) = save(inner) {
+            inner.settings = previous;
+            return Err(e);
+        }
+    }
+    Ok(inner.settings.clone())
+}
+
+fn save(inner: &Inner) -> Result<(), String> {
+    let Some(dir) = &inner.dir else {
+        return Ok(());
+    };
+    let path = dir.join(FILE_NAME);
+    let text = serde_json::to_string_pretty(&inner.settings).map_err(|e| e.to_string())?;
+    write_atomic(&path, text.as_bytes())
+        .map_err(|e| format!("无法保存设置 {}: {}", path.display(), e))
+}
+
+/// 先写入临时文件并落盘，再替换目标文件，避免写到一半时损坏
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::ShortcutAction;
+    use phantom_protocol::GestureEventType;
+    use serde_json::json;
+
+    #[test]
+    fn default_settings_round_trip_through_load() {
+        let text = serde_json::to_string(&Settings::default()).unwrap();
+        assert_eq!(load(&text).unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn load_skips_unknown_and_mistyped_fields() {
+        let text = json!({
+            "version": 1,
+            "unknown": true,
+            "camera": { "fps": "fast", "width": 1280 },
+        })
+        .to_string();
+        let settings = load(&text).unwrap();
+        assert_eq!(settings.camera.width, 1280);
+        assert_eq!(settings.camera.fps, Settings::default().camera.fps);
+    }
+
+    #[test]
+    fn load_rejects_invalid_values() {
+        let text = json!({ "camera": { "fps": 0 } }).to_string();
+        assert!(load(&text).unwrap_err().starts_with("camera.fps"));
+    }
+
+    #[test]
+    fn deserialize_errors_include_field_path() {
+        let cases = [
+            ("host.close_action", json!("explode")),
+            ("host.restart_policy.max_retries", json!(-1)),
+            ("server.port", json!(70000)),
+        ];
+        for (path, value) in cases {
+            let mut document = serde_json::to_value(Settings::default()).unwrap();
+            let pointer = format!("/{}", path.replace('.', "/"));
+            *document.pointer_mut(&pointer).unwrap() = value;
+            let error = from_document(document).unwrap_err();
+            assert!(error.starts_with(&format!("{}: ", path)), "{error}");
+        }
+    }
+
+    #[test]
+    fn tuning_changes_are_pushed_live() {
+        let before = Settings::default();
+        let mut after = before.clone();
+        after.gesture.pinch_distance_ratio += 0.05;
+        after.state_machine.t_enter = 150;
+        after.action.mouse_sensitivity = 2.0;
+
+        let changes = backend_changes(&before, &after);
+        assert_eq!(
+            Value::Object(changes.live),
+            json!({
+                "gesture": { "pinch_distance_ratio": after.gesture.pinch_distance_ratio },
+                "state_machine": { "t_enter": 150 },
+                "action": { "mouse_sensitivity": 2.0 },
+            })
+        );
+        assert!(changes.restart_required.is_empty());
+    }
+
+    #[test]
+    fn camera_and_server_changes_need_restart() {
+        let before = Settings::default();
+        let mut after = before.clone();
+        after.camera.fps = 60;
+        after.camera.mirror = false;
+        after.server.port = 9000;
+
+        let changes = backend_changes(&before, &after);
+        assert!(changes.live.is_empty());
+        assert_eq!(
+            changes.restart_required,
+            ["server.port", "camera.fps", "camera.mirror"]
+        );
+    }
+
+    #[test]
+    fn gesture_priority_is_replaced_whole() {
+        let before = Settings::default();
+        let mut after = before.clone();
+        after.state_machine.gesture_priority.remove("victory");
+        after
+            .state_machine
+            .gesture_priority
+            .insert("ok".to_string(), 4);
+
+        let changes = backend_changes(&before, &after);
+        assert_eq!(
+            Value::Object(changes.live),
+            json!({
+                "state_machine": {
+                    "gesture_priority": after.state_machine.gesture_priority,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn host_changes_are_not_sent_to_backend() {
+        let before = Settings::default();
+        let mut after = before.clone();
+        after.host.profile = "media".to_string();
+        after.host.restart_policy.max_retries += 1;
+
+        let changes = backend_changes(&before, &after);
+        assert!(changes.live.is_empty());
+        assert!(changes.restart_required.is_empty());
+    }
+
+    /// 含禁用的快捷键、空参数与未指定显示器的设置，用于检查导出导入无损
+    fn edited_settings() -> Settings {
+        let mut settings = Settings::default();
+        settings.camera.fps = 60;
+        settings.gesture.pinch_distance_ratio = 0.3;
+        settings.host.display.monitor = None;
+        settings
+            .host
+            .shortcuts
+            .insert(ShortcutAction::ToggleWindow, None);
+        let profile = settings.host.mappings.keys().next().unwrap().clone();
+        settings
+            .host
+            .mappings
+            .get_mut(&profile)
+            .unwrap()
+            .push(ActionBinding {
+                gesture: "ok".to_string(),
+                on: GestureEventType::Enter,
+                action: Action::Launch {
+                    program: "xdg-open".to_string(),
+                    args: vec![String::new()],
+                },
+                interval_ms: None,
+            });
+        settings
+    }
+
+    #[test]
+    fn export_import_round_trips() {
+        for format in [Format::Json, Format::Toml] {
+            let store = SettingsStore::new();
+            store.modify(|s| *s = edited_settings()).unwrap();
+            let text = store.export(format).unwrap();
+
+            let imported = SettingsStore::new().import(&text, format).unwrap();
+            assert_eq!(imported, edited_settings(), "{format:?}");
+            // 再次导入同一文档不产生变化
+            let preview = store.preview_import(&text, format).unwrap();
+            assert!(preview.changes.is_empty(), "{format:?}");
+            assert!(preview.host_actions.is_empty(), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn import_errors_name_the_field() {
+        type Case = (&'static str, &'static str, &'static str);
+        let cases: [Case; 3] = [
+            (
+                r#"{ "camera": { "zoom": 2 } }"#,
+                "[camera]\nzoom = 2\n",
+                "未知的设置项: camera.zoom",
+            ),
+            (
+                r#"{ "camera": { "fps": "fast" } }"#,
+                "[camera]\nfps = \"fast\"\n",
+                "camera.fps 应为非负整数",
+            ),
+            (
+                r#"{ "camera": { "fps": 0 } }"#,
+                "[camera]\nfps = 0\n",
+                "camera.fps",
+            ),
+        ];
+        for (json_text, toml_text, expected) in cases {
+            for (text, format) in [(json_text, Format::Json), (toml_text, Format::Toml)] {
+                let store = SettingsStore::new();
+                let error = store.import(text, format).unwrap_err();
+                assert!(error.starts_with(expected), "{format:?} {text}: {error}");
+                assert_eq!(store.preview_import(text, format).unwrap_err(), error);
+                assert_eq!(store.get(), Settings::default());
+            }
+        }
+    }
+
+    #[test]
+    fn import_flags_new_host_actions() {
+        let settings = edited_settings();
+        let profile = settings.host.mappings.keys().next().unwrap();
+        let index = settings.host.mappings[profile].len() - 1;
+
+        let found = imported_host_actions(&Settings::default(), &settings);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].path,
+            format!("host.mappings.{}.{}", profile, index)
+        );
+        assert_eq!(found[0].binding, settings.host.mappings[profile][index]);
+        // 已有的相同绑定不再标出
+        assert!(imported_host_actions(&settings, &settings).is_empty());
+    }
+
+    #[test]
+    fn preset_import_rejects_other_sections() {
+        let store = SettingsStore::new();
+        let text = r#"{ "gesture": { "pinch_distance_ratio": 0.3 }, "camera": { "fps": 60 } }"#;
+        assert_eq!(
+            store.import_preset("mine", text, Format::Json).unwrap_err(),
+            "预设只能包含 gesture、state_machine、action（发现 camera）"
+        );
+        assert!(store.presets().iter().all(|preset| preset.builtin));
+
+        let text = "version = 1\n[gesture]\npinch_distance_ratio = 0.3\n";
+        store.import_preset("mine", text, Format::Toml).unwrap();
+        let applied = store.apply_preset("mine").unwrap();
+        assert_eq!(applied.gesture.pinch_distance_ratio, 0.3);
+    }
+
+    #[test]
+    fn preset_export_import_round_trips() {
+        for format in [Format::Json, Format::Toml] {
+            let store = SettingsStore::new();
+            let text = store.export_preset("presenter", format).unwrap();
+            store.import_preset("copy", &text, format).unwrap();
+            let inner = store.inner.lock().unwrap();
+            assert_eq!(
+                find_preset(&inner, "copy").unwrap(),
+                find_preset(&inner, "presenter").unwrap(),
+                "{format:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! 调校预设
+//!
+//! 预设只包含识别与鼠标参数（`gesture`、`state_machine`、`action`），
+//! 应用时整体替换这三部分，不影响摄像头、端口和宿主设置。
+//! 内置预设在默认值基础上调整；用户预设保存在配置目录的 `presets.json` 中
+
+use serde::{Deserialize, Serialize};
+
+use super::schema::{ActionMapping, GestureThresholds, Settings, StateMachineConfig};
+
+/// 预设包含的设置部分
+pub const SECTIONS: &[&str] = &["gesture", "state_machine", "action"];
+
+/// 预设名称的最大长度（字符）
+const MAX_NAME_LEN: usize = 32;
+
+/// 一套调校参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tuning {
+    pub gesture: GestureThresholds,
+    pub state_machine: StateMachineConfig,
+    pub action: ActionMapping,
+}
+
+impl Tuning {
+    /// 取出设置中的调校参数
+    pub fn of(settings: &Settings) -> Self {
+        Self {
+            gesture: settings.gesture.clone(),
+            state_machine: settings.state_machine.clone(),
+            action: settings.action.clone(),
+        }
+    }
+
+    /// 替换设置中的调校参数
+    pub fn apply_to(self, settings: &mut Settings) {
+        settings.gesture = self.gesture;
+        settings.state_machine = self.state_machine;
+        settings.action = self.action;
+    }
+}
+
+/// 内置预设
+pub struct BuiltinPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// 在默认参数上做的调整
+    adjust: fn(&mut Tuning),
+}
+
+impl BuiltinPreset {
+    pub fn tuning(&self) -> Tuning {
+        let mut tuning = Tuning::of(&Settings::default());
+        (self.adjust)(&mut tuning);
+        tuning
+    }
+}
+
+/// 所有内置预设
+pub const BUILTIN_PRESETS: &[BuiltinPreset] = &[
+    BuiltinPreset {
+        id: "sensitive",
+        name: "灵敏",
+        adjust: |t| {
+            // 更低的进入阈值与更短的确认时间，适合光线良好、手部动作幅度小的场景
+            t.gesture.pinch_distance_ratio = 0.3;
+            t.gesture.pinch_release_ratio = 0.4;
+            t.gesture.slide_min_distance = 0.07;
+            t.state_machine.p_high = 0.35;
+            t.state_machine.p_hold = 0.25;
+            t.state_machine.p_low = 0.15;
+            t.state_machine.t_enter = 80;
+            t.state_machine.t_exit = 100;
+            t.state_machine.t_cooldown = 150;
+            t.state_machine.ema_alpha = 0.45;
+            t.state_machine.median_window = 3;
+            t.action.mouse_sensitivity = 2.0;
+            t.action.mouse_smoothing = 0.6;
+        },
+    },
+    BuiltinPreset {
+        id: "conservative",
+        name: "稳健",
+        adjust: |t| {
+            // 更高的阈值与更长的确认时间，减少误触发
+            t.gesture.pinch_distance_ratio = 0.2;
+            t.gesture.slide_min_distance = 0.15;
+            t.gesture.slide_max_z_change = 0.04;
+            t.state_machine.p_high = 0.55;
+            t.state_machine.p_hold = 0.4;
+            t.state_machine.p_low = 0.25;
+            t.state_machine.t_enter = 200;
+            t.state_machine.t_exit = 150;
+            t.state_machine.t_cooldown = 400;
+            t.state_machine.ema_alpha = 0.2;
+            t.state_machine.median_window = 7;
+            t.action.mouse_sensitivity = 1.2;
+            t.action.mouse_smoothing = 0.8;
+        },
+    },
+    BuiltinPreset {
+        id: "presenter",
+        name: "演示",
+        adjust: |t| {
+            // 站立演示：滑动要求幅度更大、容许前后移动，翻页之间留出冷却，指针更平稳
+            t.gesture.slide_min_distance = 0.15;
+            t.gesture.slide_max_z_change = 0.08;
+            t.state_machine.p_high = 0.45;
+            t.state_machine.t_enter = 150;
+            t.state_machine.t_cooldown = 500;
+            t.state_machine.ema_alpha = 0.25;
+            t.state_machine.median_window = 7;
+            t.action.mouse_sensitivity = 1.0;
+            t.action.mouse_smoothing = 0.85;
+        },
+    },
+];
+
+/// 预设列表项（`get_presets` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetInfo {
+    pub id: String,
+    pub name: String,
+    pub builtin: bool,
+}
+
+pub fn find_builtin(id: &str) -> Option<&'static BuiltinPreset> {
+    BUILTIN_PRESETS.iter().find(|preset| preset.id == id)
+}
+
+/// 检查用户预设名称
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+    if name.trim() != name {
+        return Err("预设名称不能以空白开头或结尾".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("预设名称不能超过 {} 个字符", MAX_NAME_LEN));
+    }
+    if find_builtin(name).is_some() {
+        return Err(format!("{} 是内置预设，不能修改", name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_names() {
+        let long = "a".repeat(MAX_NAME_LEN + 1);
+        let cases = [
+            ("", "预设名称不能为空"),
+            ("  ", "预设名称不能为空"),
+            (" mine", "预设名称不能以空白开头或结尾"),
+            (long.as_str(), "预设名称不能超过"),
+        ];
+        for (name, expected) in cases {
+            let error = validate_name(name).unwrap_err();
+            assert!(error.starts_with(expected), "{name:?}: {error}");
+        }
+        assert!(validate_name("我的预设").is_ok());
+    }
+
+    #[test]
+    fn rejects_builtin_ids() {
+        for preset in BUILTIN_PRESETS {
+            assert_eq!(
+                validate_name(preset.id).unwrap_err(),
+                format!("{} 是内置预设，不能修改", preset.id)
+            );
+        }
+    }
+}
@@ -0,0 +1,456 @@
+//! 设置文档结构
+//!
+//! 后端相关部分与 `python_service/config/settings.py` 中的 dataclass 一一对应，
+//! 字段名与默认值保持一致；`host` 部分是宿主自身的设置
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::RestartPolicy;
+use crate::display::DisplaySettings;
+use crate::executor::ExecutorSettings;
+use crate::filter::FilterSettings;
+use crate::focus::AppProfileRules;
+use crate::input::InputSettings;
+use crate::lifecycle::CloseAction;
+use crate::mapping::{self, ProfileMappings};
+use crate::profiles;
+use crate::shortcuts::{self, ShortcutBindings};
+
+/// 当前设置文档版本
+pub const CURRENT_VERSION: u32 = 1;
+
+/// 设置文档
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    /// 文档版本，用于迁移
+    pub version: u32,
+    pub gesture: GestureThresholds,
+    pub state_machine: StateMachineConfig,
+    pub action: ActionMapping,
+    pub server: ServerConfig,
+    pub camera: CameraConfig,
+    pub host: HostSettings,
+}
+
+/// 手势识别阈值（`GestureThresholds`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GestureThresholds {
+    /// 手指伸展判定角度（弧度）
+    pub finger_extended_angle: f64,
+    /// 手指弯曲判定角度（弧度）
+    pub finger_bent_angle: f64,
+    /// 拇指-食指距离小于手掌宽度的该比例时为捏合
+    pub pinch_distance_ratio: f64,
+    /// 大于该比例时松开
+    pub pinch_release_ratio: f64,
+    /// 指尖到手腕距离小于手长的该比例时为握拳
+    pub fist_tip_wrist_ratio: f64,
+    /// 指尖间距大于手掌宽度的该比例时为张开
+    pub open_spread_ratio: f64,
+    /// 最小滑动距离（相对画面宽度）
+    pub slide_min_distance: f64,
+    /// 滑动时允许的最大 Z 轴变化
+    pub slide_max_z_change: f64,
+}
+
+/// 手势状态机参数（`StateMachineConfig`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateMachineConfig {
+    /// 进入手势的概率阈值
+    pub p_high: f64,
+    /// 保持手势的概率阈值
+    pub p_hold: f64,
+    /// 退出手势的概率阈值
+    pub p_low: f64,
+    /// 进入手势需要的持续时间（毫秒）
+    pub t_enter: u32,
+    /// 退出手势需要的持续时间（毫秒）
+    pub t_exit: u32,
+    /// 手势之间的冷却时间（毫秒）
+    pub t_cooldown: u32,
+    /// 指数移动平均系数
+    pub ema_alpha: f64,
+    /// 中值滤波窗口大小
+    pub median_window: u32,
+    /// 手势优先级（越大越优先）
+    pub gesture_priority: BTreeMap<String, u32>,
+}
+
+/// 鼠标控制参数（`ActionMapping`；手势到动作的映射由手势配置决定）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionMapping {
+    pub mouse_sensitivity: f64,
+    pub mouse_smoothing: f64,
+}
+
+/// WebSocket 服务参数（`ServerConfig`；后端始终只监听本机地址）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// 首选 WebSocket 端口（被占用时自动另选）
+    pub port: u16,
+    /// 首选 MJPEG 端口
+    pub mjpeg_port: u16,
+    /// 心跳间隔（毫秒）
+    pub heartbeat_interval: u32,
+    /// 连接超时（毫秒）
+    pub connection_timeout: u32,
+}
+
+/// 摄像头参数（`CameraConfig`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraConfig {
+    pub device_id: u32,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    /// 是否镜像（自拍模式）
+    pub mirror: bool,
+}
+
+/// 宿主设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostSettings {
+    /// 关闭主窗口时的行为
+    pub close_action: CloseAction,
+    /// 后端自动重启策略
+    pub restart_policy: RestartPolicy,
+    /// 当前手势配置（没有匹配的应用规则时生效）
+    pub profile: String,
+    /// 按前台应用切换手势配置
+    pub app_profiles: AppProfileRules,
+    /// 各手势配置的动作绑定
+    pub mappings: ProfileMappings,
+    /// 输入注入方式
+    pub input: InputSettings,
+    /// 宿主执行手势动作
+    pub executor: ExecutorSettings,
+    /// 鼠标绝对定位映射到哪个显示器
+    pub display: DisplaySettings,
+    /// 关键点滤波
+    pub filter: FilterSettings,
+    /// 全局快捷键
+    pub shortcuts: ShortcutBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            gesture: GestureThresholds {
+                finger_extended_angle: 2.5,
+                finger_bent_angle: 1.8,
+                pinch_distance_ratio: 0.25,
+                pinch_release_ratio: 0.35,
+                fist_tip_wrist_ratio: 0.5,
+                open_spread_ratio: 0.8,
+                slide_min_distance: 0.1,
+                slide_max_z_change: 0.05,
+            },
+            state_machine: StateMachineConfig {
+                p_high: 0.4,
+                p_hold: 0.3,
+                p_low: 0.2,
+                t_enter: 120,
+                t_exit: 120,
+                t_cooldown: 200,
+                ema_alpha: 0.3,
+                median_window: 5,
+                gesture_priority: [
+                    ("open", 6),
+                    ("fist", 5),
+                    ("thumbs_up", 4),
+                    ("point", 3),
+                    ("victory", 3),
+                    ("ok", 3),
+                    ("idle", 0),
+                ]
+                .into_iter()
+                .map(|(gesture, priority)| (gesture.to_string(), priority))
+                .collect(),
+            },
+            action: ActionMapping {
+                mouse_sensitivity: 1.5,
+                mouse_smoothing: 0.7,
+            },
+            server: ServerConfig {
+                port: 8765,
+                mjpeg_port: 8766,
+                heartbeat_interval: 5000,
+                connection_timeout: 30000,
+            },
+            camera: CameraConfig {
+                device_id: 0,
+                width: 640,
+                height: 480,
+                fps: 30,
+                mirror: true,
+            },
+            host: HostSettings {
+                close_action: CloseAction::default(),
+                restart_policy: RestartPolicy::default(),
+                profile: profiles::DEFAULT_PROFILE.to_string(),
+                app_profiles: AppProfileRules::default(),
+                mappings: mapping::default_mappings(),
+                input: InputSettings::default(),
+                executor: ExecutorSettings::default(),
+                display: DisplaySettings::default(),
+                filter: FilterSettings::default(),
+                shortcuts: shortcuts::default_bindings(),
+            },
+        }
+    }
+}
+
+impl Settings {
+    /// 校验取值范围，错误信息包含字段路径
+    pub fn validate(&self) -> Result<(), String> {
+        let g = &self.gesture;
+        range(
+            "gesture.finger_extended_angle",
+            g.finger_extended_angle,
+            0.5,
+            3.2,
+        )?;
+        range("gesture.finger_bent_angle", g.finger_bent_angle, 0.5, 3.2)?;
+        range(
+            "gesture.pinch_distance_ratio",
+            g.pinch_distance_ratio,
+            0.05,
+            1.0,
+        )?;
+        range(
+            "gesture.pinch_release_ratio",
+            g.pinch_release_ratio,
+            0.05,
+            1.5,
+        )?;
+        range(
+            "gesture.fist_tip_wrist_ratio",
+            g.fist_tip_wrist_ratio,
+            0.1,
+            1.0,
+        )?;
+        range("gesture.open_spread_ratio", g.open_spread_ratio, 0.1, 2.0)?;
+        range(
+            "gesture.slide_min_distance",
+            g.slide_min_distance,
+            0.01,
+            1.0,
+        )?;
+        range("gesture.slide_max_z_change", g.slide_max_z_change, 0.0, 1.0)?;
+        if g.finger_bent_angle >= g.finger_extended_angle {
+            return Err("gesture.finger_bent_angle 必须小于 finger_extended_angle".to_string());
+        }
+        if g.pinch_release_ratio <= g.pinch_distance_ratio {
+            return Err("gesture.pinch_release_ratio 必须大于 pinch_distance_ratio".to_string());
+        }
+
+        let s = &self.state_machine;
+        range("state_machine.p_high", s.p_high, 0.0, 1.0)?;
+        range("state_machine.p_hold", s.p_hold, 0.0, 1.0)?;
+        range("state_machine.p_low", s.p_low, 0.0, 1.0)?;
+        if !(s.p_low <= s.p_hold && s.p_hold <= s.p_high) {
+            return Err("state_machine 须满足 p_low <= p_hold <= p_high".to_string());
+        }
+        range("state_machine.t_enter", s.t_enter, 0, 2000)?;
+        range("state_machine.t_exit", s.t_exit, 0, 2000)?;
+        range("state_machine.t_cooldown", s.t_cooldown, 0, 5000)?;
+        range("state_machine.ema_alpha", s.ema_alpha, 0.01, 1.0)?;
+        // 后端只保留最近 10 帧的概率
+        range("state_machine.median_window", s.median_window, 1, 10)?;
+        for (gesture, priority) in &s.gesture_priority {
+            range(
+                &format!("state_machine.gesture_priority.{}", gesture),
+                *priority,
+                0,
+                100,
+            )?;
+        }
+
+        let a = &self.action;
+        range("action.mouse_sensitivity", a.mouse_sensitivity, 0.1, 10.0)?;
+        range("action.mouse_smoothing", a.mouse_smoothing, 0.0, 0.99)?;
+
+        let sv = &self.server;
+        range("server.port", sv.port, 1024, u16::MAX)?;
+        range("server.mjpeg_port", sv.mjpeg_port, 1024, u16::MAX)?;
+        if sv.port == sv.mjpeg_port {
+            return Err("server.port 与 server.mjpeg_port 不能相同".to_string());
+        }
+        range(
+            "server.heartbeat_interval",
+            sv.heartbeat_interval,
+            1000,
+            60000,
+        )?;
+        range(
+            "server.connection_timeout",
+            sv.connection_timeout,
+            sv.heartbeat_interval * 2,
+            300000,
+        )?;
+
+        let c = &self.camera;
+        range("camera.device_id", c.device_id, 0, 63)?;
+        range("camera.width", c.width, 160, 3840)?;
+        range("camera.height", c.height, 120, 2160)?;
+        range("camera.fps", c.fps, 1, 120)?;
+
+        let h = &self.host;
+        h.restart_policy
+            .validate()
+            .map_err(|e| format!("host.restart_policy: {}", e))?;
+        if profiles::find(&h.profile).is_none() {
+            return Err(format!("host.profile: 未知的手势配置 {}", h.profile));
+        }
+        h.app_profiles
+            .validate()
+            .map_err(|e| format!("host.app_profiles.{}", e))?;
+        mapping::validate_mappings(&h.mappings).map_err(|e| format!("host.mappings.{}", e))?;
+        range(
+            "host.executor.mouse_speed",
+            h.executor.mouse_speed,
+            0.1,
+            10.0,
+        )?;
+        range(
+            "host.executor.mouse_deadzone",
+            h.executor.mouse_deadzone,
+            0.0,
+            0.05,
+        )?;
+        h.display
+            .validate()
+            .map_err(|e| format!("host.display.{}", e))?;
+        let f = &h.filter;
+        range(
+            "host.filter.one_euro.min_cutoff",
+            f.one_euro.min_cutoff,
+            0.01,
+            30.0,
+        )?;
+        range("host.filter.one_euro.beta", f.one_euro.beta, 0.0, 1000.0)?;
+        range(
+            "host.filter.one_euro.d_cutoff",
+            f.one_euro.d_cutoff,
+            0.01,
+            30.0,
+        )?;
+        range(
+            "host.filter.kalman.process_noise",
+            f.kalman.process_noise,
+            1e-6,
+            1e4,
+        )?;
+        range(
+            "host.filter.kalman.measurement_noise",
+            f.kalman.measurement_noise,
+            1e-9,
+            1.0,
+        )?;
+        range(
+            "host.filter.double_exponential.alpha",
+            f.double_exponential.alpha,
+            0.01,
+            1.0,
+        )?;
+        range(
+            "host.filter.double_exponential.gamma",
+            f.double_exponential.gamma,
+            0.0,
+            1.0,
+        )?;
+        range("host.filter.deadzone", f.deadzone, 0.0, 0.05)?;
+        // 每只手 21 个关键点
+        range("host.filter.trace.landmark", f.trace.landmark, 0, 20)?;
+        shortcuts::validate_bindings(&h.shortcuts).map_err(|e| format!("host.shortcuts: {}", e))
+    }
+}
+
+/// 检查数值是否在 [min, max] 范围内
+fn range<T: PartialOrd + std::fmt::Display>(
+    path: &str,
+    value: T,
+    min: T,
+    max: T,
+) -> Result<(), String> {
+    // 用取反的比较使 NaN 也不能通过（TOML 可以写出 nan）
+    if !(value >= min && value <= max) {
+        return Err(format!(
+            "{} 应在 {} 到 {} 之间（当前为 {}）",
+            path, min, max, value
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_settings_are_valid() {
+        let settings = Settings::default();
+        settings.validate().unwrap();
+        let document = serde_json::to_value(&settings).unwrap();
+        let parsed: Settings = serde_json::from_value(document).unwrap();
+        assert_eq!(parsed, settings);
+        parsed.validate().unwrap();
+    }
+
+    /// 期望的错误路径与修改方式
+    type Case = (&'static str, fn(&mut Settings));
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let cases: &[Case] = &[
+            ("gesture.pinch_distance_ratio", |s| {
+                s.gesture.pinch_distance_ratio = 5.0
+            }),
+            ("gesture.pinch_release_ratio", |s| {
+                s.gesture.pinch_release_ratio = 0.2
+            }),
+            ("gesture.open_spread_ratio", |s| {
+                s.gesture.open_spread_ratio = f64::NAN
+            }),
+            ("state_machine", |s| s.state_machine.p_low = 0.35),
+            ("state_machine.median_window", |s| {
+                s.state_machine.median_window = 11
+            }),
+            ("state_machine.gesture_priority.fist", |s| {
+                s.state_machine
+                    .gesture_priority
+                    .insert("fist".to_string(), 101);
+            }),
+            ("action.mouse_smoothing", |s| s.action.mouse_smoothing = 1.0),
+            ("server.port", |s| s.server.port = 80),
+            ("server.port", |s| s.server.mjpeg_port = s.server.port),
+            ("server.heartbeat_interval", |s| {
+                s.server.heartbeat_interval = 500
+            }),
+            ("server.connection_timeout", |s| {
+                s.server.connection_timeout = s.server.heartbeat_interval
+            }),
+            ("camera.fps", |s| s.camera.fps = 0),
+            ("host.restart_policy", |s| {
+                s.host.restart_policy.multiplier = f64::NAN
+            }),
+            ("host.profile", |s| s.host.profile = "unknown".to_string()),
+            ("host.executor.mouse_deadzone", |s| {
+                s.host.executor.mouse_deadzone = -0.1
+            }),
+            ("host.filter.deadzone", |s| s.host.filter.deadzone = 0.1),
+            ("host.filter.trace.landmark", |s| {
+                s.host.filter.trace.landmark = 21
+            }),
+        ];
+        for (path, modify) in cases {
+            let mut settings = Settings::default();
+            modify(&mut settings);
+            let error = settings.validate().unwrap_err();
+            assert!(error.starts_with(path), "{path}: {error}");
+        }
+    }
+}
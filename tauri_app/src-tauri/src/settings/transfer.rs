@@ -0,0 +1,134 @@
+//! 设置导入导出
+//!
+//! 导出的文档与 `settings.json` 结构相同，可选 TOML 或 JSON。
+//! TOML 没有空值，导出时空值（如禁用的快捷键）写为空字符串，导入时只在可为空的字段上还原
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 可以为空的设置项（`*` 匹配任意键或数组下标），新增可为空的设置项时需同步修改
+const NULLABLE: &[&str] = &[
+    "host.shortcuts.*",
+    "host.display.monitor",
+    "host.app_profiles.rules.*.class",
+    "host.app_profiles.rules.*.title",
+];
+
+/// 文档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+/// 把文档序列化为文本
+pub fn export<T: Serialize>(document: &T, format: Format) -> Result<String, String> {
+    let mut value = serde_json::to_value(document).map_err(|e| e.to_string())?;
+    match format {
+        Format::Json => serde_json::to_string_pretty(&value).map_err(|e| e.to_string()),
+        Format::Toml => {
+            replace_nulls(&mut value);
+            toml::to_string_pretty(&value).map_err(|e| format!("无法导出为 TOML: {}", e))
+        }
+    }
+}
+
+/// 解析导入的文本
+pub fn parse(text: &str, format: Format) -> Result<Value, String> {
+    match format {
+        Format::Json => serde_json::from_str(text).map_err(|e| format!("JSON 格式错误: {}", e)),
+        Format::Toml => {
+            let mut value: Value =
+                toml::from_str(text).map_err(|e| format!("TOML 格式错误: {}", e))?;
+            restore_nulls(&mut value, &mut Vec::new());
+            Ok(value)
+        }
+    }
+}
+
+fn replace_nulls(value: &mut Value) {
+    match value {
+        Value::Null => *value = Value::String(String::new()),
+        Value::Object(object) => object.values_mut().for_each(replace_nulls),
+        Value::Array(array) => array.iter_mut().for_each(replace_nulls),
+        _ => {}
+    }
+}
+
+fn restore_nulls(value: &mut Value, path: &mut Vec<String>) {
+    match value {
+        Value::String(text) if text.is_empty() && is_nullable(path) => *value = Value::Null,
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                path.push(key.clone());
+                restore_nulls(value, path);
+                path.pop();
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter_mut().enumerate() {
+                path.push(index.to_string());
+                restore_nulls(value, path);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_nullable(path: &[String]) -> bool {
+    NULLABLE.iter().any(|pattern| {
+        let segments: Vec<&str> = pattern.split('.').collect();
+        segments.len() == path.len()
+            && segments
+                .iter()
+                .zip(path)
+                .all(|(segment, key)| *segment == "*" || segment == key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn toml_restores_nulls_only_on_nullable_fields() {
+        let document = json!({
+            "host": {
+                "shortcuts": { "toggle_camera": null, "emergency_stop": "CmdOrCtrl+Shift+F12" },
+                "display": { "monitor": null },
+                "app_profiles": { "rules": [{ "class": null, "title": "", "profile": "" }] },
+                "mappings": {
+                    "default": [{ "action": { "type": "launch", "program": "code", "args": ["", "-n"] } }],
+                },
+                "profile": "",
+            },
+        });
+        let text = export(&document, Format::Toml).unwrap();
+        let parsed = parse(&text, Format::Toml).unwrap();
+
+        let mut expected = document.clone();
+        // TOML 无法区分空值与空字符串，可为空的字段一律还原为空值
+        expected["host"]["app_profiles"]["rules"][0]["title"] = Value::Null;
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn json_keeps_empty_strings() {
+        let document = json!({ "host": { "shortcuts": { "toggle_camera": "" }, "profile": "" } });
+        let text = export(&document, Format::Json).unwrap();
+        assert_eq!(parse(&text, Format::Json).unwrap(), document);
+    }
+
+    #[test]
+    fn reports_syntax_errors_by_format() {
+        assert!(parse("{", Format::Json)
+            .unwrap_err()
+            .starts_with("JSON 格式错误"));
+        assert!(parse("host = ", Format::Toml)
+            .unwrap_err()
+            .starts_with("TOML 格式错误"));
+    }
+}
@@ -0,0 +1,137 @@
+//! 快捷键组合的规范化
+//!
+//! 同一组合有多种写法（`Ctrl+Shift+A`、`shift+control+a`），冲突检测前先统一形式
+
+/// 修饰键的规范名称与排序
+const MODIFIERS: &[(&str, &[&str])] = &[
+    (
+        "CmdOrCtrl",
+        &[
+            "cmdorctrl",
+            "commandorcontrol",
+            "cmdorcontrol",
+            "commandorctrl",
+        ],
+    ),
+    ("Ctrl", &["ctrl", "control"]),
+    ("Cmd", &["cmd", "command", "super", "meta"]),
+    ("Alt", &["alt", "option"]),
+    ("Shift", &["shift"]),
+];
+
+/// `CmdOrCtrl` 在当前平台对应的修饰键
+#[cfg(target_os = "macos")]
+const CMD_OR_CTRL: &str = "Cmd";
+#[cfg(not(target_os = "macos"))]
+const CMD_OR_CTRL: &str = "Ctrl";
+
+/// 规范化快捷键组合：修饰键按固定顺序排列，主键大写
+///
+/// 不做完整的按键名校验（由系统注册时校验），只拒绝明显无效的组合
+pub fn normalize(accelerator: &str) -> Result<String, String> {
+    let (modifiers, key) = parse(accelerator)?;
+    Ok(join(&modifiers, &key))
+}
+
+/// 系统实际注册的组合：`CmdOrCtrl` 换成当前平台的修饰键，冲突检测按此比较
+pub fn resolve(accelerator: &str) -> Result<String, String> {
+    let (modifiers, key) = parse(accelerator)?;
+    let platform = MODIFIERS
+        .iter()
+        .position(|(name, _)| *name == CMD_OR_CTRL)
+        .unwrap_or_default();
+    let mut resolved: Vec<usize> = modifiers
+        .into_iter()
+        .map(|index| if index == 0 { platform } else { index })
+        .collect();
+    resolved.sort_unstable();
+    resolved.dedup();
+    Ok(join(&resolved, &key))
+}
+
+/// 拆分为排好序的修饰键序号与大写的主键
+fn parse(accelerator: &str) -> Result<(Vec<usize>, String), String> {
+    let mut modifiers = Vec::new();
+    let mut key: Option<String> = None;
+
+    for part in accelerator.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err(format!("无效的快捷键: {}", accelerator));
+        }
+        let lower = part.to_lowercase();
+        match MODIFIERS
+            .iter()
+            .position(|(_, aliases)| aliases.contains(&lower.as_str()))
+        {
+            Some(index) if !modifiers.contains(&index) => modifiers.push(index),
+            Some(_) => return Err(format!("快捷键中有重复的修饰键: {}", accelerator)),
+            None if key.is_none() => key = Some(part.to_uppercase()),
+            None => return Err(format!("快捷键只能包含一个主键: {}", accelerator)),
+        }
+    }
+
+    let key = key.ok_or_else(|| format!("快捷键缺少主键: {}", accelerator))?;
+    if modifiers.is_empty() {
+        return Err(format!("全局快捷键至少需要一个修饰键: {}", accelerator));
+    }
+
+    modifiers.sort_unstable();
+    Ok((modifiers, key))
+}
+
+fn join(modifiers: &[usize], key: &str) -> String {
+    let mut parts: Vec<&str> = modifiers.iter().map(|&i| MODIFIERS[i].0).collect();
+    parts.push(key);
+    parts.join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_aliases_and_order() {
+        let cases = [
+            ("shift+control+a", "Ctrl+Shift+A"),
+            ("CommandOrControl + Shift + F9", "CmdOrCtrl+Shift+F9"),
+            ("option+meta+space", "Cmd+Alt+SPACE"),
+            ("Ctrl+CmdOrCtrl+X", "CmdOrCtrl+Ctrl+X"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_accelerators() {
+        let cases = [
+            ("Ctrl+", "无效的快捷键"),
+            ("Ctrl+control+A", "快捷键中有重复的修饰键"),
+            ("Ctrl+A+B", "快捷键只能包含一个主键"),
+            ("Ctrl+Shift", "快捷键缺少主键"),
+            ("F9", "全局快捷键至少需要一个修饰键"),
+        ];
+        for (input, expected) in cases {
+            let error = normalize(input).unwrap_err();
+            assert!(error.starts_with(expected), "{input}: {error}");
+            assert_eq!(resolve(input).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn resolves_cmd_or_ctrl_for_platform() {
+        let cases = [
+            ("CmdOrCtrl+Shift+F9", format!("{}+Shift+F9", CMD_OR_CTRL)),
+            ("Ctrl+Shift+F9", "Ctrl+Shift+F9".to_string()),
+            ("Alt+Shift+F9", "Alt+Shift+F9".to_string()),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(resolve(input).unwrap(), expected, "{input}");
+        }
+        // 与平台修饰键同时出现时合并
+        assert_eq!(
+            resolve(&format!("CmdOrCtrl+{}+K", CMD_OR_CTRL)).unwrap(),
+            format!("{}+K", CMD_OR_CTRL)
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! 全局快捷键
+//!
+//! 由 Rust 宿主向系统注册，主窗口隐藏或失去焦点时同样有效。
+//! 绑定保存在设置的 `host.shortcuts` 中，可通过命令修改；
+//! 与其他动作重复或被其他应用占用的组合会被拒绝
+
+mod accelerator;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, GlobalShortcutManager, Manager};
+
+use crate::{emergency, AppState};
+
+/// 快捷键对应的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// 紧急停止
+    EmergencyStop,
+    /// 激活/停用控制
+    ToggleActive,
+    /// 暂停/恢复摄像头
+    ToggleCamera,
+    /// 显示/隐藏主窗口
+    ToggleWindow,
+}
+
+impl ShortcutAction {
+    const ALL: [ShortcutAction; 4] = [
+        ShortcutAction::EmergencyStop,
+        ShortcutAction::ToggleActive,
+        ShortcutAction::ToggleCamera,
+        ShortcutAction::ToggleWindow,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ShortcutAction::EmergencyStop => "紧急停止",
+            ShortcutAction::ToggleActive => "激活/停用控制",
+            ShortcutAction::ToggleCamera => "暂停/恢复摄像头",
+            ShortcutAction::ToggleWindow => "显示/隐藏窗口",
+        }
+    }
+
+    fn default_accelerator(self) -> &'static str {
+        match self {
+            ShortcutAction::EmergencyStop => "CmdOrCtrl+Shift+F12",
+            ShortcutAction::ToggleActive => "CmdOrCtrl+Shift+F9",
+            ShortcutAction::ToggleCamera => "CmdOrCtrl+Shift+F10",
+            ShortcutAction::ToggleWindow => "CmdOrCtrl+Shift+F11",
+        }
+    }
+}
+
+/// 快捷键绑定（`None` 表示禁用）
+pub type ShortcutBindings = BTreeMap<ShortcutAction, Option<String>>;
+
+/// 一个动作的绑定与注册状态（`get_shortcuts` 命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutInfo {
+    pub action: ShortcutAction,
+    pub accelerator: Option<String>,
+    /// 是否已向系统注册成功
+    pub registered: bool,
+    /// 注册失败的原因（例如被其他应用占用）
+    pub error: Option<String>,
+}
+
+struct Inner {
+    bindings: ShortcutBindings,
+    /// 注册失败的动作及原因
+    errors: BTreeMap<ShortcutAction, String>,
+}
+
+/// 全局快捷键注册表
+pub struct Shortcuts {
+    inner: Mutex<Inner>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                bindings: default_bindings(),
+                errors: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// 按设置注册全部快捷键（启动时调用）
+    pub fn init(&self, app_handle: &AppHandle, bindings: &ShortcutBindings) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.bindings = bindings.clone();
+        for (&action, accelerator) in bindings {
+            if let Some(accelerator) = accelerator {
+                if let Err(e) = register(app_handle, action, accelerator) {
+                    eprintln!("[Tauri] {}", e);
+                    inner.errors.insert(action, e);
+                }
+            }
+        }
+    }
+
+    /// 当前绑定
+    pub fn bindings(&self) -> ShortcutBindings {
+        self.inner.lock().unwrap().bindings.clone()
+    }
+
+    /// 全部绑定及注册状态
+    pub fn list(&self) -> Vec<ShortcutInfo> {
+        let inner = self.inner.lock().unwrap();
+        ShortcutAction::ALL
+            .iter()
+            .map(|&action| {
+                let accelerator = inner.bindings.get(&action).cloned().flatten();
+                let error = inner.errors.get(&action).cloned();
+                ShortcutInfo {
+                    action,
+                    registered: accelerator.is_some() && error.is_none(),
+                    accelerator,
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// 修改一个动作的快捷键：新组合注册成功后才注销旧组合，失败时保持原设置
+    pub fn set(
+        &self,
+        app_handle: &AppHandle,
+        action: ShortcutAction,
+        accelerator: Option<&str>,
+    ) -> Result<(), String> {
+        let accelerator = accelerator
+            .map(str::trim)
+            .filter(|accelerator| !accelerator.is_empty())
+            .map(accelerator::normalize)
+            .transpose()?;
+        if accelerator.is_none() && action == ShortcutAction::EmergencyStop {
+            return Err("紧急停止快捷键不能禁用".to_string());
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let current = inner.bindings.get(&action).cloned().flatten();
+        let registered = current.is_some() && !inner.errors.contains_key(&action);
+        if current == accelerator && (registered || accelerator.is_none()) {
+            return Ok(());
+        }
+
+        if let Some(accelerator) = &accelerator {
+            if let Some(other) = conflicting_action(&inner.bindings, action, accelerator) {
+                return Err(format!(
+                    "快捷键 {} 已用于「{}」",
+                    accelerator,
+                    other.label()
+                ));
+            }
+            // 与原组合相同时是重试之前失败的注册
+            register(app_handle, action, accelerator)?;
+        }
+        if let Some(current) =
+            current.filter(|current| registered && accelerator.as_ref() != Some(current))
+        {
+            unregister(app_handle, &current);
+        }
+
+        println!(
+            "[Tauri] 快捷键「{}」: {}",
+            action.label(),
+            accelerator.as_deref().unwrap_or("已禁用")
+        );
+        inner.errors.remove(&action);
+        inner.bindings.insert(action, accelerator);
+        Ok(())
+    }
+
+    /// 替换全部绑定（已校验），只重新注册有变化或之前注册失败的动作；
+    /// 注册失败记录在状态中而不返回错误
+    pub fn apply(&self, app_handle: &AppHandle, bindings: &ShortcutBindings) {
+        let mut inner = self.inner.lock().unwrap();
+        let changed: Vec<ShortcutAction> = ShortcutAction::ALL
+            .into_iter()
+            .filter(|action| {
+                inner.bindings.get(action) != bindings.get(action)
+                    || inner.errors.contains_key(action)
+            })
+            .collect();
+
+        // 先全部注销再注册，避免动作之间互换组合时冲突
+        for action in &changed {
+            let current = inner.bindings.get(action).cloned().flatten();
+            if let Some(current) = current {
+                if inner.errors.remove(action).is_none() {
+                    unregister(app_handle, &current);
+                }
+            }
+        }
+        for action in changed {
+            let accelerator = bindings.get(&action).cloned().flatten();
+            if let Some(accelerator) = accelerator {
+                if let Err(e) = register(app_handle, action, &accelerator) {
+                    eprintln!("[Tauri] {}", e);
+                    inner.errors.insert(action, e);
+                }
+            }
+        }
+        inner.bindings = bindings.clone();
+    }
+}
+
+/// 默认绑定
+pub fn default_bindings() -> ShortcutBindings {
+    ShortcutAction::ALL
+        .iter()
+        .map(|&action| (action, Some(action.default_accelerator().to_string())))
+        .collect()
+}
+
+/// 校验绑定：组合格式有效、动作之间不重复、紧急停止不能禁用
+pub fn validate_bindings(bindings: &ShortcutBindings) -> Result<(), String> {
+    if bindings
+        .get(&ShortcutAction::EmergencyStop)
+        .is_none_or(Option::is_none)
+    {
+        return Err("紧急停止快捷键不能禁用".to_string());
+    }
+
+    let mut seen: BTreeMap<String, ShortcutAction> = BTreeMap::new();
+    for (&action, accelerator) in bindings {
+        let Some(accelerator) = accelerator else {
+            continue;
+        };
+        let resolved = accelerator::resolve(accelerator)?;
+        if let Some(other) = seen.insert(resolved, action) {
+            return Err(format!(
+                "「{}」与「{}」使用了相同的快捷键 {}",
+                other.label(),
+                action.label(),
+                accelerator
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 找出已使用同一组合的其他动作
+fn conflicting_action(
+    bindings: &ShortcutBindings,
+    action: ShortcutAction,
+    accelerator: &str,
+) -> Option<ShortcutAction> {
+    let accelerator = accelerator::resolve(accelerator).ok()?;
+    bindings.iter().find_map(|(&other, bound)| {
+        let bound = bound.as_deref()?;
+        let same = accelerator::resolve(bound).is_ok_and(|bound| bound == accelerator);
+        (other != action && same).then_some(other)
+    })
+}
+
+/// 向系统注册（组合被其他应用占用时失败）
+fn register(
+    app_handle: &AppHandle,
+    action: ShortcutAction,
+    accelerator: &str,
+) -> Result<(), String> {
+    let handle = app_handle.clone();
+    app_handle
+        .global_shortcut_manager()
+        .register(accelerator, move || on_shortcut(&handle, action))
+        .map_err(|e| {
+            format!(
+                "无法注册「{}」快捷键 {}（可能已被其他应用占用）: {}",
+                action.label(),
+                accelerator,
+                e
+            )
+        })
+}
+
+fn unregister(app_handle: &AppHandle, accelerator: &str) {
+    if let Err(e) = app_handle.global_shortcut_manager().unregister(accelerator) {
+        eprintln!("[Tauri] 无法注销快捷键 {}: {}", accelerator, e);
+    }
+}
+
+/// 快捷键触发
+fn on_shortcut(app_handle: &AppHandle, action: ShortcutAction) {
+    let state = app_handle.state::<AppState>();
+    let view = state.tray.view();
+
+    let result = match action {
+        ShortcutAction::EmergencyStop => {
+            emergency::trigger(app_handle);
+            Ok(())
+        }
+        ShortcutAction::ToggleActive => state.bridge.set_active(!view.active),
+        ShortcutAction::ToggleCamera => state.bridge.set_camera_paused(!view.camera_paused),
+        ShortcutAction::ToggleWindow => {
+            match app_handle.get_window("main") {
+                Some(window) if window.is_visible().unwrap_or(false) => {
+                    let _ = window.hide();
+                }
+                _ => crate::show_main_window(app_handle),
+            }
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("[Tauri] 快捷键「{}」执行失败: {}", action.label(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 默认绑定中替换一个动作
+    fn with(action: ShortcutAction, accelerator: Option<&str>) -> ShortcutBindings {
+        let mut bindings = default_bindings();
+        bindings.insert(action, accelerator.map(str::to_string));
+        bindings
+    }
+
+    #[test]
+    fn default_bindings_are_valid() {
+        assert_eq!(validate_bindings(&default_bindings()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_duplicate_and_disabled_bindings() {
+        let cases = [
+            (
+                with(ShortcutAction::ToggleWindow, Some("shift+cmdorctrl+f9")),
+                "「激活/停用控制」与「显示/隐藏窗口」使用了相同的快捷键 shift+cmdorctrl+f9",
+            ),
+            (
+                with(ShortcutAction::EmergencyStop, None),
+                "紧急停止快捷键不能禁用",
+            ),
+            (
+                with(ShortcutAction::ToggleCamera, Some("F10")),
+                "全局快捷键至少需要一个修饰键: F10",
+            ),
+        ];
+        for (bindings, expected) in cases {
+            assert_eq!(validate_bindings(&bindings).unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn cmd_or_ctrl_conflicts_with_platform_modifier() {
+        let platform = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Ctrl"
+        };
+        let accelerator = format!("{}+Shift+F9", platform);
+        let bindings = with(ShortcutAction::ToggleWindow, Some(&accelerator));
+        assert!(validate_bindings(&bindings).is_err());
+        assert_eq!(
+            conflicting_action(
+                &default_bindings(),
+                ShortcutAction::ToggleWindow,
+                &accelerator
+            ),
+            Some(ShortcutAction::ToggleActive)
+        );
+        // 同一动作改为等价写法不算冲突
+        assert_eq!(
+            conflicting_action(
+                &default_bindings(),
+                ShortcutAction::ToggleActive,
+                &accelerator
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! 单实例
+//!
+//! 第一个实例持有锁文件并在本地端口上监听；后续启动检测到锁被占用时，
+//! 把命令行参数转发给运行中的实例后退出，由运行中的实例显示并聚焦主窗口
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// 锁文件名（整个进程生命周期内持有排他锁）
+const LOCK_FILE: &str = "instance.lock";
+
+/// 记录监听端口的文件名（Windows 下被锁定的文件无法被其他进程读取，故单独存放）
+const PORT_FILE: &str = "instance.port";
+
+/// 连接运行中实例的重试次数（对方可能尚未写入端口）
+const CONNECT_RETRIES: u32 = 20;
+
+/// 两次连接重试之间的间隔
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 单次读写的超时时间
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 转发给运行中实例的启动信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    /// 命令行参数（含程序路径）
+    pub args: Vec<String>,
+    /// 启动时的工作目录
+    pub cwd: String,
+}
+
+/// 检测结果
+pub enum Instance {
+    /// 当前是唯一实例，需在应用启动后调用 [`PrimaryInstance::listen`]
+    Primary(PrimaryInstance),
+    /// 已有实例在运行，参数已转发
+    Forwarded,
+}
+
+/// 主实例：持有锁文件与监听端口
+pub struct PrimaryInstance {
+    lock: File,
+    listener: TcpListener,
+}
+
+/// 检测是否已有实例在运行；已有时转发参数
+pub fn acquire(dir: &Path) -> Result<Instance, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("无法创建目录 {}: {}", dir.display(), e))?;
+
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))
+        .map_err(|e| format!("无法打开锁文件: {}", e))?;
+
+    match lock.try_lock() {
+        Ok(()) => {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+                .map_err(|e| format!("无法监听单实例端口: {}", e))?;
+            let port = listener
+                .local_addr()
+                .map_err(|e| format!("无法获取单实例端口: {}", e))?
+                .port();
+            fs::write(dir.join(PORT_FILE), port.to_string())
+                .map_err(|e| format!("无法写入单实例端口: {}", e))?;
+
+            Ok(Instance::Primary(PrimaryInstance { lock, listener }))
+        }
+        Err(TryLockError::WouldBlock) => {
+            forward(&dir.join(PORT_FILE), &current_request())?;
+            Ok(Instance::Forwarded)
+        }
+        Err(TryLockError::Error(e)) => Err(format!("无法锁定锁文件: {}", e)),
+    }
+}
+
+/// 单实例文件所在目录
+pub fn instance_dir(identifier: &str) -> PathBuf {
+    tauri::api::path::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(identifier)
+}
+
+impl PrimaryInstance {
+    /// 在后台线程接收后续启动转发的参数
+    pub fn listen(self, app_handle: AppHandle) {
+        let PrimaryInstance { lock, listener } = self;
+        thread::spawn(move || {
+            // 锁随监听线程存活到进程结束
+            let _lock = lock;
+            for stream in listener.incoming().flatten() {
+                match receive(stream) {
+                    Ok(request) => {
+                        println!("[Tauri] 收到新实例启动请求: {:?}", request.args);
+                        crate::show_main_window(&app_handle);
+                        let _ = app_handle.emit_all("single-instance", request);
+                    }
+                    Err(e) => eprintln!("[Tauri] 处理新实例请求失败: {}", e),
+                }
+            }
+        });
+    }
+}
+
+fn current_request() -> LaunchRequest {
+    LaunchRequest {
+        args: std::env::args().collect(),
+        cwd: std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// 把启动信息发给运行中的实例，等待其确认
+fn forward(port_file: &Path, request: &LaunchRequest) -> Result<(), String> {
+    let mut last_error = String::new();
+    for _ in 0..CONNECT_RETRIES {
+        match try_forward(port_file, request) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+        thread::sleep(RETRY_INTERVAL);
+    }
+    Err(format!("无法连接运行中的实例: {}", last_error))
+}
+
+fn try_forward(port_file: &Path, request: &LaunchRequest) -> Result<(), String> {
+    let port: u16 = fs::read_to_string(port_file)
+        .map_err(|e| e.to_string())?
+        .trim()
+        .parse()
+        .map_err(|e| format!("端口无效: {}", e))?;
+
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    if reply.trim() != "ok" {
+        return Err("运行中的实例未确认".to_string());
+    }
+    Ok(())
+}
+
+fn receive(stream: TcpStream) -> Result<LaunchRequest, String> {
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    let request = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+
+    let mut stream = stream;
+    stream.write_all(b"ok\n").map_err(|e| e.to_string())?;
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_launch_is_forwarded_to_primary() {
+        let dir = tempfile::tempdir().unwrap();
+        let Instance::Primary(primary) = acquire(dir.path()).unwrap() else {
+            panic!("第一个实例应为主实例");
+        };
+        let receiver = thread::spawn(move || {
+            let (stream, _) = primary.listener.accept().unwrap();
+            // 主实例在确认后仍持有锁
+            (receive(stream), primary)
+        });
+
+        assert!(matches!(acquire(dir.path()).unwrap(), Instance::Forwarded));
+        let (request, _primary) = receiver.join().unwrap();
+        let request = request.unwrap();
+        assert_eq!(request.args, current_request().args);
+        assert_eq!(request.cwd, current_request().cwd);
+    }
+
+    #[test]
+    fn lock_is_released_with_primary() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = acquire(dir.path()).unwrap();
+        assert!(matches!(first, Instance::Primary(_)));
+        drop(first);
+        assert!(matches!(acquire(dir.path()).unwrap(), Instance::Primary(_)));
+    }
+}
@@ -0,0 +1,156 @@
+//! 托盘图标
+//!
+//! 按控制状态在运行时绘制图标：窗口隐藏时托盘图标是唯一的反馈，
+//! 必须一眼就能看出手势控制是否即将操作鼠标键盘
+
+use tauri::Icon;
+
+use super::TrayView;
+use crate::backend::BackendState;
+
+/// 图标边长（像素）
+const SIZE: u32 = 32;
+
+/// 每个像素的采样数（每边），用于抗锯齿
+const SUPERSAMPLE: u32 = 4;
+
+const RED: [u8; 3] = [0xe5, 0x39, 0x35];
+const AMBER: [u8; 3] = [0xf5, 0xa6, 0x23];
+const GREEN: [u8; 3] = [0x22, 0xc5, 0x5e];
+const LIGHT: [u8; 3] = [0xe0, 0xe0, 0xe0];
+const DARK: [u8; 3] = [0x3a, 0x3a, 0x3a];
+
+/// 图标状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconState {
+    /// 后端未运行或已崩溃
+    BackendDown,
+    /// 后端启动中或宿主尚未连上
+    Starting,
+    /// 控制未激活
+    Idle,
+    /// 控制已激活，没有识别到手势
+    Active,
+    /// 控制已激活且正在执行手势（颜色与手势对应）
+    Gesture([u8; 3]),
+    /// 紧急停止时闪烁
+    Alert,
+}
+
+impl IconState {
+    pub fn from_view(view: &TrayView) -> Self {
+        match view.backend {
+            BackendState::Stopped | BackendState::Crashed => return IconState::BackendDown,
+            BackendState::Starting | BackendState::Running => return IconState::Starting,
+            BackendState::Ready => {}
+        }
+        if !view.connected {
+            return IconState::Starting;
+        }
+        if !view.active {
+            return IconState::Idle;
+        }
+        match view.gesture.as_deref() {
+            Some(gesture) if !view.camera_paused => IconState::Gesture(gesture_color(gesture)),
+            _ => IconState::Active,
+        }
+    }
+
+    /// 绘制图标
+    pub fn render(self) -> Icon {
+        // (填充色, 外环颜色, 外环宽度)
+        let (fill, ring, ring_width) = match self {
+            IconState::BackendDown => (Some(DARK), RED, 3.0),
+            IconState::Starting => (None, AMBER, 4.0),
+            IconState::Idle => (Some(gesture_color("idle")), LIGHT, 2.5),
+            IconState::Active => (Some(GREEN), LIGHT, 2.5),
+            IconState::Gesture(color) => (Some(color), GREEN, 4.0),
+            IconState::Alert => (Some(RED), LIGHT, 2.5),
+        };
+        Icon::Rgba {
+            rgba: draw_badge(fill, ring, ring_width),
+            width: SIZE,
+            height: SIZE,
+        }
+    }
+}
+
+/// 托盘提示文字
+pub fn tooltip(view: &TrayView) -> String {
+    if let Some(failure) = &view.failure {
+        return format!("PhantomHand - {}", failure);
+    }
+    let state = match IconState::from_view(view) {
+        IconState::BackendDown => "后端未运行".to_string(),
+        IconState::Starting => "正在启动".to_string(),
+        IconState::Idle => "待机（控制未激活）".to_string(),
+        IconState::Active if view.camera_paused => "控制已激活（摄像头已暂停）".to_string(),
+        IconState::Active => "控制已激活".to_string(),
+        IconState::Alert => "紧急停止".to_string(),
+        IconState::Gesture(_) => format!(
+            "控制已激活 - 手势: {}",
+            view.gesture.as_deref().unwrap_or_default()
+        ),
+    };
+    format!("PhantomHand - {}", state)
+}
+
+/// 手势颜色（与前端 `GESTURE_COLORS` 保持一致）
+fn gesture_color(gesture: &str) -> [u8; 3] {
+    match gesture {
+        "idle" => [0x66, 0x66, 0x66],
+        "open" => [0x00, 0xff, 0xff],
+        "fist" => [0xff, 0x66, 0x00],
+        "pinch" => [0x00, 0xff, 0x00],
+        "point" => [0xff, 0xff, 0x00],
+        "victory" => [0xff, 0x00, 0xff],
+        "ok" => [0x00, 0xff, 0x88],
+        _ => [0xff, 0xff, 0xff],
+    }
+}
+
+/// 绘制带外环的圆形徽标，返回 RGBA 像素
+fn draw_badge(fill: Option<[u8; 3]>, ring: [u8; 3], ring_width: f32) -> Vec<u8> {
+    let center = SIZE as f32 / 2.0;
+    let outer = center - 1.0;
+    let inner = outer - ring_width;
+    let step = 1.0 / SUPERSAMPLE as f32;
+    let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            // 统计落在外环与内圆中的子采样点
+            let (mut ring_hits, mut fill_hits) = (0.0, 0.0);
+            for sy in 0..SUPERSAMPLE {
+                for sx in 0..SUPERSAMPLE {
+                    let px = x as f32 + (sx as f32 + 0.5) * step - center;
+                    let py = y as f32 + (sy as f32 + 0.5) * step - center;
+                    let distance = (px * px + py * py).sqrt();
+                    if distance <= inner {
+                        fill_hits += 1.0;
+                    } else if distance <= outer {
+                        ring_hits += 1.0;
+                    }
+                }
+            }
+
+            let fill_alpha = if fill.is_some() { fill_hits } else { 0.0 };
+            let alpha = (ring_hits + fill_alpha) / samples;
+            if alpha == 0.0 {
+                rgba.extend_from_slice(&[0, 0, 0, 0]);
+                continue;
+            }
+
+            // 按覆盖比例混合外环与填充色
+            let fill_color = fill.unwrap_or(ring);
+            let total = ring_hits + fill_alpha;
+            let mix = |i: usize| {
+                ((ring[i] as f32 * ring_hits + fill_color[i] as f32 * fill_alpha) / total).round()
+                    as u8
+            };
+            rgba.extend_from_slice(&[mix(0), mix(1), mix(2), (alpha * 255.0).round() as u8]);
+        }
+    }
+    rgba
+}
@@ -0,0 +1,279 @@
+//! 系统托盘
+//!
+//! 菜单实时显示后端健康、控制激活状态与当前手势，并提供激活/停用、暂停摄像头、
+//! 重启后端和切换手势配置等操作。状态变化时通过托盘句柄原地更新菜单项，
+//! 窗口隐藏时也能随时停用手势控制。托盘图标与提示文字同样随状态切换（见 [`icon`]）
+
+mod icon;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu,
+};
+
+use crate::backend::BackendState;
+use crate::focus::ActiveProfile;
+use crate::profiles::{self, BUILTIN_PROFILES};
+use crate::AppState;
+use icon::IconState;
+
+/// 闪烁次数
+const FLASH_COUNT: u32 = 4;
+
+/// 闪烁时每次亮/灭的时长
+const FLASH_INTERVAL: Duration = Duration::from_millis(150);
+
+/// 手势配置菜单项 ID 前缀
+const PROFILE_PREFIX: &str = "profile:";
+
+/// 根据状态生成菜单项文字
+type LabelFn = fn(&TrayView) -> String;
+
+/// 托盘显示的状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayView {
+    pub backend: BackendState,
+    /// 宿主是否已连接后端 WebSocket
+    pub connected: bool,
+    pub active: bool,
+    pub camera_paused: bool,
+    /// 当前主导手势（无手或 idle 时为空）
+    pub gesture: Option<String>,
+    /// 托盘中选择的手势配置（`host.profile`）
+    pub profile: String,
+    /// 实际生效的手势配置（可能由前台应用决定）
+    pub active_profile: ActiveProfile,
+    /// 后端放弃重启或紧急停止未能结束后端时的错误信息
+    pub failure: Option<String>,
+}
+
+/// 托盘菜单控制器
+pub struct TrayController {
+    view: Mutex<TrayView>,
+}
+
+impl TrayController {
+    pub fn new() -> Self {
+        Self {
+            view: Mutex::new(TrayView {
+                backend: BackendState::Stopped,
+                connected: false,
+                active: false,
+                camera_paused: false,
+                gesture: None,
+                profile: profiles::DEFAULT_PROFILE.to_string(),
+                active_profile: ActiveProfile {
+                    profile: profiles::DEFAULT_PROFILE.to_string(),
+                    app: None,
+                },
+                failure: None,
+            }),
+        }
+    }
+
+    /// 当前状态
+    pub fn view(&self) -> TrayView {
+        self.view.lock().unwrap().clone()
+    }
+
+    /// 修改状态，有变化时更新菜单
+    pub fn update(&self, app_handle: &AppHandle, f: impl FnOnce(&mut TrayView)) {
+        let mut view = self.view.lock().unwrap();
+        let before = view.clone();
+        f(&mut view);
+        if *view != before {
+            apply(app_handle, &before, &view);
+        }
+    }
+
+    /// 按当前状态设置托盘图标与提示文字（启动时调用一次，替换配置中的静态图标）
+    pub fn refresh(&self, app_handle: &AppHandle) {
+        let view = self.view.lock().unwrap();
+        let tray = app_handle.tray_handle();
+        let _ = tray.set_icon(IconState::from_view(&view).render());
+        let _ = tray.set_tooltip(&icon::tooltip(&view));
+    }
+
+    /// 闪烁托盘图标（紧急停止时提示），结束后恢复为当前状态的图标
+    pub fn flash(&self, app_handle: &AppHandle) {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let tray = app_handle.tray_handle();
+            for _ in 0..FLASH_COUNT {
+                let _ = tray.set_icon(IconState::Alert.render());
+                tokio::time::sleep(FLASH_INTERVAL).await;
+                app_handle.state::<AppState>().tray.refresh(&app_handle);
+                tokio::time::sleep(FLASH_INTERVAL).await;
+            }
+        });
+    }
+}
+
+/// 创建托盘菜单（初始状态，之后由 [`TrayController::update`] 原地更新）
+pub fn create_menu() -> SystemTrayMenu {
+    let initial = TrayController::new().view();
+
+    let mut profile_menu = SystemTrayMenu::new();
+    for profile in BUILTIN_PROFILES {
+        let mut item = CustomMenuItem::new(profile_item_id(profile.id), profile.name);
+        if profile.id == initial.profile {
+            item = item.selected();
+        }
+        profile_menu = profile_menu.add_item(item);
+    }
+
+    SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("backend_status", backend_label(&initial)).disabled())
+        .add_item(CustomMenuItem::new("control_status", control_label(&initial)).disabled())
+        .add_item(CustomMenuItem::new("gesture_status", gesture_label(&initial)).disabled())
+        .add_item(CustomMenuItem::new("profile_status", profile_label(&initial)).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("toggle_active", toggle_label(&initial)).disabled())
+        .add_item(CustomMenuItem::new("pause_camera", camera_label(&initial)).disabled())
+        .add_item(CustomMenuItem::new("restart_backend", "重启后端"))
+        .add_submenu(SystemTraySubmenu::new("手势配置", profile_menu))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("show", "显示窗口"))
+        .add_item(CustomMenuItem::new("hide", "隐藏窗口"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit", "退出"))
+}
+
+/// 处理本模块负责的菜单项
+pub fn on_menu_item_click(app_handle: &AppHandle, id: &str) {
+    let state = app_handle.state::<AppState>();
+    let view = state.tray.view();
+
+    let result = match id {
+        "toggle_active" => state.bridge.set_active(!view.active),
+        "pause_camera" => state.bridge.set_camera_paused(!view.camera_paused),
+        "restart_backend" => {
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if let Err(e) = state.backend.restart(&app_handle).await {
+                    eprintln!("[Tauri] 重启后端失败: {}", e);
+                }
+            });
+            Ok(())
+        }
+        _ => match id.strip_prefix(PROFILE_PREFIX) {
+            Some(profile) => crate::set_profile(app_handle, profile),
+            None => Ok(()),
+        },
+    };
+
+    if let Err(e) = result {
+        eprintln!("[Tauri] 托盘操作失败: {}", e);
+    }
+}
+
+/// 把状态变化同步到菜单项
+fn apply(app_handle: &AppHandle, before: &TrayView, view: &TrayView) {
+    let tray = app_handle.tray_handle();
+    let set_enabled = |id: &str, enabled: bool| {
+        if let Some(item) = tray.try_get_item(id) {
+            let _ = item.set_enabled(enabled);
+        }
+    };
+
+    // 只更新文字有变化的菜单项（手势每帧都可能变化）
+    let labels: [(&str, LabelFn); 6] = [
+        ("backend_status", backend_label),
+        ("control_status", control_label),
+        ("gesture_status", gesture_label),
+        ("profile_status", profile_label),
+        ("toggle_active", toggle_label),
+        ("pause_camera", camera_label),
+    ];
+    for (id, label) in labels {
+        let title = label(view);
+        if title != label(before) {
+            if let Some(item) = tray.try_get_item(id) {
+                let _ = item.set_title(title);
+            }
+        }
+    }
+
+    if before.connected != view.connected {
+        set_enabled("toggle_active", view.connected);
+        set_enabled("pause_camera", view.connected);
+    }
+
+    // 图标每帧都可能随手势变化，只在状态切换时重新绘制
+    let icon_state = IconState::from_view(view);
+    if icon_state != IconState::from_view(before) {
+        let _ = tray.set_icon(icon_state.render());
+    }
+    let tooltip = icon::tooltip(view);
+    if tooltip != icon::tooltip(before) {
+        let _ = tray.set_tooltip(&tooltip);
+    }
+
+    if before.profile != view.profile {
+        for profile in BUILTIN_PROFILES {
+            if let Some(item) = tray.try_get_item(&profile_item_id(profile.id)) {
+                let _ = item.set_selected(profile.id == view.profile);
+            }
+        }
+    }
+}
+
+fn profile_item_id(profile: &str) -> String {
+    format!("{}{}", PROFILE_PREFIX, profile)
+}
+
+fn backend_label(view: &TrayView) -> String {
+    let state = match view.backend {
+        BackendState::Starting | BackendState::Running => "启动中",
+        BackendState::Ready => "正常",
+        BackendState::Crashed => "已崩溃",
+        BackendState::Stopped => "未运行",
+    };
+    format!("后端：{}", state)
+}
+
+fn control_label(view: &TrayView) -> String {
+    let state = match (view.connected, view.active) {
+        (false, _) => "未连接",
+        (true, true) => "已激活",
+        (true, false) => "未激活",
+    };
+    format!("控制：{}", state)
+}
+
+fn gesture_label(view: &TrayView) -> String {
+    if view.camera_paused {
+        return "手势：摄像头已暂停".to_string();
+    }
+    format!("手势：{}", view.gesture.as_deref().unwrap_or("无"))
+}
+
+fn profile_label(view: &TrayView) -> String {
+    let active = &view.active_profile;
+    let name = profiles::find(&active.profile).map_or(active.profile.as_str(), |p| p.name);
+    match &active.app {
+        Some(app) => format!("配置：{}（{}）", name, app),
+        None => format!("配置：{}", name),
+    }
+}
+
+fn toggle_label(view: &TrayView) -> String {
+    let label = if view.active {
+        "停用控制"
+    } else {
+        "激活控制"
+    };
+    label.to_string()
+}
+
+fn camera_label(view: &TrayView) -> String {
+    let label = if view.camera_paused {
+        "恢复摄像头"
+    } else {
+        "暂停摄像头"
+    };
+    label.to_string()
+}
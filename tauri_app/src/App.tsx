@@ -2,24 +2,59 @@
  * PhantomHand 主应用组件
  */
 
-import { useEffect } from 'react'
+import { useEffect, useState } from 'react'
 import { Canvas } from '@react-three/fiber'
 import { EffectComposer, Bloom } from '@react-three/postprocessing'
+import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
 
 import { HandVisualization } from './canvas/HandVisualization'
 import { StatusPanel } from './components/StatusPanel'
 import { ControlPanel } from './components/ControlPanel'
 import { CameraPreview } from './components/CameraPreview'
 import { useHandStore } from './stores/handStore'
+import { BackendEndpoints, BackendStatus } from './types'
+import { FrameData } from './types/protocol'
+
+// 浏览器开发模式下使用后端默认端口
+const DEFAULT_WS_URL = 'ws://127.0.0.1:8765'
+
+// 是否运行在 Tauri 中（浏览器开发模式下没有 IPC）
+const isTauri = '__TAURI_IPC__' in window
 
 function App() {
   const { connect, disconnect, isConnected } = useHandStore()
+  const [streamUrl, setStreamUrl] = useState<string | undefined>(undefined)
 
-  // 连接 WebSocket
+  // 连接 WebSocket（Tauri 中等待后端就绪后，按后端分配的端口连接）
   useEffect(() => {
-    connect('ws://127.0.0.1:8765')
+    if (!isTauri) {
+      connect(DEFAULT_WS_URL)
+      return () => {
+        disconnect()
+      }
+    }
+
+    const connectToBackend = async () => {
+      const endpoints = await invoke<BackendEndpoints>('get_backend_endpoints')
+      setStreamUrl(endpoints.stream_url)
+      connect(endpoints.ws_url)
+    }
+
+    const unlisten = listen('backend-ready', () => connectToBackend())
+    // 宿主转发的帧经过关键点滤波，可视化优先使用
+    const unlistenFrame = listen<FrameData>('bridge-frame', (event) => {
+      useHandStore.getState().applyHostFrame(event.payload)
+    })
+    invoke<BackendStatus>('get_backend_status').then((status) => {
+      if (status.state === 'ready') {
+        connectToBackend()
+      }
+    })
 
     return () => {
+      unlisten.then((fn) => fn())
+      unlistenFrame.then((fn) => fn())
       disconnect()
     }
   }, [connect, disconnect])
@@ -57,7 +92,7 @@ function App() {
         <ControlPanel />
 
         {/* 摄像头预览 */}
-        <CameraPreview />
+        {(!isTauri || streamUrl) && <CameraPreview streamUrl={streamUrl} />}
 
         {/* 连接状态指示器 */}
         <div className={`connection-indicator ${isConnected ? 'connected' : 'disconnected'}`}>
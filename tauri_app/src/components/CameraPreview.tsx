@@ -70,6 +70,11 @@ export function CameraPreview({ streamUrl = 'http://127.0.0.1:8766/stream' }: Ca
     }
   }, [isDragging])
 
+  // Stream URL changes when the backend restarts on new ports
+  useEffect(() => {
+    setHasError(false)
+  }, [streamUrl])
+
   const handleRetry = () => {
     setHasError(false)
   }
@@ -5,7 +5,8 @@
 
 import { create } from 'zustand'
 import { Vector3 } from 'three'
-import { HandLandmarks, FrameData, GestureEvent } from '../types'
+import { GestureEvent } from '../types'
+import { ClientMessage, Envelope, FrameData, HandData, ServerMessage } from '../types/protocol'
 
 // 将 2D 归一化坐标转换为 3D 空间坐标
 function convertLandmarksTo3D(landmarks: number[][]): Vector3[] {
@@ -47,6 +48,9 @@ interface HandStore {
   // 防止状态覆盖的标志
   _activeOverrideUntil: number
 
+  // 在此之前使用宿主转发的（经过关键点滤波的）帧，忽略直连后端收到的关键点
+  _hostFrameUntil: number
+
   // 最近的手势事件
   lastEvent: GestureEvent | null
 
@@ -54,6 +58,7 @@ interface HandStore {
   connect: (url: string) => void
   disconnect: () => void
   setActive: (active: boolean) => void
+  applyHostFrame: (frame: FrameData) => void
 
   // 直接获取手部数据（用于渲染循环）
   getLeftHand: () => HandState | null
@@ -71,6 +76,7 @@ export const useHandStore = create<HandStore>((set, get) => ({
   inferenceTime: 0,
   isActive: false,
   _activeOverrideUntil: 0,
+  _hostFrameUntil: 0,
   lastEvent: null,
 
   // 连接 WebSocket
@@ -107,7 +113,7 @@ export const useHandStore = create<HandStore>((set, get) => ({
 
     ws.onmessage = (event) => {
       try {
-        const message = JSON.parse(event.data)
+        const message = JSON.parse(event.data) as Envelope<ServerMessage>
         handleMessage(message, set, get)
       } catch (e) {
         console.error('[WS] 解析消息失败:', e)
@@ -128,25 +134,59 @@ export const useHandStore = create<HandStore>((set, get) => ({
 
   // 设置激活状态
   setActive: (active: boolean) => {
-    const ws = get().ws
-    if (ws && ws.readyState === WebSocket.OPEN) {
-      ws.send(JSON.stringify({
-        type: 'set_active',
-        data: { active }
-      }))
-    }
+    sendMessage(get().ws, { type: 'set_active', data: { active } })
     // 设置覆盖保护，500ms 内忽略后端状态更新
     set({ isActive: active, _activeOverrideUntil: Date.now() + 500 })
   },
 
+  // 宿主转发的帧 (bridge-frame 事件)，关键点已按 host.filter 滤波
+  applyHostFrame: (frame: FrameData) => {
+    updateHands(frame.hands, get)
+    get()._hostFrameUntil = Date.now() + 500
+  },
+
   // 直接获取方法（用于渲染循环，避免订阅）
   getLeftHand: () => get().leftHand,
   getRightHand: () => get().rightHand,
 }))
 
+// 发送消息（类型由协议定义生成）
+function sendMessage(ws: WebSocket | null, message: ClientMessage) {
+  if (ws && ws.readyState === WebSocket.OPEN) {
+    const envelope: Envelope<ClientMessage> = { ...message, timestamp: Date.now() }
+    ws.send(JSON.stringify(envelope))
+  }
+}
+
+// 更新手部数据
+function updateHands(hands: HandData[], get: () => HandStore) {
+  let leftHand: HandState | null = null
+  let rightHand: HandState | null = null
+
+  for (const hand of hands) {
+    const handState: HandState = {
+      landmarks: convertLandmarksTo3D(hand.landmarks),
+      gesture: hand.gesture,
+      gestureScore: hand.gesture_score || 0,
+      state: hand.state
+    }
+
+    if (hand.handedness === 'Left') {
+      leftHand = handState
+    } else {
+      rightHand = handState
+    }
+  }
+
+  // 直接修改状态对象，避免重渲染
+  const store = get()
+  store.leftHand = leftHand
+  store.rightHand = rightHand
+}
+
 // 处理 WebSocket 消息
 function handleMessage(
-  message: { type: string; timestamp: number; data: Record<string, unknown> },
+  message: Envelope<ServerMessage>,
   set: (state: Partial<HandStore>) => void,
   get: () => HandStore
 ) {
@@ -154,62 +194,38 @@ function handleMessage(
     case 'connected': {
       console.log('[WS] 收到欢迎消息:', message.data)
       // Sync active state from server
-      const activeState = message.data.active as boolean
-      if (activeState !== undefined) {
-        set({ isActive: activeState })
-      }
+      set({ isActive: message.data.active })
       break
     }
 
     case 'frame_data': {
       // Backend uses snake_case, need to map to camelCase
-      const rawData = message.data as Record<string, unknown>
-      const hands = rawData.hands as Array<Record<string, unknown>> || []
-
-      // 更新手部数据
-      let leftHand: HandState | null = null
-      let rightHand: HandState | null = null
-
-      for (const hand of hands) {
-        const handState: HandState = {
-          landmarks: convertLandmarksTo3D(hand.landmarks as number[][]),
-          gesture: hand.gesture as string,
-          gestureScore: (hand.gesture_score as number) || 0,
-          state: hand.state as string
-        }
-
-        if (hand.handedness === 'Left') {
-          leftHand = handState
-        } else {
-          rightHand = handState
-        }
+      const rawData = message.data
+      // 宿主正在转发滤波后的帧时以宿主为准
+      if (Date.now() >= get()._hostFrameUntil) {
+        updateHands(rawData.hands || [], get)
       }
 
-      // 直接修改状态对象，避免重渲染
-      const store = get()
-      store.leftHand = leftHand
-      store.rightHand = rightHand
-
       // 只更新统计信息（这会触发订阅了这些值的组件重渲染）
       // 注意：不再从 frame_data 更新 isActive，避免覆盖用户的设置
       // isActive 只在连接时从 welcome 消息同步，之后由用户控制
       set({
-        inferenceTime: (rawData.inference_time_ms as number) || 0
+        inferenceTime: rawData.inference_time_ms || 0
       })
       break
     }
 
     case 'gesture_event': {
       // Backend uses snake_case, map to camelCase
-      const rawEvent = message.data as Record<string, unknown>
+      const rawEvent = message.data
       const event: GestureEvent = {
-        eventType: rawEvent.event_type as GestureEvent['eventType'],
-        gesture: rawEvent.gesture as string,
-        handId: rawEvent.hand_id as string,
-        timestamp: rawEvent.timestamp as number,
-        holdDuration: rawEvent.hold_duration as number,
-        confidence: rawEvent.confidence as number,
-        meta: rawEvent.meta as Record<string, unknown>
+        eventType: rawEvent.event_type,
+        gesture: rawEvent.gesture,
+        handId: rawEvent.hand_id,
+        timestamp: rawEvent.timestamp,
+        holdDuration: rawEvent.hold_duration,
+        confidence: rawEvent.confidence,
+        meta: rawEvent.meta
       }
       console.log('[EVENT]', event.eventType, event.gesture)
       set({ lastEvent: event })
@@ -218,28 +234,30 @@ function handleMessage(
 
     case 'active_changed': {
       // 后端广播的状态变更，同步到所有窗口
-      const active = message.data.active as boolean
+      const active = message.data.active
       console.log('[WS] Active state changed:', active)
       set({ isActive: active })
       break
     }
 
+    case 'camera_paused':
+      console.log('[WS] Camera paused:', message.data.paused)
+      break
+
+    case 'config_ack':
+      // 只回复给发起 config_update 的宿主，界面通过 settings-applied 事件获知结果
+      break
+
     case 'pong':
       // 心跳响应
       break
 
     default:
-      console.log('[WS] 未知消息类型:', message.type)
+      console.log('[WS] 未知消息类型:', (message as { type: string }).type)
   }
 }
 
 // 心跳定时器
 setInterval(() => {
-  const store = useHandStore.getState()
-  if (store.ws && store.ws.readyState === WebSocket.OPEN) {
-    store.ws.send(JSON.stringify({
-      type: 'ping',
-      timestamp: Date.now()
-    }))
-  }
+  sendMessage(useHandStore.getState().ws, { type: 'ping', data: {} })
 }, 5000)
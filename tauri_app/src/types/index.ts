@@ -3,41 +3,389 @@
  */
 
 import { Vector3 } from 'three'
+import {
+  Action,
+  ActionBinding,
+  DisplayArea,
+  GestureEventType,
+  InputCommand,
+  JsonValue,
+  MouseButton,
+} from './protocol'
 
-// 手部关键点数据
-export interface HandLandmarks {
-  id: string              // 手部 ID (left/right)
-  handedness: string      // 左手/右手
-  landmarks: number[][]   // 21x3 关键点坐标 (归一化 0-1)
-  gesture: string         // 当前手势
-  gestureScore: number    // 手势置信度
-  state: string           // 状态 (idle/entering/held/exiting)
-}
-
-// 帧数据消息
-export interface FrameData {
-  frameId: number
-  hands: HandLandmarks[]
-  inferenceTimeMs: number
-  active: boolean
-}
-
-// 手势事件
+// 手势事件（界面使用的 camelCase 形式，原始消息见 protocol.ts）
 export interface GestureEvent {
-  eventType: 'enter' | 'hold' | 'exit' | 'slide'
+  eventType: GestureEventType
   gesture: string
   handId: string
   timestamp: number
   holdDuration: number
   confidence: number
-  meta?: Record<string, unknown>
+  meta?: { [key in string]?: JsonValue }
+}
+
+// 后端进程状态 (backend-status 事件 / get_backend_status 命令)
+export interface BackendStatus {
+  state: 'starting' | 'running' | 'ready' | 'crashed' | 'stopped'
+  pid: number | null
+  last_exit: { code: number | null; signal: number | null } | null
+  last_started_at: number | null   // Unix 毫秒
+  restart_count: number
+  stderr_tail: string[]
+}
+
+// 后端访问地址 (get_backend_endpoints 命令)
+export interface BackendEndpoints {
+  ws_url: string
+  stream_url: string
+  ws_port: number
+  mjpeg_port: number
+}
+
+// 宿主 WebSocket 连接状态 (get_bridge_status 命令 / bridge-status 事件)
+export interface BridgeStatus {
+  connected: boolean
+  url: string | null
+  server_version: string | null
+  protocol_error: string | null
+  active: boolean | null
+  camera_paused: boolean | null
+  reconnects: number
+}
+
+// 紧急停止结果 (emergency-stop 事件)
+export interface EmergencyStop {
+  killed_backend: boolean   // WebSocket 停用失败后结束了运行中的后端进程
+  error: string | null
+}
+
+// 全局快捷键 (get_shortcuts / set_shortcut / reset_shortcuts 命令)
+export type ShortcutAction = 'emergency_stop' | 'toggle_active' | 'toggle_camera' | 'toggle_window'
+
+export interface ShortcutInfo {
+  action: ShortcutAction
+  accelerator: string | null   // 如 "CmdOrCtrl+Shift+F9"，null 表示禁用
+  registered: boolean
+  error: string | null         // 注册失败原因（如被其他应用占用）
+}
+
+// 按前台应用切换手势配置的规则：class/title 不区分大小写，支持 * 与 ? 通配符
+export interface AppRule {
+  class?: string | null        // WM_CLASS 类名或实例名
+  title?: string | null        // 窗口标题
+  profile: string
+}
+
+// 绑定了 event 动作的手势触发 (gesture-action 事件)
+export interface GestureAction {
+  name: string                 // 绑定中的事件名
+  gesture: string
+  event_type: GestureEventType
+  hand_id: string
+}
+
+// 输入注入方式：auto 在 Linux 上依次尝试 xtest 与 uinput，backend 由后端注入，mock 只记录
+export type InputDriver = 'auto' | 'backend' | 'xtest' | 'uinput' | 'mock'
+
+// 输入注入状态 (get_input_status)
+export interface InputStatus {
+  driver: InputDriver
+  backend: 'xtest' | 'uinput' | 'mock' | null   // 实际使用的注入后端，由后端注入时为空
+  error: string | null         // 无法打开注入后端的原因
+}
+
+// mock 记录的输入事件 (take_recorded_input)
+export type InputEvent =
+  | { type: 'move_relative'; dx: number; dy: number }
+  | { type: 'move_absolute'; x: number; y: number }
+  | { type: 'button'; button: MouseButton; pressed: boolean }
+  | { type: 'scroll'; amount: number }
+  | { type: 'key'; key: string; pressed: boolean }
+  | { type: 'text'; text: string }
+
+// 宿主执行状态 (get_executor_status)
+export interface ExecutorStatus {
+  enabled: boolean             // 设置中是否启用
+  running: boolean             // 实际是否由宿主执行
+  active: boolean              // 宿主记录的激活状态
+  error: string | null         // 启用但无法由宿主执行的原因
+}
+
+// 宿主执行动作的审计记录 (action-audit 事件 / get_action_audit)
+export interface AuditEntry {
+  timestamp: number            // Unix 毫秒
+  gesture: string              // 滑动时为 slide_<方向>
+  event_type: GestureEventType
+  hand_id: string
+  action: Action | null        // 激活与停用控制时为空
+  reason: string
+  commands: InputCommand[]     // 注入的输入
+  merged: number               // 合并进这条记录的鼠标移动帧数
+  error: string | null
+}
+
+// 鼠标绝对定位的映射方式 (host.display.mode)
+export type DisplayMode = 'desktop' | 'under_cursor' | 'focused_window' | 'monitor'
+
+// 显示器（位置与尺寸为虚拟桌面上的物理像素）
+export interface Monitor extends DisplayArea {
+  name: string
+  scale_factor: number
+  primary: boolean
+}
+
+// 显示器状态 (get_display_status / display-changed 事件)
+export interface DisplayStatus {
+  monitors: Monitor[]
+  desktop: DisplayArea | null  // 所有显示器的外接矩形
+  target: {
+    monitor: string | null     // 映射到整个桌面时为空
+    area: DisplayArea
+  } | null
+  source: 'randr' | 'tauri' | null
+  error: string | null         // 无法枚举显示器或监听变化的原因
+}
+
+// 关键点滤波的调试记录 (filter-trace 事件 / get_filter_trace)
+export interface FilterTraceEntry {
+  timestamp: number            // Unix 毫秒
+  frame_id: number
+  hand_id: string
+  landmark: number             // 关键点序号
+  raw: [number, number, number]
+  // 依次经过各级滤波后的位置，最后一项为最终结果；未启用滤波时为空
+  stages: { name: FilterSmoother | 'deadzone'; value: [number, number, number] }[]
+}
+
+export type FilterSmoother = 'none' | 'one_euro' | 'kalman' | 'double_exponential'
+
+// 校准对应的摄像头与显示器
+export interface CalibrationKey {
+  camera: string               // 设备号、分辨率与是否镜像，如 "0 1280x720 mirror"
+  layout: string               // 所有显示器的名称、尺寸与位置
+  monitor: string | null       // 映射到整个桌面时为空
+}
+
+// 保存的校准
+export interface Calibration extends CalibrationKey {
+  matrix: number[]             // 画面 -> 映射区域的 3x3 单应矩阵（按行排列）
+  points: [number, number][]   // 记录的指尖位置，与目标点依次对应
+  created: number              // Unix 毫秒
+}
+
+// 鼠标映射校准状态 (get_calibration_status / start_calibration 等命令，calibration-changed 事件)
+export interface CalibrationStatus {
+  key: CalibrationKey | null   // 显示器尚未枚举时为空
+  calibrated: boolean          // 当前是否有生效的校准
+  session: {
+    // 依次指向的目标点：映射区域内的归一化位置与桌面上的像素位置
+    targets: { x: number; y: number; screen_x: number; screen_y: number }[]
+    captured: number           // 已记录的点数（即当前目标的序号）
+    dwell: number              // 当前目标的停留进度（0-1），满后自动记录
+    fingertip: [number, number] | null  // 最近一帧的指尖位置（画面归一化坐标）
+  } | null
+  error: string | null         // 最近一次校准失败的原因
+  saved: Calibration[]
+}
+
+// 实际生效的手势配置 (profile-changed 事件)
+export interface ActiveProfile {
+  profile: string
+  app: string | null           // 命中规则的应用，按 host.profile 生效时为空
+}
+
+// 前台窗口与生效配置 (get_app_profile_status)
+export interface FocusStatus {
+  window: { class: string; instance: string; title: string } | null
+  active: ActiveProfile | null
+  error: string | null         // 无法检测前台窗口的原因
+}
+
+// 持久化设置 (get_settings / update_settings / reset_settings 命令，settings-changed 事件)
+// 后端部分与 python_service/config/settings.py 一致，修改后在下次启动后端时生效
+export interface Settings {
+  version: number
+  gesture: {
+    finger_extended_angle: number
+    finger_bent_angle: number
+    pinch_distance_ratio: number
+    pinch_release_ratio: number
+    fist_tip_wrist_ratio: number
+    open_spread_ratio: number
+    slide_min_distance: number
+    slide_max_z_change: number
+  }
+  state_machine: {
+    p_high: number
+    p_hold: number
+    p_low: number
+    t_enter: number
+    t_exit: number
+    t_cooldown: number
+    ema_alpha: number
+    median_window: number
+    gesture_priority: Record<string, number>
+  }
+  action: {
+    mouse_sensitivity: number
+    mouse_smoothing: number
+  }
+  server: {
+    port: number
+    mjpeg_port: number
+    heartbeat_interval: number
+    connection_timeout: number
+  }
+  camera: {
+    device_id: number
+    width: number
+    height: number
+    fps: number
+    mirror: boolean
+  }
+  host: {
+    close_action: 'quit' | 'hide_to_tray'
+    restart_policy: {
+      enabled: boolean
+      max_retries: number
+      initial_delay_ms: number
+      multiplier: number
+      max_delay_ms: number
+      reset_after_ms: number
+    }
+    profile: string              // 没有匹配的应用规则时生效
+    app_profiles: {
+      enabled: boolean
+      rules: AppRule[]
+    }
+    // 手势配置 -> 动作绑定（set_profile_bindings / reset_profile_bindings 修改）
+    mappings: Record<string, ActionBinding[]>
+    input: {
+      driver: InputDriver
+    }
+    // 由宿主执行手势动作（需要可用的注入后端）
+    executor: {
+      enabled: boolean
+      pointer_mode: 'relative' | 'absolute'
+      mouse_speed: number        // 相对移动的速度增益
+      mouse_deadzone: number     // 相对移动的死区（归一化坐标）
+    }
+    // 鼠标绝对定位映射到哪个显示器
+    display: {
+      mode: DisplayMode
+      monitor: string | null     // mode 为 monitor 时的显示器名称
+    }
+    // 关键点滤波（宿主执行动作与可视化使用滤波后的关键点）
+    filter: {
+      enabled: boolean           // 启用后宿主执行动作时不再叠加固定平滑
+      smoother: FilterSmoother
+      one_euro: {
+        min_cutoff: number       // 静止时的截止频率 (Hz)
+        beta: number             // 截止频率随速度增加的系数
+        d_cutoff: number         // 速度估计的截止频率 (Hz)
+      }
+      kalman: {
+        process_noise: number    // 越大越跟手
+        measurement_noise: number // 越大越平滑
+      }
+      double_exponential: {
+        alpha: number            // 位置的平滑系数
+        gamma: number            // 趋势的平滑系数
+      }
+      deadzone: number           // 死区半径（归一化坐标），0 为关闭
+      trace: {
+        enabled: boolean
+        landmark: number         // 记录的关键点序号，默认为食指指尖
+      }
+    }
+    shortcuts: Partial<Record<ShortcutAction, string | null>>
+  }
+}
+
+// update_settings 的参数：只包含要修改的字段
+export type SettingsPatch = {
+  [K in Exclude<keyof Settings, 'version'>]?: Partial<Settings[K]>
+}
+
+// 设置导入导出格式
+export type SettingsFormat = 'toml' | 'json'
+
+// 导入或应用预设前预览的变化
+export interface SettingsChange {
+  path: string                 // 字段路径，如 gesture.pinch_distance_ratio
+  old: unknown
+  new: unknown
+}
+
+// 导入设置前的预览 (preview_settings_import)
+export interface ImportPreview {
+  changes: SettingsChange[]
+  // 导入后新增的启动程序、执行命令或打开链接的绑定，导入前需要用户确认
+  host_actions: ImportedAction[]
+}
+
+export interface ImportedAction {
+  path: string                 // 绑定位置，如 host.mappings.default.3
+  binding: ActionBinding
+}
+
+// 调校预设 (get_presets)
+export interface PresetInfo {
+  id: string
+  name: string
+  builtin: boolean
+}
+
+// 设置推送结果 (settings-applied 事件)
+export interface SettingsApplied {
+  request_id: number | null
+  applied: string[]            // 后端确认已生效的字段路径
+  restart_required: string[]   // 需要重启后端才能生效的字段路径
+  deferred: boolean            // 后端未连接，连接后自动同步
+  error: string | null
+}
+
+// 后端日志 (get_logs 命令 / backend-log 事件)
+export type LogLevel = 'debug' | 'info' | 'warn' | 'error'
+export type LogCategory = 'server' | 'stats' | 'action' | 'mjpeg' | 'other'
+
+export interface LogEntry {
+  seq: number
+  timestamp: number
+  level: LogLevel
+  category: LogCategory
+  source: 'stdout' | 'stderr'
+  message: string
+}
+
+export interface LogFilter {
+  level?: LogLevel
+  category?: LogCategory
+  contains?: string
+  since_seq?: number
 }
 
-// WebSocket 消息
-export interface WebSocketMessage {
-  type: string
+// 运行指标 (get_metrics 命令 / metrics 事件)
+export interface Metrics {
   timestamp: number
-  data: Record<string, unknown>
+  fps: number
+  avg_fps: number
+  frame_count: number
+  dropped_frames: number
+  clients: number
+  inference: {
+    samples: number
+    p50: number
+    p95: number
+    p99: number
+    max: number
+  } | null
+  process: {
+    pid: number
+    processes: number
+    cpu_percent: number
+    rss_bytes: number
+  } | null
 }
 
 // 手部可视化状态
@@ -79,7 +427,7 @@ export const HAND_CONNECTIONS: [number, number][] = [
 // 指尖索引
 export const FINGER_TIPS = [4, 8, 12, 16, 20]
 
-// 手势颜色映射
+// 手势颜色映射（托盘图标 src-tauri/src/tray/icon.rs 中有一份副本，修改时须同步）
 export const GESTURE_COLORS: Record<string, string> = {
   idle: '#666666',
   open: '#00ffff',
@@ -0,0 +1,113 @@
+// 此文件由 src-tauri/protocol 生成，请勿手动修改
+
+export const PROTOCOL_VERSION = "0.9.0"
+
+export type JsonValue = number | string | boolean | Array<JsonValue> | { [key in string]?: JsonValue } | null;
+
+export type Empty = Record<string, never>;
+
+export type CameraInfo = { width: number, height: number, };
+
+export type ConnectedConfig = { camera: CameraInfo, };
+
+export type Connected = { message: string, 
+/**
+ * 协议版本
+ */
+version: string, active: boolean, 
+/**
+ * 摄像头是否已暂停
+ */
+camera_paused: boolean, config: ConnectedConfig, };
+
+export type HandData = { id: string, 
+/**
+ * `Left` / `Right`
+ */
+handedness: string, 
+/**
+ * 21 个关键点，每个为归一化的 [x, y, z]
+ */
+landmarks: Array<[number, number, number]>, gesture: string, gesture_score: number, 
+/**
+ * idle / entering / held / exiting
+ */
+state: string, };
+
+export type FrameData = { frame_id: number, hands: Array<HandData>, inference_time_ms: number, active: boolean, };
+
+export type GestureEventType = "enter" | "hold" | "exit" | "slide";
+
+export type GestureEvent = { event_type: GestureEventType, gesture: string, hand_id: string, timestamp: number, 
+/**
+ * 保持时长（毫秒）
+ */
+hold_duration: number, confidence: number, 
+/**
+ * 附加信息（如滑动方向与距离）
+ */
+meta: { [key in string]?: JsonValue }, };
+
+export type ActiveState = { active: boolean, };
+
+export type CameraPaused = { paused: boolean, };
+
+export type Action = { "type": "mouse_move" } | { "type": "mouse_click" } | { "type": "mouse_drag" } | { "type": "mouse_scroll", amount: number, } | { "type": "volume_up" } | { "type": "volume_down" } | { "type": "volume_mute" } | { "type": "media_play_pause" } | { "type": "media_next" } | { "type": "media_prev" } | { "type": "switch_window" } | { "type": "screenshot" } | { "type": "slide_next" } | { "type": "slide_prev" } | { "type": "key_chord", keys: string, } | { "type": "text", text: string, } | { "type": "launch", program: string, args: Array<string>, } | { "type": "open_url", url: string, } | { "type": "shell", command: string, } | { "type": "event", name: string, };
+
+export type ActionBinding = { 
+/**
+ * 手势名；滑动时为方向（left / right / up / down）
+ */
+gesture: string, 
+/**
+ * 触发时机
+ */
+on: GestureEventType, action: Action, 
+/**
+ * 保持期间重复触发的间隔（毫秒），只用于 hold 上的一次性动作
+ */
+interval_ms: number | null, };
+
+export type ActionMap = { bindings: Array<ActionBinding>, };
+
+export type MouseButton = "left" | "right" | "middle";
+
+export type InputCommand = { "op": "move_relative", dx: number, dy: number, } | { "op": "move_absolute", x: number, y: number, } | { "op": "button", button: MouseButton, pressed: boolean, } | { "op": "scroll", amount: number, } | { "op": "keys", keys: string, } | { "op": "text", text: string, };
+
+export type InputDelegate = { 
+/**
+ * 为 true 时后端不再直接注入，改为发送 `input` 消息
+ */
+enabled: boolean, 
+/**
+ * 桌面尺寸（像素），用于换算相对移动；未知时为空
+ */
+screen_width: number | null, screen_height: number | null, };
+
+export type HostExecution = { enabled: boolean, };
+
+export type DisplayArea = { x: number, y: number, width: number, height: number, };
+
+export type PointerCalibration = { matrix: [number, number, number, number, number, number, number, number, number] | null, };
+
+export type ConfigUpdate = { request_id: number, changes: { [key in string]?: JsonValue }, };
+
+export type ConfigAck = { request_id: number, 
+/**
+ * 已生效的字段路径（如 `gesture.pinch_distance_ratio`）
+ */
+applied: Array<string>, 
+/**
+ * 校验失败的原因，失败时不会应用任何字段
+ */
+error: string | null, };
+
+export type ServerMessage = { "type": "connected", "data": Connected } | { "type": "frame_data", "data": FrameData } | { "type": "gesture_event", "data": GestureEvent } | { "type": "active_changed", "data": ActiveState } | { "type": "camera_paused", "data": CameraPaused } | { "type": "config_ack", "data": ConfigAck } | { "type": "input", "data": InputCommand } | { "type": "pong", "data": Empty };
+
+export type ClientMessage = { "type": "ping", "data": Empty } | { "type": "set_active", "data": ActiveState } | { "type": "set_camera_paused", "data": CameraPaused } | { "type": "set_action_map", "data": ActionMap } | { "type": "config_update", "data": ConfigUpdate } | { "type": "set_input_delegate", "data": InputDelegate } | { "type": "set_host_execution", "data": HostExecution } | { "type": "set_display_area", "data": DisplayArea } | { "type": "set_pointer_calibration", "data": PointerCalibration } | { "type": "shutdown", "data": Empty };
+
+export type Envelope<T> = { 
+/**
+ * 毫秒时间戳（`frame_data` 与 `gesture_event` 为摄像头启动以来的毫秒数）
+ */
+timestamp: number, } & T;